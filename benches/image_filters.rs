@@ -0,0 +1,87 @@
+//! Times the preprocessing filters on one thread against the full rayon pool.
+//!
+//! Run with `cargo bench --bench image_filters`.
+
+use ascii_converter::application::services::image_filters::{
+    adaptive_threshold, enhance_contrast, gaussian_blur,
+};
+use image::{DynamicImage, Rgb, RgbImage};
+use std::time::{Duration, Instant};
+
+const WIDTHS: [u32; 4] = [200, 800, 2000, 4000];
+const ITERATIONS: u32 = 5;
+
+fn main() {
+    let single = rayon::ThreadPoolBuilder::new()
+        .num_threads(1)
+        .build()
+        .expect("single-thread pool");
+    println!(
+        "{:<20} {:>6} {:>12} {:>12} {:>8}",
+        "filter",
+        "width",
+        "1 thread",
+        format!("{} threads", rayon::current_num_threads()),
+        "speedup"
+    );
+
+    for width in WIDTHS {
+        let height = width * 3 / 4;
+        let img = DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            Rgb([(x ^ y) as u8, (x * 7) as u8, (y * 13) as u8])
+        }));
+        let gray = img.to_luma8();
+
+        compare(&single, "enhance_contrast", width, || {
+            enhance_contrast(&img, 1.2).into_bytes()
+        });
+        compare(&single, "gaussian_blur", width, || {
+            gaussian_blur(&gray, 1.5).into_raw()
+        });
+        compare(&single, "adaptive_threshold", width, || {
+            adaptive_threshold(&gray, 70).into_raw()
+        });
+    }
+}
+
+/// Time a filter on one thread and on the global pool, checking both give the same bytes
+fn compare(
+    single: &rayon::ThreadPool,
+    name: &str,
+    width: u32,
+    filter: impl Fn() -> Vec<u8> + Sync,
+) {
+    let (serial_time, serial) = single.install(|| time(&filter));
+    let (parallel_time, parallel) = time(&filter);
+    assert!(
+        serial == parallel,
+        "{name} output differs between thread counts"
+    );
+
+    println!(
+        "{:<20} {:>6} {:>10.2}ms {:>10.2}ms {:>7.2}x",
+        name,
+        width,
+        millis(serial_time),
+        millis(parallel_time),
+        serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+    );
+}
+
+/// Best of a few runs, after a warm-up
+fn time(filter: &impl Fn() -> Vec<u8>) -> (Duration, Vec<u8>) {
+    let output = filter();
+    let best = (0..ITERATIONS)
+        .map(|_| {
+            let start = Instant::now();
+            std::hint::black_box(filter());
+            start.elapsed()
+        })
+        .min()
+        .unwrap_or_default();
+    (best, output)
+}
+
+fn millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
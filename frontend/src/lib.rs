@@ -1,31 +1,139 @@
-use std::ops::RangeInclusive;
+use std::{cell::Cell, ops::RangeInclusive, rc::Rc};
 
-use gloo_net::http::Request;
+use futures::{channel::mpsc, SinkExt, StreamExt};
+use gloo_net::{
+    http::Request,
+    websocket::{futures::WebSocket, Message},
+};
+use gloo_timers::callback::Timeout;
 use serde::Deserialize;
+use serde_json::json;
+use wasm_bindgen_futures::spawn_local;
 use web_sys::{FormData, HtmlInputElement};
 use yew::prelude::*;
 
-#[derive(Deserialize, Debug, Clone, PartialEq)]
-struct UploadResponse {
-    image_id: String,
-    format: String,
-    width: u32,
-    height: u32,
-    message: String,
-}
-
 #[derive(Deserialize, Debug, Clone, PartialEq)]
 struct ConvertResponse {
     ascii_art_id: String,
     ascii_art: String,
     width: u32,
     height: u32,
+    #[serde(default)]
+    animation: Option<Animation>,
+}
+
+#[derive(Deserialize, Debug, Clone, PartialEq)]
+struct Animation {
+    frames: Vec<String>,
+    delays_ms: Vec<u32>,
+}
+
+/// Message pushed over the live preview WebSocket
+#[derive(Deserialize, Debug)]
+#[serde(tag = "type", rename_all = "lowercase")]
+enum PreviewMessage {
+    Preview {
+        revision: u64,
+        ascii_art: String,
+        width: u32,
+        height: u32,
+        #[serde(default)]
+        animation: Option<Animation>,
+    },
+    Error {
+        error: String,
+    },
+}
+
+/// Live preview connection for an uploaded image; dropping it closes the socket
+struct PreviewSession {
+    sender: mpsc::UnboundedSender<String>,
+    /// Parameter messages sent so far, to recognize previews of outdated parameters
+    sent: Rc<Cell<u64>>,
+}
+
+impl PreviewSession {
+    /// Connect to the preview endpoint of the image and send the initial parameters
+    fn open(
+        image_id: &str,
+        params: serde_json::Value,
+        ascii_art: UseStateHandle<Option<String>>,
+        animation: UseStateHandle<Option<Animation>>,
+        frame_index: UseStateHandle<usize>,
+        status: UseStateHandle<Option<String>>,
+    ) -> Option<Self> {
+        let location = web_sys::window()?.location();
+        let scheme = if location.protocol().ok()? == "https:" {
+            "wss"
+        } else {
+            "ws"
+        };
+        let url = format!(
+            "{}://{}/api/preview/{}",
+            scheme,
+            location.host().ok()?,
+            image_id
+        );
+        let (mut write, mut read) = WebSocket::open(&url).ok()?.split();
+
+        let (sender, mut outgoing) = mpsc::unbounded::<String>();
+        spawn_local(async move {
+            while let Some(text) = outgoing.next().await {
+                if write.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            let _ = write.close().await;
+        });
+
+        let sent = Rc::new(Cell::new(0));
+        let latest = Rc::clone(&sent);
+        spawn_local(async move {
+            while let Some(Ok(message)) = read.next().await {
+                let Message::Text(text) = message else {
+                    continue;
+                };
+                match serde_json::from_str(&text) {
+                    Ok(PreviewMessage::Preview {
+                        revision,
+                        ascii_art: art,
+                        width,
+                        height,
+                        animation: frames,
+                    }) => {
+                        // A newer preview is on its way
+                        if revision < latest.get() {
+                            continue;
+                        }
+                        ascii_art.set(Some(art));
+                        frame_index.set(0);
+                        animation.set(frames);
+                        status.set(Some(format!("Live preview: ASCII {}x{}", width, height)));
+                    }
+                    Ok(PreviewMessage::Error { error }) => status.set(Some(error)),
+                    Err(_) => {}
+                }
+            }
+        });
+
+        let session = Self { sender, sent };
+        session.send(params);
+        Some(session)
+    }
+
+    /// Send changed parameters; the server answers with a new preview once they settle
+    fn send(&self, changes: serde_json::Value) {
+        self.sent.set(self.sent.get() + 1);
+        let _ = self.sender.unbounded_send(changes.to_string());
+    }
 }
 
 #[function_component(App)]
 fn app() -> Html {
     let file_input_ref = use_node_ref();
     let ascii_art = use_state(|| None::<String>);
+    let animation = use_state(|| None::<Animation>);
+    let frame_index = use_state(|| 0usize);
     let status = use_state(|| None::<String>);
     let is_loading = use_state(|| false);
 
@@ -33,10 +141,14 @@ fn app() -> Html {
     let detail = use_state(|| String::from("high"));
     let contrast = use_state(|| 1.2f32);
     let blur = use_state(|| 0.5f32);
+    let preview = use_mut_ref(|| None::<PreviewSession>);
 
     let on_convert = {
+        let preview = preview.clone();
         let file_input_ref = file_input_ref.clone();
         let ascii_art = ascii_art.clone();
+        let animation = animation.clone();
+        let frame_index = frame_index.clone();
         let status = status.clone();
         let is_loading = is_loading.clone();
         let width_state = width.clone();
@@ -57,63 +169,72 @@ fn app() -> Html {
 
             let status = status.clone();
             let ascii_art = ascii_art.clone();
+            let animation = animation.clone();
+            let frame_index = frame_index.clone();
             let is_loading = is_loading.clone();
             let width_value = *width_state;
             let detail_value = (*detail_state).clone();
             let contrast_value = *contrast_state;
             let blur_value = *blur_state;
+            let preview = preview.clone();
 
-            wasm_bindgen_futures::spawn_local(async move {
+            spawn_local(async move {
                 is_loading.set(true);
-                status.set(Some("Loading image...".into()));
 
                 let form = FormData::new().expect("formdata");
                 form.append_with_blob_and_filename("image", &file, &file.name())
                     .expect("append file");
+                for (name, value) in [
+                    ("width", width_value.to_string()),
+                    ("detail", detail_value.clone()),
+                    ("contrast", contrast_value.to_string()),
+                    ("blur", blur_value.to_string()),
+                ] {
+                    form.append_with_str(name, &value).expect("append field");
+                }
 
-                let upload_resp = Request::post("/api/upload")
+                status.set(Some("Converting to ASCII...".into()));
+                let convert_resp = Request::post("/api/convert")
                     .body(form)
                     .expect("failed to set body")
                     .send()
                     .await;
 
-                match upload_resp {
+                match convert_resp {
                     Ok(resp) => {
                         if !resp.ok() {
-                            status.set(Some(format!("Error loading: {}", resp.status())));
+                            status.set(Some(format!("Error converting: {}", resp.status())));
                             is_loading.set(false);
                             return;
                         }
-                        let data: UploadResponse = resp.json().await.unwrap();
-                        status.set(Some(format!("Loaded: {}x{}", data.width, data.height)));
-
-                        let url = format!(
-                            "/api/convert/{}?width={}&detail={}&contrast={}&blur={}",
-                            data.image_id, width_value, detail_value, contrast_value, blur_value
-                        );
-                        status.set(Some("Converting to ASCII...".into()));
-
-                        match Request::post(&url).send().await {
-                            Ok(resp2) => {
-                                if !resp2.ok() {
-                                    status
-                                        .set(Some(format!("Error converting: {}", resp2.status())));
-                                    is_loading.set(false);
-                                    return;
-                                }
-                                let conv: ConvertResponse = resp2.json().await.unwrap();
-                                ascii_art.set(Some(conv.ascii_art));
-                                status.set(Some(format!(
-                                    "Done! ASCII {}x{}",
-                                    conv.width, conv.height
-                                )));
-                                is_loading.set(false);
-                            }
-                            Err(e) => {
-                                status.set(Some(format!("Network error: {}", e)));
-                                is_loading.set(false);
-                            }
-                        }
+                        let image_id = resp.headers().get("x-image-id");
+                        let conv: ConvertResponse = resp.json().await.unwrap();
+                        ascii_art.set(Some(conv.ascii_art));
+                        let frame_count = conv.animation.as_ref().map_or(1, |a| a.frames.len());
+                        frame_index.set(0);
+                        animation.set(conv.animation);
+                        status.set(Some(format!(
+                            "Done! ASCII {}x{}, {} frame(s)",
+                            conv.width, conv.height, frame_count
+                        )));
+                        is_loading.set(false);
+
+                        // Keep the image open for live re-conversion as the sliders move
+                        *preview.borrow_mut() = image_id.and_then(|image_id| {
+                            PreviewSession::open(
+                                &image_id,
+                                json!({
+                                    "width": width_value,
+                                    "detail": detail_value,
+                                    "contrast": contrast_value,
+                                    "blur": blur_value,
+                                }),
+                                ascii_art,
+                                animation,
+                                frame_index,
+                                status,
+                            )
+                        });
                     }
                     Err(e) => {
                         status.set(Some(format!("Network error: {}", e)));
@@ -124,13 +245,46 @@ fn app() -> Html {
         })
     };
 
+    // Advance animated results frame by frame, honoring each frame's delay
+    {
+        let frame_index = frame_index.clone();
+        use_effect_with(
+            ((*animation).clone(), *frame_index),
+            move |(animation, index)| {
+                let timeout = animation.as_ref().and_then(|animation| {
+                    let delay = *animation.delays_ms.get(*index)?;
+                    let next = (*index + 1) % animation.frames.len();
+                    Some(Timeout::new(delay, move || frame_index.set(next)))
+                });
+                move || drop(timeout)
+            },
+        );
+    }
+
+    let displayed_art = match &*animation {
+        Some(animation) => animation.frames.get(*frame_index).cloned(),
+        None => (*ascii_art).clone(),
+    };
+
+    // Forward a parameter change to the live preview, if one is open
+    let send_preview = {
+        let preview = preview.clone();
+        move |changes: serde_json::Value| {
+            if let Some(session) = &*preview.borrow() {
+                session.send(changes);
+            }
+        }
+    };
+
     let on_width_change = {
         let width = width.clone();
+        let send_preview = send_preview.clone();
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
             if let Ok(v) = input.value().parse::<u32>() {
                 if RangeInclusive::new(10, 500).contains(&v) {
                     width.set(v);
+                    send_preview(json!({ "width": v }));
                 }
             }
         })
@@ -138,19 +292,23 @@ fn app() -> Html {
 
     let on_detail_change = {
         let detail = detail.clone();
+        let send_preview = send_preview.clone();
         Callback::from(move |e: Event| {
             let input: HtmlInputElement = e.target_unchecked_into();
+            send_preview(json!({ "detail": input.value() }));
             detail.set(input.value());
         })
     };
 
     let on_contrast_change = {
         let contrast = contrast.clone();
+        let send_preview = send_preview.clone();
         Callback::from(move |e: InputEvent| {
             let input: HtmlInputElement = e.target_unchecked_into();
             if let Ok(v) = input.value().parse::<f32>() {
                 if RangeInclusive::new(0.1, 3.0).contains(&v) {
                     contrast.set(v);
+                    send_preview(json!({ "contrast": v }));
                 }
             }
         })
@@ -163,6 +321,7 @@ fn app() -> Html {
             if let Ok(v) = input.value().parse::<f32>() {
                 if RangeInclusive::new(0.0, 5.0).contains(&v) {
                     blur.set(v);
+                    send_preview(json!({ "blur": v }));
                 }
             }
         })
@@ -350,7 +509,7 @@ fn app() -> Html {
                     </div>
                 }
 
-                if let Some(a) = &*ascii_art {
+                if let Some(a) = &displayed_art {
                     <div style="
                         margin-top: 20px;
                         padding: 20px;
@@ -376,7 +535,7 @@ fn app() -> Html {
                 }
             </div>
             </div>
-        
+
         <footer style="
             width: 100%;
             max-width: 1000px;
@@ -405,9 +564,9 @@ fn app() -> Html {
                 <span>{"and"}</span>
                 <span style="font-size: 18px;">{"🦀"}</span>
                 <span>{"by"}</span>
-                <a 
-                    href="https://github.com/0xataru" 
-                    target="_blank" 
+                <a
+                    href="https://github.com/0xataru"
+                    target="_blank"
                     style="
                         color: #4ECDC4;
                         text-decoration: none;
@@ -431,4 +590,3 @@ use wasm_bindgen::prelude::*;
 pub fn start() {
     yew::Renderer::<App>::new().render();
 }
-
@@ -0,0 +1,290 @@
+use crate::domain::value_objects::ColorMode;
+use std::fmt::Write;
+
+/// Escape sequence resetting all terminal attributes
+pub const ANSI_RESET: &str = "\x1b[0m";
+
+/// Channel levels of the xterm 6x6x6 color cube
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/// Quantize an RGB color to the nearest xterm-256 palette index
+pub fn rgb_to_ansi256(rgb: [u8; 3]) -> u8 {
+    // Nearest color in the 6x6x6 cube (indices 16-231)
+    let cube_index = |v: u8| -> usize {
+        CUBE_LEVELS
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, &level)| (level as i32 - v as i32).abs())
+            .map(|(i, _)| i)
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(rgb[0]), cube_index(rgb[1]), cube_index(rgb[2]));
+    let cube_rgb = [CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]];
+    let cube_code = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    // Nearest shade on the grayscale ramp (indices 232-255, 8..=238 in steps of 10)
+    let average = (rgb[0] as u32 + rgb[1] as u32 + rgb[2] as u32) / 3;
+    let gray_step = ((average.saturating_sub(3)) / 10).min(23) as u8;
+    let gray_level = 8 + gray_step * 10;
+    let gray_code = 232 + gray_step;
+
+    if color_distance(rgb, [gray_level; 3]) < color_distance(rgb, cube_rgb) {
+        gray_code
+    } else {
+        cube_code
+    }
+}
+
+/// Squared euclidean distance between two RGB colors
+pub fn color_distance(a: [u8; 3], b: [u8; 3]) -> u32 {
+    a.iter()
+        .zip(b.iter())
+        .map(|(&x, &y)| {
+            let d = x as i32 - y as i32;
+            (d * d) as u32
+        })
+        .sum()
+}
+
+/// Append the foreground color escape sequence for the given mode
+pub fn push_foreground(out: &mut String, mode: ColorMode, rgb: [u8; 3]) {
+    match mode {
+        ColorMode::None => {}
+        ColorMode::Ansi256 => {
+            let _ = write!(out, "\x1b[38;5;{}m", rgb_to_ansi256(rgb));
+        }
+        ColorMode::TrueColor => {
+            let _ = write!(out, "\x1b[38;2;{};{};{}m", rgb[0], rgb[1], rgb[2]);
+        }
+    }
+}
+
+/// Append the background color escape sequence for the given mode
+pub fn push_background(out: &mut String, mode: ColorMode, rgb: [u8; 3]) {
+    match mode {
+        ColorMode::None => {}
+        ColorMode::Ansi256 => {
+            let _ = write!(out, "\x1b[48;5;{}m", rgb_to_ansi256(rgb));
+        }
+        ColorMode::TrueColor => {
+            let _ = write!(out, "\x1b[48;2;{};{};{}m", rgb[0], rgb[1], rgb[2]);
+        }
+    }
+}
+
+/// Reduce a color to what the mode can actually display, so that runs of
+/// colors that render identically can be merged into one escape sequence
+pub fn effective_color(mode: ColorMode, rgb: [u8; 3]) -> [u8; 3] {
+    match mode {
+        ColorMode::Ansi256 => ansi256_to_rgb(rgb_to_ansi256(rgb)),
+        _ => rgb,
+    }
+}
+
+/// Get the RGB value of an xterm-256 palette index
+pub fn ansi256_to_rgb(code: u8) -> [u8; 3] {
+    match code {
+        0..=15 => {
+            const BASE: [[u8; 3]; 16] = [
+                [0, 0, 0],
+                [128, 0, 0],
+                [0, 128, 0],
+                [128, 128, 0],
+                [0, 0, 128],
+                [128, 0, 128],
+                [0, 128, 128],
+                [192, 192, 192],
+                [128, 128, 128],
+                [255, 0, 0],
+                [0, 255, 0],
+                [255, 255, 0],
+                [0, 0, 255],
+                [255, 0, 255],
+                [0, 255, 255],
+                [255, 255, 255],
+            ];
+            BASE[code as usize]
+        }
+        16..=231 => {
+            let index = (code - 16) as usize;
+            [
+                CUBE_LEVELS[index / 36],
+                CUBE_LEVELS[(index / 6) % 6],
+                CUBE_LEVELS[index % 6],
+            ]
+        }
+        _ => [8 + (code - 232) * 10; 3],
+    }
+}
+
+/// Writer for a single line of colored glyphs that only emits an escape
+/// sequence when the color differs from the previous glyph
+pub struct ColoredLine<'a> {
+    out: &'a mut String,
+    mode: ColorMode,
+    current: Option<[u8; 3]>,
+    current_background: Option<[u8; 3]>,
+}
+
+impl<'a> ColoredLine<'a> {
+    /// Start a new colored line appending to `out`
+    pub fn new(out: &'a mut String, mode: ColorMode) -> Self {
+        Self {
+            out,
+            mode,
+            current: None,
+            current_background: None,
+        }
+    }
+
+    /// Append a glyph in the given foreground color
+    pub fn push(&mut self, glyph: char, rgb: [u8; 3]) {
+        if self.mode.is_colored() {
+            let color = effective_color(self.mode, rgb);
+            if self.current != Some(color) {
+                push_foreground(self.out, self.mode, color);
+                self.current = Some(color);
+            }
+        }
+        self.out.push(glyph);
+    }
+
+    /// Append a glyph with both foreground and background colors
+    pub fn push_with_background(&mut self, glyph: char, rgb: [u8; 3], background: [u8; 3]) {
+        if self.mode.is_colored() {
+            let color = effective_color(self.mode, background);
+            if self.current_background != Some(color) {
+                push_background(self.out, self.mode, color);
+                self.current_background = Some(color);
+            }
+        }
+        self.push(glyph, rgb);
+    }
+
+    /// Terminate the line, resetting attributes if any color was emitted
+    pub fn finish(self) {
+        if self.current.is_some() || self.current_background.is_some() {
+            self.out.push_str(ANSI_RESET);
+        }
+    }
+}
+
+/// Glyph of colored text together with the colors in effect where it was drawn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StyledCell {
+    pub glyph: char,
+    pub foreground: Option<[u8; 3]>,
+    pub background: Option<[u8; 3]>,
+}
+
+/// Split text containing ANSI color sequences into rows of styled cells. Colors carry
+/// over to following lines as they would in a terminal; other escape sequences are
+/// dropped.
+pub fn parse_styled_lines(content: &str) -> Vec<Vec<StyledCell>> {
+    let mut lines = vec![Vec::new()];
+    let (mut foreground, mut background) = (None, None);
+    let mut chars = content.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\x1b' => {
+                if chars.peek() != Some(&'[') {
+                    continue;
+                }
+                chars.next();
+
+                // Control sequence: parameter bytes up to a final byte in @..~
+                let mut params = String::new();
+                let mut command = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        command = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if command == Some('m') {
+                    apply_sgr(&params, &mut foreground, &mut background);
+                }
+            }
+            '\n' => lines.push(Vec::new()),
+            '\r' => {}
+            glyph => {
+                if let Some(line) = lines.last_mut() {
+                    line.push(StyledCell {
+                        glyph,
+                        foreground,
+                        background,
+                    });
+                }
+            }
+        }
+    }
+
+    if content.ends_with('\n') {
+        lines.pop();
+    }
+    lines
+}
+
+/// Remove ANSI escape sequences, keeping only the glyphs and line breaks
+pub fn strip_ansi(content: &str) -> String {
+    let lines: Vec<String> = parse_styled_lines(content)
+        .iter()
+        .map(|line| line.iter().map(|cell| cell.glyph).collect())
+        .collect();
+    let mut text = lines.join("\n");
+    if content.ends_with('\n') {
+        text.push('\n');
+    }
+    text
+}
+
+/// Update the current colors from the parameters of a Select Graphic Rendition sequence
+fn apply_sgr(params: &str, foreground: &mut Option<[u8; 3]>, background: &mut Option<[u8; 3]>) {
+    let codes: Vec<u32> = params
+        .split(';')
+        .map(|code| code.parse().unwrap_or(0))
+        .collect();
+    let palette = |code: u32| Some(ansi256_to_rgb(code.min(255) as u8));
+
+    let mut i = 0;
+    while i < codes.len() {
+        match codes[i] {
+            0 => {
+                *foreground = None;
+                *background = None;
+            }
+            code @ 30..=37 => *foreground = palette(code - 30),
+            code @ 90..=97 => *foreground = palette(code - 90 + 8),
+            code @ 40..=47 => *background = palette(code - 40),
+            code @ 100..=107 => *background = palette(code - 100 + 8),
+            39 => *foreground = None,
+            49 => *background = None,
+            code @ (38 | 48) => {
+                let target = if code == 38 {
+                    &mut *foreground
+                } else {
+                    &mut *background
+                };
+                match codes.get(i + 1) {
+                    Some(5) => {
+                        if let Some(&index) = codes.get(i + 2) {
+                            *target = palette(index);
+                        }
+                        i += 2;
+                    }
+                    Some(2) => {
+                        if let Some(rgb) = codes.get(i + 2..i + 5) {
+                            *target = Some([rgb[0], rgb[1], rgb[2]].map(|v| v.min(255) as u8));
+                        }
+                        i += 4;
+                    }
+                    _ => {}
+                }
+            }
+            _ => {}
+        }
+        i += 1;
+    }
+}
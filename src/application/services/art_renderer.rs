@@ -0,0 +1,354 @@
+use crate::{
+    application::services::{
+        ansi::{parse_styled_lines, StyledCell},
+        bitmap_font::BitmapFont,
+    },
+    domain::value_objects::ExportOptions,
+};
+use image::{Rgb, RgbImage};
+use std::{fmt::Write, io::Cursor};
+use thiserror::Error;
+
+/// Upper bound on the pixels of a rendered PNG, about 120MB of RGB data
+const MAX_RENDER_PIXELS: u64 = 40_000_000;
+
+/// Baseline of a text row as a fraction of the row height, matching the bundled font
+const SVG_BASELINE: f32 = 0.8;
+
+#[derive(Error, Debug)]
+pub enum RenderError {
+    #[error("Rendered image would be too large ({width}x{height} pixels)")]
+    TooLarge { width: u64, height: u64 },
+    #[error("Failed to encode image: {0}")]
+    Encode(#[from] image::ImageError),
+}
+
+/// Draws ASCII art, including ANSI-colored art, as PNG or SVG images or as HTML
+pub struct ArtRenderer {
+    font: &'static BitmapFont,
+}
+
+/// Styled rows of the art and the pixel geometry derived from the options
+struct Layout {
+    lines: Vec<Vec<StyledCell>>,
+    cell_width: u32,
+    cell_height: u32,
+    width: u64,
+    height: u64,
+}
+
+impl ArtRenderer {
+    /// Create a renderer using the bundled bitmap font
+    pub fn new() -> Self {
+        Self {
+            font: BitmapFont::builtin(),
+        }
+    }
+
+    /// Rasterize the art with the bitmap font, scaled to the requested font size
+    pub fn render_png(
+        &self,
+        content: &str,
+        options: &ExportOptions,
+    ) -> Result<Vec<u8>, RenderError> {
+        let layout = self.layout(content, options);
+        if layout.width * layout.height > MAX_RENDER_PIXELS {
+            return Err(RenderError::TooLarge {
+                width: layout.width,
+                height: layout.height,
+            });
+        }
+
+        let (font_width, font_height) = (self.font.cell_width(), self.font.cell_height());
+        let mut image = RgbImage::from_pixel(
+            layout.width as u32,
+            layout.height as u32,
+            Rgb(options.background),
+        );
+
+        for (row, line) in layout.lines.iter().enumerate() {
+            for (column, cell) in line.iter().enumerate() {
+                let left = options.padding + column as u32 * layout.cell_width;
+                let top = options.padding + row as u32 * layout.cell_height;
+                let (foreground, background) = cell_colors(cell, options);
+
+                let mask = self.font.glyph(cell.glyph);
+                for y in 0..layout.cell_height {
+                    for x in 0..layout.cell_width {
+                        // Nearest-neighbor sample of the glyph at this size
+                        let source = (y * font_height / layout.cell_height) * font_width
+                            + x * font_width / layout.cell_width;
+                        let ink = mask.is_some_and(|mask| mask[source as usize]);
+                        if ink {
+                            image.put_pixel(left + x, top + y, Rgb(foreground));
+                        } else if let Some(background) = background {
+                            image.put_pixel(left + x, top + y, Rgb(background));
+                        }
+                    }
+                }
+            }
+        }
+
+        let mut png = Vec::new();
+        image.write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)?;
+        Ok(png)
+    }
+
+    /// Lay the art out as SVG, one `<text>` element per row with a `<tspan>` per run of
+    /// identically colored cells. Rows are stretched to the bitmap font's cell width so
+    /// the result lines up whatever monospace font the viewer substitutes.
+    pub fn render_svg(&self, content: &str, options: &ExportOptions) -> String {
+        let layout = self.layout(content, options);
+        let mut svg = String::new();
+
+        let _ = writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = layout.width,
+            h = layout.height
+        );
+        let _ = writeln!(
+            svg,
+            r#"<rect width="100%" height="100%" fill="{}"/>"#,
+            hex(options.background)
+        );
+
+        // Cell backgrounds, merged into one rectangle per run
+        for (row, line) in layout.lines.iter().enumerate() {
+            let top = options.padding + row as u32 * layout.cell_height;
+            for (start, length, background) in runs(line, |cell| cell_colors(cell, options).1) {
+                if let Some(background) = background {
+                    let _ = writeln!(
+                        svg,
+                        r#"<rect x="{}" y="{}" width="{}" height="{}" fill="{}"/>"#,
+                        options.padding + start as u32 * layout.cell_width,
+                        top,
+                        length as u32 * layout.cell_width,
+                        layout.cell_height,
+                        hex(background)
+                    );
+                }
+            }
+        }
+
+        let _ = writeln!(
+            svg,
+            r#"<g font-family="monospace" font-size="{}" fill="{}" xml:space="preserve">"#,
+            options.font_size,
+            hex(options.foreground)
+        );
+        for (row, line) in layout.lines.iter().enumerate() {
+            if line.is_empty() {
+                continue;
+            }
+            let baseline =
+                options.padding as f32 + (row as f32 + SVG_BASELINE) * layout.cell_height as f32;
+            let _ = write!(
+                svg,
+                r#"<text x="{}" y="{}" textLength="{}" lengthAdjust="spacingAndGlyphs">"#,
+                options.padding,
+                baseline,
+                line.len() as u32 * layout.cell_width
+            );
+            for (start, length, foreground) in runs(line, |cell| cell_colors(cell, options).0) {
+                let text: String = line[start..start + length]
+                    .iter()
+                    .map(|cell| cell.glyph)
+                    .collect();
+                if foreground == options.foreground {
+                    svg.push_str(&escape_xml(&text));
+                } else {
+                    let _ = write!(
+                        svg,
+                        r#"<tspan fill="{}">{}</tspan>"#,
+                        hex(foreground),
+                        escape_xml(&text)
+                    );
+                }
+            }
+            svg.push_str("</text>\n");
+        }
+        svg.push_str("</g>\n</svg>\n");
+        svg
+    }
+
+    /// Lay the art out as an embeddable `<pre>` element with inline CSS. Runs of cells
+    /// with the same colors share one `<span>`, and text is escaped so that glyphs like
+    /// `<` and `&` from the ramps display literally.
+    pub fn render_html_snippet(&self, content: &str, options: &ExportOptions) -> String {
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            r#"<pre class="ascii-art" style="margin:0;padding:{}px;color:{};background:{};font-family:monospace;font-size:{}px;line-height:1">"#,
+            options.padding,
+            hex(options.foreground),
+            hex(options.background),
+            options.font_size
+        );
+
+        let lines = parse_styled_lines(content);
+        for (row, line) in lines.iter().enumerate() {
+            if row > 0 {
+                html.push('\n');
+            }
+            for (start, length, (foreground, background)) in
+                runs(line, |cell| cell_colors(cell, options))
+            {
+                let text: String = line[start..start + length]
+                    .iter()
+                    .map(|cell| cell.glyph)
+                    .collect();
+
+                let mut style = String::new();
+                if foreground != options.foreground {
+                    let _ = write!(style, "color:{}", hex(foreground));
+                }
+                if let Some(background) = background {
+                    if !style.is_empty() {
+                        style.push(';');
+                    }
+                    let _ = write!(style, "background:{}", hex(background));
+                }
+
+                if style.is_empty() {
+                    html.push_str(&escape_xml(&text));
+                } else {
+                    let _ = write!(
+                        html,
+                        r#"<span style="{}">{}</span>"#,
+                        style,
+                        escape_xml(&text)
+                    );
+                }
+            }
+        }
+        html.push_str("</pre>");
+        html
+    }
+
+    /// Wrap the `<pre>` snippet in a self-contained HTML document
+    pub fn render_html(&self, content: &str, options: &ExportOptions) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>ASCII art</title>\n\
+             <style>body{{margin:0;background:{}}}</style>\n</head>\n<body>\n{}\n</body>\n</html>\n",
+            hex(options.background),
+            self.render_html_snippet(content, options)
+        )
+    }
+
+    fn layout(&self, content: &str, options: &ExportOptions) -> Layout {
+        let lines = parse_styled_lines(content);
+        let cell_height = options.font_size.max(1);
+        let cell_width = ((cell_height * self.font.cell_width()) as f32
+            / self.font.cell_height() as f32)
+            .round()
+            .max(1.0) as u32;
+
+        let columns = lines.iter().map(Vec::len).max().unwrap_or(0) as u64;
+        let padding = 2 * options.padding as u64;
+        Layout {
+            width: columns * cell_width as u64 + padding,
+            height: lines.len() as u64 * cell_height as u64 + padding,
+            lines,
+            cell_width,
+            cell_height,
+        }
+    }
+}
+
+impl Default for ArtRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Foreground and optional background color of a cell under the export options
+fn cell_colors(cell: &StyledCell, options: &ExportOptions) -> ([u8; 3], Option<[u8; 3]>) {
+    if options.cell_colors {
+        (
+            cell.foreground.unwrap_or(options.foreground),
+            cell.background,
+        )
+    } else {
+        (options.foreground, None)
+    }
+}
+
+/// Split a row into (start, length, key) runs of consecutive cells with the same key
+fn runs<K: PartialEq>(
+    line: &[StyledCell],
+    key: impl Fn(&StyledCell) -> K,
+) -> Vec<(usize, usize, K)> {
+    let mut runs: Vec<(usize, usize, K)> = Vec::new();
+    for (i, cell) in line.iter().enumerate() {
+        let cell_key = key(cell);
+        match runs.last_mut() {
+            Some((_, length, last)) if *last == cell_key => *length += 1,
+            _ => runs.push((i, 1, cell_key)),
+        }
+    }
+    runs
+}
+
+fn hex(rgb: [u8; 3]) -> String {
+    format!("#{:02x}{:02x}{:02x}", rgb[0], rgb[1], rgb[2])
+}
+
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn png_and_svg_keep_cell_colors() {
+        let renderer = ArtRenderer::new();
+        let options = ExportOptions {
+            padding: 2,
+            ..ExportOptions::default()
+        };
+        let content = "\x1b[38;2;255;0;0m#\x1b[0m<\n";
+
+        let png = image::load_from_memory(&renderer.render_png(content, &options).unwrap())
+            .unwrap()
+            .to_rgb8();
+        assert_eq!(png.dimensions(), (2 * 8 + 4, 13 + 4));
+        assert!(png.pixels().any(|p| p.0 == [255, 0, 0]));
+        assert!(png.pixels().any(|p| p.0 == [255, 255, 255]));
+
+        let svg = renderer.render_svg(content, &options);
+        assert!(svg.contains(r##"<tspan fill="#ff0000">#</tspan>&lt;</text>"##));
+    }
+
+    #[test]
+    fn html_escapes_ramp_glyphs_and_merges_color_runs() {
+        let renderer = ArtRenderer::new();
+        let options = ExportOptions::default();
+        let content = "\x1b[38;2;255;0;0m<<\x1b[38;2;0;0;255m&\x1b[0m>\n";
+
+        let html = renderer.render_html_snippet(content, &options);
+        assert!(html.ends_with(
+            r#"<span style="color:#ff0000">&lt;&lt;</span><span style="color:#0000ff">&amp;</span>&gt;</pre>"#
+        ));
+
+        let plain = ExportOptions {
+            cell_colors: false,
+            ..options
+        };
+        assert!(renderer
+            .render_html(content, &plain)
+            .contains(">&lt;&lt;&amp;&gt;</pre>"));
+    }
+}
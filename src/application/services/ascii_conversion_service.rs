@@ -1,5 +1,25 @@
-use crate::domain::{entities::ImageData, value_objects::ConversionConfig};
-use image::{DynamicImage, GenericImageView, GrayImage, Luma, Rgb, RgbImage};
+use crate::{
+    application::services::{
+        ansi::{color_distance, ColoredLine},
+        bitmap_font::BitmapFont,
+        dithering,
+        glyph_atlas::GlyphAtlas,
+        image_decoding, image_filters,
+    },
+    domain::{
+        entities::{AsciiFrame, ImageData},
+        value_objects::{ConversionConfig, Dithering, EdgeFill, ImageLimits, RenderMode},
+    },
+};
+use image::{
+    codecs::{gif::GifDecoder, webp::WebPDecoder},
+    AnimationDecoder, DynamicImage, Frame, GenericImageView, GrayImage, ImageDecoder, Luma, Rgb,
+    RgbImage,
+};
+use std::{
+    cell::{Cell, RefCell},
+    io::Cursor,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -8,222 +28,876 @@ pub enum ConversionError {
     ImageDecodeError(#[from] image::ImageError),
     #[error("Invalid image data")]
     InvalidImageData,
+    #[error("Conversion cancelled")]
+    Cancelled,
+    #[error("Output would sample {width}x{height} pixels, beyond the limits ({limits})")]
+    OutputTooLarge {
+        width: u64,
+        height: u64,
+        limits: ImageLimits,
+    },
+}
+
+/// First codepoint of the Unicode Braille Patterns block (blank pattern)
+const BRAILLE_BASE: u32 = 0x2800;
+
+/// Dot offsets within a 2x4 Braille cell and the bit each one sets
+const BRAILLE_DOTS: [(u32, u32, u32); 8] = [
+    (0, 0, 0x01),
+    (0, 1, 0x02),
+    (0, 2, 0x04),
+    (1, 0, 0x08),
+    (1, 1, 0x10),
+    (1, 2, 0x20),
+    (0, 3, 0x40),
+    (1, 3, 0x80),
+];
+
+/// Half block glyphs indexed by a mask of filled pixels (bit 0 top, bit 1 bottom)
+const HALF_BLOCK_GLYPHS: [char; 4] = [' ', '▀', '▄', '█'];
+
+/// Quadrant glyphs indexed by a mask of filled pixels
+/// (bit 0 top-left, bit 1 top-right, bit 2 bottom-left, bit 3 bottom-right)
+const QUADRANT_GLYPHS: [char; 16] = [
+    ' ', '▘', '▝', '▀', '▖', '▌', '▞', '▛', '▗', '▚', '▐', '▜', '▄', '▙', '▟', '█',
+];
+
+/// Samples per character cell used when matching glyph shapes
+const STRUCTURAL_CELL_WIDTH: u32 = 6;
+const STRUCTURAL_CELL_HEIGHT: u32 = 10;
+
+/// Pixels per character cell used for edge detection
+const EDGE_CELL_WIDTH: u32 = 2;
+const EDGE_CELL_HEIGHT: u32 = 4;
+
+/// Largest possible Sobel gradient magnitude for 8-bit intensities
+const MAX_SOBEL_MAGNITUDE: f32 = 1442.5;
+
+/// Upper bound on decoded animation frames, later frames are dropped
+const MAX_ANIMATION_FRAMES: usize = 500;
+
+/// Frame delay used when an animation asks for (near) zero delay, as browsers do
+const DEFAULT_FRAME_DELAY_MS: u32 = 100;
+
+/// Rendered ASCII art together with its size in character cells
+#[derive(Debug, Clone)]
+pub struct AsciiRendering {
+    pub content: String,
+    pub columns: u32,
+    pub rows: u32,
+    /// Every frame of an animated source, empty for still images
+    pub frames: Vec<AsciiFrame>,
+}
+
+/// Asks whether a conversion should go on; checked between its stages and rows so that
+/// a cancelled conversion stops soon, even for a single large image
+#[derive(Clone, Copy)]
+struct Proceed<'a>(&'a dyn Fn() -> bool);
+
+impl Proceed<'_> {
+    fn check(self) -> Result<(), ConversionError> {
+        if (self.0)() {
+            Ok(())
+        } else {
+            Err(ConversionError::Cancelled)
+        }
+    }
 }
 
 /// Service for converting images to ASCII art
-pub struct AsciiConversionService;
+pub struct AsciiConversionService {
+    limits: ImageLimits,
+}
 
 impl AsciiConversionService {
     /// Create a new ASCII conversion service
     pub fn new() -> Self {
-        Self
+        Self::with_limits(ImageLimits::default())
     }
 
-    /// Convert image data to ASCII art
-    pub async fn convert_to_ascii(
+    /// Create a conversion service refusing to decode images beyond `limits`
+    pub fn with_limits(limits: ImageLimits) -> Self {
+        Self { limits }
+    }
+
+    /// Convert image data to ASCII art, blocking the calling thread. `on_progress` is
+    /// called with the number of finished and total frames (1 for still images), again
+    /// between the stages and rows of each frame, and cancels the conversion by
+    /// returning false.
+    pub fn convert_with_progress(
         &self,
         image_data: &ImageData,
         config: &ConversionConfig,
-    ) -> Result<String, ConversionError> {
-        // Load image from bytes
-        let img = image::load_from_memory(&image_data.data)?;
+        on_progress: impl FnMut(usize, usize) -> bool,
+    ) -> Result<AsciiRendering, ConversionError> {
+        let on_progress = RefCell::new(on_progress);
+        let (done, total) = (Cell::new(0), Cell::new(1));
+        let keep_going = || (on_progress.borrow_mut())(done.get(), total.get());
+        let proceed = Proceed(&keep_going);
+
+        proceed.check()?;
+        let frames = self.decode_animation(&image_data.data)?;
+        if frames.is_empty() {
+            proceed.check()?;
+
+            // Load image from bytes
+            let img = image_decoding::decode(&image_data.data, &self.limits)?;
+
+            // Convert to ASCII using the improved algorithm
+            let rendering = self.convert_image_to_ascii(&img, config, proceed)?;
+            (on_progress.borrow_mut())(1, 1);
+            return Ok(rendering);
+        }
+
+        // Convert every frame with the same configuration; the first one doubles as the still
+        total.set(frames.len());
+        let mut ascii_frames = Vec::with_capacity(total.get());
+        let mut first = None;
+        for (index, (img, delay_ms)) in frames.into_iter().enumerate() {
+            done.set(index);
+            proceed.check()?;
+            let rendering = self.convert_image_to_ascii(&img, config, proceed)?;
+            ascii_frames.push(AsciiFrame {
+                content: rendering.content.clone(),
+                delay_ms,
+            });
+            first.get_or_insert(rendering);
+        }
+        (on_progress.borrow_mut())(total.get(), total.get());
+
+        let mut rendering = first.ok_or(ConversionError::InvalidImageData)?;
+        rendering.frames = ascii_frames;
+        Ok(rendering)
+    }
+
+    /// Decode all frames of an animated GIF or WebP with their delays in milliseconds.
+    /// Returns no frames for still images and formats without animation support. Frames
+    /// beyond the pixel limit, which animations share, are dropped.
+    fn decode_animation(&self, data: &[u8]) -> Result<Vec<(DynamicImage, u32)>, ConversionError> {
+        let limits = image_decoding::decoder_limits(&self.limits);
+        let (frames, (width, height)) = match image::guess_format(data)? {
+            image::ImageFormat::Gif => {
+                let mut decoder = GifDecoder::new(Cursor::new(data))?;
+                decoder.set_limits(limits)?;
+                let dimensions = decoder.dimensions();
+                (decoder.into_frames(), dimensions)
+            }
+            image::ImageFormat::WebP => {
+                let mut decoder = WebPDecoder::new(Cursor::new(data))?;
+                if !decoder.has_animation() {
+                    return Ok(Vec::new());
+                }
+                decoder.set_limits(limits)?;
+                let dimensions = decoder.dimensions();
+                (decoder.into_frames(), dimensions)
+            }
+            _ => return Ok(Vec::new()),
+        };
+        if !self.limits.allows(width, height) {
+            return Err(image_decoding::limits_exceeded().into());
+        }
 
-        // Convert to ASCII using the improved algorithm
-        let ascii_art = self.convert_image_to_ascii(&img, config).await;
+        let frame_pixels = (width as u64 * height as u64).max(1);
+        let max_frames = (self.limits.max_pixels / frame_pixels) as usize;
+        let frames: Vec<Frame> = frames
+            .take(max_frames.clamp(1, MAX_ANIMATION_FRAMES))
+            .collect::<Result<_, _>>()?;
+        if frames.len() < 2 {
+            return Ok(Vec::new());
+        }
 
-        Ok(ascii_art)
+        Ok(frames
+            .into_iter()
+            .map(|frame| {
+                let (numerator, denominator) = frame.delay().numer_denom_ms();
+                let delay_ms = numerator / denominator.max(1);
+                let delay_ms = if delay_ms < 20 {
+                    DEFAULT_FRAME_DELAY_MS
+                } else {
+                    delay_ms
+                };
+                (DynamicImage::ImageRgba8(frame.into_buffer()), delay_ms)
+            })
+            .collect())
     }
 
-    /// Convert DynamicImage to ASCII art with improved algorithm
-    async fn convert_image_to_ascii(
+    /// Convert DynamicImage to ASCII art using the configured render mode
+    fn convert_image_to_ascii(
         &self,
         img: &DynamicImage,
         config: &ConversionConfig,
-    ) -> String {
-        let ascii_chars = config.detail_level.char_set();
+        proceed: Proceed,
+    ) -> Result<AsciiRendering, ConversionError> {
+        match config.render_mode {
+            RenderMode::Ascii => self.render_ramp(img, config, proceed),
+            RenderMode::Braille => self.render_braille(img, config, proceed),
+            RenderMode::HalfBlock => {
+                self.render_blocks(img, config, proceed, 1, &HALF_BLOCK_GLYPHS)
+            }
+            RenderMode::Quadrant => self.render_blocks(img, config, proceed, 2, &QUADRANT_GLYPHS),
+            RenderMode::Structural => self.render_structural(img, config, proceed),
+            RenderMode::Edges => self.render_edges(img, config, proceed),
+        }
+    }
 
+    /// Number of text rows needed to keep the image proportions at the given column count
+    fn text_rows(&self, img: &DynamicImage, columns: u32) -> u32 {
         let (img_width, img_height) = img.dimensions();
         let aspect_ratio = img_height as f32 / img_width as f32;
         // Adjust aspect ratio compensation for better proportions in text
-        let height = (config.width as f32 * aspect_ratio * 0.43) as u32;
+        (columns as f32 * aspect_ratio * 0.43) as u32
+    }
 
+    /// Pixel size of the canvas `columns`x`rows` cells of `cell_width`x`cell_height` pixels
+    /// are sampled from. Refused beyond the limits before anything is resized, since a tall
+    /// image at many columns would otherwise resize into far more memory than it decoded to.
+    fn canvas(
+        &self,
+        columns: u32,
+        rows: u32,
+        cell_width: u32,
+        cell_height: u32,
+    ) -> Result<(u32, u32), ConversionError> {
+        let width = columns as u64 * cell_width as u64;
+        let height = rows as u64 * cell_height as u64;
+        match (u32::try_from(width), u32::try_from(height)) {
+            (Ok(w), Ok(h)) if self.limits.allows(w, h) => Ok((w, h)),
+            _ => Err(ConversionError::OutputTooLarge {
+                width,
+                height,
+                limits: self.limits,
+            }),
+        }
+    }
+
+    /// Resize, contrast-enhance and smooth the image, returning the resized colors
+    /// alongside the processed grayscale pixels
+    fn preprocess(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        width: u32,
+        height: u32,
+        proceed: Proceed,
+    ) -> Result<(RgbImage, GrayImage), ConversionError> {
         // Use better resampling for sharper results
-        let resized = img.resize_exact(
-            config.width,
-            height,
-            image::imageops::FilterType::CatmullRom,
-        );
+        let resized = img.resize_exact(width, height, image::imageops::FilterType::CatmullRom);
+        proceed.check()?;
 
         // Apply contrast enhancement before converting to grayscale
-        let contrast_adjusted = self.enhance_contrast(&resized, config.contrast_factor);
+        let contrast_adjusted = image_filters::enhance_contrast(&resized, config.contrast_factor);
         let gray = contrast_adjusted.to_luma8();
+        proceed.check()?;
 
         // Apply edge-preserving smoothing to reduce noise while maintaining details
-        let smoothed = self.gaussian_blur(&gray, config.blur_sigma);
+        let smoothed = image_filters::gaussian_blur(&gray, config.blur_sigma);
+        proceed.check()?;
+
+        // Colors are sampled before contrast enhancement to stay faithful to the source
+        Ok((resized.to_rgb8(), smoothed))
+    }
+
+    /// Render one character per cell from the detail level's intensity ramp
+    fn render_ramp(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        proceed: Proceed,
+    ) -> Result<AsciiRendering, ConversionError> {
+        let ascii_chars = self.ramp_glyphs(config);
+        let height = self.text_rows(img, config.width);
+        self.canvas(config.width, height, 1, 1)?;
+
+        let (colors, smoothed) = self.preprocess(img, config, config.width, height, proceed)?;
 
-        // Use adaptive thresholding for better character mapping
-        let processed = self.adaptive_threshold(&smoothed, ascii_chars.len());
+        let char_indices = self.ramp_indices(&smoothed, config, ascii_chars.len());
+        proceed.check()?;
+        let colors_row = colors.rows();
 
         let mut result = String::with_capacity((config.width * height + height) as usize);
 
-        for (y, row) in processed.rows().enumerate() {
-            for pixel in row {
-                let Luma([intensity]) = *pixel;
-                let char_index = self.map_intensity_to_char(intensity, ascii_chars.len());
-                result.push(ascii_chars.chars().nth(char_index).unwrap());
+        for (y, (indices, color_row)) in char_indices
+            .chunks(config.width as usize)
+            .zip(colors_row)
+            .enumerate()
+        {
+            proceed.check()?;
+            let mut line = ColoredLine::new(&mut result, config.color_mode);
+            for (&char_index, &Rgb(rgb)) in indices.iter().zip(color_row) {
+                line.push(ascii_chars[char_index], rgb);
             }
+            line.finish();
             if y < height as usize - 1 {
                 result.push('\n');
             }
         }
 
-        result
+        Ok(AsciiRendering {
+            content: result,
+            columns: config.width,
+            rows: height,
+            frames: Vec::new(),
+        })
     }
 
-    /// Enhance contrast of an image
-    fn enhance_contrast(&self, img: &DynamicImage, factor: f32) -> DynamicImage {
-        let rgb_img = img.to_rgb8();
-        let (width, height) = rgb_img.dimensions();
-        let mut enhanced = RgbImage::new(width, height);
+    /// Map every pixel to a ramp index, dithering between ramp levels if configured
+    fn ramp_indices(
+        &self,
+        img: &GrayImage,
+        config: &ConversionConfig,
+        levels: usize,
+    ) -> Vec<usize> {
+        let apply_invert = |intensity: u8| {
+            if config.invert {
+                255 - intensity
+            } else {
+                intensity
+            }
+        };
+
+        if config.dithering == Dithering::None {
+            // Use adaptive thresholding for better character mapping
+            let processed = image_filters::adaptive_threshold(img, levels);
+            return processed
+                .pixels()
+                .map(|&Luma([intensity])| {
+                    self.map_intensity_to_char(apply_invert(intensity), levels)
+                })
+                .collect();
+        }
+
+        // Equalize without quantizing, then let the ditherer pick between adjacent levels
+        // on the same perceptual scale as `map_intensity_to_char`
+        let equalized = image_filters::adaptive_threshold(img, 256);
+        let values = equalized
+            .pixels()
+            .map(|&Luma([intensity])| (apply_invert(intensity) as f32 / 255.0).powf(0.7) * 255.0)
+            .collect();
 
-        for (x, y, pixel) in rgb_img.enumerate_pixels() {
-            let Rgb([r, g, b]) = *pixel;
+        dithering::quantize(
+            values,
+            img.width() as usize,
+            levels,
+            config.dithering,
+            config.serpentine,
+        )
+    }
 
-            // Apply contrast enhancement to each channel
-            let new_r = ((r as f32 - 128.0) * factor + 128.0).clamp(0.0, 255.0) as u8;
-            let new_g = ((g as f32 - 128.0) * factor + 128.0).clamp(0.0, 255.0) as u8;
-            let new_b = ((b as f32 - 128.0) * factor + 128.0).clamp(0.0, 255.0) as u8;
+    /// Glyphs of the intensity ramp from lightest to densest
+    fn ramp_glyphs(&self, config: &ConversionConfig) -> Vec<char> {
+        let Some(ramp) = &config.custom_ramp else {
+            return config.detail_level.char_set().chars().collect();
+        };
 
-            enhanced.put_pixel(x, y, Rgb([new_r, new_g, new_b]));
+        let mut glyphs = ramp.glyphs().to_vec();
+        if ramp.sort_by_density {
+            // Only the glyphs the font covers are reordered, among the positions they
+            // hold; the others keep their place in the given ramp
+            let font = BitmapFont::builtin();
+            let (positions, mut ranked): (Vec<usize>, Vec<(f32, char)>) = glyphs
+                .iter()
+                .enumerate()
+                .filter_map(|(i, &c)| Some((i, (font.ink_density(c)?, c))))
+                .unzip();
+            ranked.sort_by(|a, b| a.0.total_cmp(&b.0));
+            for (position, (_, c)) in positions.into_iter().zip(ranked) {
+                glyphs[position] = c;
+            }
         }
 
-        DynamicImage::ImageRgb8(enhanced)
+        glyphs
     }
 
-    /// Apply Gaussian blur to reduce noise while preserving edges
-    fn gaussian_blur(&self, img: &GrayImage, sigma: f32) -> GrayImage {
-        if sigma <= 0.0 {
-            return img.clone();
+    /// Render each 2x4 block of thresholded pixels as a single Braille pattern
+    fn render_braille(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        proceed: Proceed,
+    ) -> Result<AsciiRendering, ConversionError> {
+        let rows = self.text_rows(img, config.width).max(1);
+        let (width, height) = self.canvas(config.width, rows, 2, 4)?;
+
+        let (colors, smoothed) = self.preprocess(img, config, width, height, proceed)?;
+        let dots = self.binarize(&smoothed, config);
+
+        let mut result = String::with_capacity(((config.width * 3 + 1) * rows) as usize);
+
+        for row in 0..rows {
+            proceed.check()?;
+            let mut line = ColoredLine::new(&mut result, config.color_mode);
+            for column in 0..config.width {
+                let mut pattern = 0u32;
+                let mut color_sum = [0u32; 3];
+
+                for (dx, dy, bit) in BRAILLE_DOTS {
+                    let x = column * 2 + dx;
+                    let y = row * 4 + dy;
+                    if dots[(y * width + x) as usize] {
+                        pattern |= bit;
+                    }
+                    let Rgb(rgb) = *colors.get_pixel(x, y);
+                    for (sum, channel) in color_sum.iter_mut().zip(rgb) {
+                        *sum += channel as u32;
+                    }
+                }
+
+                let glyph = char::from_u32(BRAILLE_BASE + pattern).unwrap_or(' ');
+                line.push(glyph, color_sum.map(|sum| (sum / 8) as u8));
+            }
+            line.finish();
+            if row < rows - 1 {
+                result.push('\n');
+            }
         }
 
-        let (width, height) = img.dimensions();
-        let mut result = img.clone();
+        Ok(AsciiRendering {
+            content: result,
+            columns: config.width,
+            rows,
+            frames: Vec::new(),
+        })
+    }
 
-        // Create Gaussian kernel
-        let kernel_size = (6.0 * sigma).ceil() as i32;
-        let kernel_size = if kernel_size % 2 == 0 {
-            kernel_size + 1
+    /// Render block elements covering `cell_width`x2 pixels per cell. With colors enabled,
+    /// each cell is split into the foreground/background partition that best fits its pixels;
+    /// in monochrome the thresholded pixels select the glyph directly.
+    fn render_blocks(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        proceed: Proceed,
+        cell_width: u32,
+        glyphs: &[char],
+    ) -> Result<AsciiRendering, ConversionError> {
+        let rows = self.text_rows(img, config.width).max(1);
+        let (width, height) = self.canvas(config.width, rows, cell_width, 2)?;
+
+        let (colors, smoothed) = self.preprocess(img, config, width, height, proceed)?;
+        let dots = if config.color_mode.is_colored() {
+            Vec::new()
         } else {
-            kernel_size
+            self.binarize(&smoothed, config)
         };
-        let half_kernel = kernel_size / 2;
 
-        let mut kernel = Vec::new();
-        let mut sum = 0.0;
+        let pixel_count = (cell_width * 2) as usize;
+        let mut result = String::with_capacity(((config.width * 4 + 1) * rows) as usize);
 
-        for i in -half_kernel..=half_kernel {
-            let value = (-0.5 * (i as f32 / sigma).powi(2)).exp();
-            kernel.push(value);
-            sum += value;
-        }
+        for row in 0..rows {
+            proceed.check()?;
+            let mut line = ColoredLine::new(&mut result, config.color_mode);
+            for column in 0..config.width {
+                // Cell pixels in row-major order, matching the glyph mask bits
+                let positions: Vec<(u32, u32)> = (0..pixel_count as u32)
+                    .map(|i| {
+                        (
+                            column * cell_width + i % cell_width,
+                            row * 2 + i / cell_width,
+                        )
+                    })
+                    .collect();
 
-        // Normalize kernel
-        for value in &mut kernel {
-            *value /= sum;
+                if dots.is_empty() {
+                    let pixels: Vec<[u8; 3]> = positions
+                        .iter()
+                        .map(|&(x, y)| colors.get_pixel(x, y).0)
+                        .collect();
+                    let (mask, foreground, background) = self.best_partition(&pixels);
+                    line.push_with_background(glyphs[mask], foreground, background);
+                } else {
+                    let mask = positions
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &(x, y))| dots[(y * width + x) as usize])
+                        .fold(0, |mask, (bit, _)| mask | (1 << bit));
+                    line.push(glyphs[mask], [255, 255, 255]);
+                }
+            }
+            line.finish();
+            if row < rows - 1 {
+                result.push('\n');
+            }
         }
 
-        // Horizontal pass
-        let mut temp = GrayImage::new(width, height);
-        for y in 0..height {
-            for x in 0..width {
-                let mut weighted_sum = 0.0;
-                let mut weight_sum = 0.0;
-
-                for (i, &weight) in kernel.iter().enumerate() {
-                    let offset_x = x as i32 + i as i32 - half_kernel;
-                    if offset_x >= 0 && offset_x < width as i32 {
-                        let pixel_value = img.get_pixel(offset_x as u32, y)[0] as f32;
-                        weighted_sum += pixel_value * weight;
-                        weight_sum += weight;
+        Ok(AsciiRendering {
+            content: result,
+            columns: config.width,
+            rows,
+            frames: Vec::new(),
+        })
+    }
+
+    /// Find the split of cell pixels into two color groups with the lowest squared error,
+    /// returning the foreground mask and the mean color of each group
+    fn best_partition(&self, pixels: &[[u8; 3]]) -> (usize, [u8; 3], [u8; 3]) {
+        let full_mask = (1usize << pixels.len()) - 1;
+        let mean = |mask: usize| -> Option<[u8; 3]> {
+            let mut sum = [0u32; 3];
+            let mut count = 0;
+            for (i, pixel) in pixels.iter().enumerate() {
+                if mask & (1 << i) != 0 {
+                    for (total, &channel) in sum.iter_mut().zip(pixel) {
+                        *total += channel as u32;
                     }
+                    count += 1;
                 }
+            }
+            (count > 0).then(|| sum.map(|total| (total / count) as u8))
+        };
 
-                let new_value = if weight_sum > 0.0 {
-                    (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8
-                } else {
-                    img.get_pixel(x, y)[0]
-                };
+        let mut best = (full_mask, [0u8; 3], [0u8; 3]);
+        let mut best_error = u32::MAX;
 
-                temp.put_pixel(x, y, Luma([new_value]));
+        // Masks containing the first pixel cover every partition once
+        for mask in (1..=full_mask).filter(|mask| mask & 1 != 0) {
+            let foreground = mean(mask).unwrap_or_default();
+            let background = mean(full_mask & !mask).unwrap_or(foreground);
+            let error: u32 = pixels
+                .iter()
+                .enumerate()
+                .map(|(i, &pixel)| {
+                    let group = if mask & (1 << i) != 0 {
+                        foreground
+                    } else {
+                        background
+                    };
+                    color_distance(pixel, group)
+                })
+                .sum();
+
+            if error < best_error {
+                best_error = error;
+                best = (mask, foreground, background);
             }
         }
 
-        // Vertical pass
-        for y in 0..height {
-            for x in 0..width {
-                let mut weighted_sum = 0.0;
-                let mut weight_sum = 0.0;
-
-                for (i, &weight) in kernel.iter().enumerate() {
-                    let offset_y = y as i32 + i as i32 - half_kernel;
-                    if offset_y >= 0 && offset_y < height as i32 {
-                        let pixel_value = temp.get_pixel(x, offset_y as u32)[0] as f32;
-                        weighted_sum += pixel_value * weight;
-                        weight_sum += weight;
+        best
+    }
+
+    /// Render each cell as the ramp glyph whose rasterized shape best matches the cell's
+    /// pixels, so that edges follow the glyph outlines instead of only their ink density
+    fn render_structural(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        proceed: Proceed,
+    ) -> Result<AsciiRendering, ConversionError> {
+        let glyphs = self.ramp_glyphs(config);
+        let Some(atlas) = GlyphAtlas::new(
+            BitmapFont::builtin(),
+            &glyphs,
+            STRUCTURAL_CELL_WIDTH,
+            STRUCTURAL_CELL_HEIGHT,
+        ) else {
+            // None of the glyphs can be rasterized, so there are no shapes to match
+            return self.render_ramp(img, config, proceed);
+        };
+
+        let rows = self.text_rows(img, config.width).max(1);
+        let (cell_width, cell_height) = (atlas.cell_width(), atlas.cell_height());
+        let (width, height) = self.canvas(config.width, rows, cell_width, cell_height)?;
+        let (colors, smoothed) = self.preprocess(img, config, width, height, proceed)?;
+
+        let samples = (cell_width * cell_height) as usize;
+        let mut patch = Vec::with_capacity(samples);
+        let mut result = String::with_capacity(((config.width + 1) * rows) as usize);
+
+        for row in 0..rows {
+            proceed.check()?;
+            let mut line = ColoredLine::new(&mut result, config.color_mode);
+            for column in 0..config.width {
+                patch.clear();
+                let mut color_sum = [0u32; 3];
+
+                for dy in 0..cell_height {
+                    for dx in 0..cell_width {
+                        let (x, y) = (column * cell_width + dx, row * cell_height + dy);
+                        let ink = smoothed.get_pixel(x, y)[0] as f32 / 255.0;
+                        patch.push(if config.invert { 1.0 - ink } else { ink });
+
+                        let Rgb(rgb) = *colors.get_pixel(x, y);
+                        for (sum, channel) in color_sum.iter_mut().zip(rgb) {
+                            *sum += channel as u32;
+                        }
                     }
                 }
 
-                let new_value = if weight_sum > 0.0 {
-                    (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8
-                } else {
-                    temp.get_pixel(x, y)[0]
-                };
+                let color = color_sum.map(|sum| (sum / samples as u32) as u8);
+                line.push(atlas.best_match(&patch), color);
+            }
+            line.finish();
+            if row < rows - 1 {
+                result.push('\n');
+            }
+        }
 
-                result.put_pixel(x, y, Luma([new_value]));
+        Ok(AsciiRendering {
+            content: result,
+            columns: config.width,
+            rows,
+            frames: Vec::new(),
+        })
+    }
+
+    /// Render orientation glyphs along detected edges and fill the remaining cells
+    /// from the intensity ramp or with blanks
+    fn render_edges(
+        &self,
+        img: &DynamicImage,
+        config: &ConversionConfig,
+        proceed: Proceed,
+    ) -> Result<AsciiRendering, ConversionError> {
+        let rows = self.text_rows(img, config.width).max(1);
+        let (width, height) = self.canvas(config.width, rows, EDGE_CELL_WIDTH, EDGE_CELL_HEIGHT)?;
+        let (colors, smoothed) = self.preprocess(img, config, width, height, proceed)?;
+
+        let (magnitude, direction) = self.sobel(&smoothed);
+        proceed.check()?;
+        let edges = self.detect_edges(&magnitude, &direction, width, height, config);
+        proceed.check()?;
+
+        // Cell-resolution intensities for the ramp fill
+        let fill = match config.edges.fill {
+            EdgeFill::Ramp => {
+                let ascii_chars = self.ramp_glyphs(config);
+                let cells = image::imageops::resize(
+                    &smoothed,
+                    config.width,
+                    rows,
+                    image::imageops::FilterType::Triangle,
+                );
+                let indices = self.ramp_indices(&cells, config, ascii_chars.len());
+                indices.into_iter().map(|i| ascii_chars[i]).collect()
+            }
+            EdgeFill::Blank => vec![' '; (config.width * rows) as usize],
+        };
+
+        let mut result = String::with_capacity(((config.width + 1) * rows) as usize);
+
+        for row in 0..rows {
+            proceed.check()?;
+            let mut line = ColoredLine::new(&mut result, config.color_mode);
+            for column in 0..config.width {
+                let mut strongest: Option<(f32, u32, u32)> = None;
+                let mut color_sum = [0u32; 3];
+
+                for dy in 0..EDGE_CELL_HEIGHT {
+                    for dx in 0..EDGE_CELL_WIDTH {
+                        let (x, y) = (column * EDGE_CELL_WIDTH + dx, row * EDGE_CELL_HEIGHT + dy);
+                        let index = (y * width + x) as usize;
+                        if edges[index] && strongest.is_none_or(|(m, _, _)| magnitude[index] > m) {
+                            strongest = Some((magnitude[index], x, y));
+                        }
+
+                        let Rgb(rgb) = *colors.get_pixel(x, y);
+                        for (sum, channel) in color_sum.iter_mut().zip(rgb) {
+                            *sum += channel as u32;
+                        }
+                    }
+                }
+
+                let glyph = match strongest {
+                    Some((_, x, y)) => {
+                        let lower_half = y % EDGE_CELL_HEIGHT >= EDGE_CELL_HEIGHT / 2;
+                        self.edge_glyph(direction[(y * width + x) as usize], lower_half)
+                    }
+                    None => fill[(row * config.width + column) as usize],
+                };
+                let samples = EDGE_CELL_WIDTH * EDGE_CELL_HEIGHT;
+                line.push(glyph, color_sum.map(|sum| (sum / samples) as u8));
+            }
+            line.finish();
+            if row < rows - 1 {
+                result.push('\n');
             }
         }
 
-        result
+        Ok(AsciiRendering {
+            content: result,
+            columns: config.width,
+            rows,
+            frames: Vec::new(),
+        })
     }
 
-    /// Apply adaptive thresholding for better character mapping
-    fn adaptive_threshold(&self, img: &GrayImage, levels: usize) -> GrayImage {
+    /// Compute the Sobel gradient of every pixel, returning the magnitude normalized to
+    /// 0..1 and the direction of the edge (perpendicular to the gradient) in degrees within
+    /// 0..180, measured with the y axis pointing down
+    fn sobel(&self, img: &GrayImage) -> (Vec<f32>, Vec<f32>) {
         let (width, height) = img.dimensions();
-        let mut result = img.clone();
-
-        // Calculate histogram
-        let mut histogram = vec![0; 256];
-        for y in 0..height {
-            for x in 0..width {
-                let intensity = img.get_pixel(x, y)[0] as usize;
-                histogram[intensity] += 1;
+        let pixel = |x: i64, y: i64| -> f32 {
+            let x = x.clamp(0, width as i64 - 1) as u32;
+            let y = y.clamp(0, height as i64 - 1) as u32;
+            img.get_pixel(x, y)[0] as f32
+        };
+
+        let mut magnitude = Vec::with_capacity((width * height) as usize);
+        let mut direction = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height as i64 {
+            for x in 0..width as i64 {
+                let gx = (pixel(x + 1, y - 1) + 2.0 * pixel(x + 1, y) + pixel(x + 1, y + 1))
+                    - (pixel(x - 1, y - 1) + 2.0 * pixel(x - 1, y) + pixel(x - 1, y + 1));
+                let gy = (pixel(x - 1, y + 1) + 2.0 * pixel(x, y + 1) + pixel(x + 1, y + 1))
+                    - (pixel(x - 1, y - 1) + 2.0 * pixel(x, y - 1) + pixel(x + 1, y - 1));
+
+                magnitude.push((gx * gx + gy * gy).sqrt() / MAX_SOBEL_MAGNITUDE);
+                // The edge runs along (-gy, gx)
+                direction.push(gx.atan2(-gy).to_degrees().rem_euclid(180.0));
+            }
+        }
+
+        (magnitude, direction)
+    }
+
+    /// Classify edge pixels with optional non-maximum suppression and hysteresis
+    fn detect_edges(
+        &self,
+        magnitude: &[f32],
+        direction: &[f32],
+        width: u32,
+        height: u32,
+        config: &ConversionConfig,
+    ) -> Vec<bool> {
+        let (width, height) = (width as i64, height as i64);
+        let options = &config.edges;
+        let at = |x: i64, y: i64| -> f32 {
+            if (0..width).contains(&x) && (0..height).contains(&y) {
+                magnitude[(y * width + x) as usize]
+            } else {
+                0.0
+            }
+        };
+
+        // Non-maximum suppression keeps pixels that peak across the edge
+        let mut candidates: Vec<f32> = magnitude.to_vec();
+        if options.thin {
+            for y in 0..height {
+                for x in 0..width {
+                    let index = (y * width + x) as usize;
+                    // Neighbors across the edge lie along the gradient direction
+                    let (dx, dy) = match direction[index] {
+                        d if !(22.5..157.5).contains(&d) => (0, 1),
+                        d if d < 67.5 => (1, -1),
+                        d if d < 112.5 => (1, 0),
+                        _ => (1, 1),
+                    };
+                    // Ties go to the first pixel so plateaus stay one pixel wide
+                    let m = magnitude[index];
+                    if m < at(x + dx, y + dy) || m <= at(x - dx, y - dy) {
+                        candidates[index] = 0.0;
+                    }
+                }
             }
         }
 
-        // Calculate cumulative distribution
-        let total_pixels = (width * height) as f32;
-        let mut cumulative = vec![0.0; 256];
-        cumulative[0] = histogram[0] as f32 / total_pixels;
+        // Hysteresis: grow strong edges through connected weak ones
+        let mut edges = vec![false; candidates.len()];
+        let mut stack: Vec<(i64, i64)> = Vec::new();
+        for (index, &m) in candidates.iter().enumerate() {
+            if m >= options.high_threshold && m > 0.0 {
+                edges[index] = true;
+                stack.push((index as i64 % width, index as i64 / width));
+            }
+        }
+        while let Some((x, y)) = stack.pop() {
+            for (nx, ny) in (-1..=1).flat_map(|dy| (-1..=1).map(move |dx| (x + dx, y + dy))) {
+                if !(0..width).contains(&nx) || !(0..height).contains(&ny) {
+                    continue;
+                }
+                let index = (ny * width + nx) as usize;
+                let m = candidates[index];
+                if !edges[index] && m > 0.0 && m >= options.low_threshold {
+                    edges[index] = true;
+                    stack.push((nx, ny));
+                }
+            }
+        }
 
-        for i in 1..256 {
-            cumulative[i] = cumulative[i - 1] + histogram[i] as f32 / total_pixels;
+        edges
+    }
+
+    /// Pick the glyph drawing an edge running in the given direction
+    fn edge_glyph(&self, direction: f32, lower_half: bool) -> char {
+        match direction {
+            d if !(22.5..157.5).contains(&d) => {
+                if lower_half {
+                    '_'
+                } else {
+                    '-'
+                }
+            }
+            d if d < 67.5 => '\\',
+            d if d < 112.5 => '|',
+            _ => '/',
+        }
+    }
+
+    /// Reduce a grayscale image to on/off pixels using the configured threshold and dithering
+    fn binarize(&self, img: &GrayImage, config: &ConversionConfig) -> Vec<bool> {
+        let threshold = config.threshold.unwrap_or_else(|| self.otsu_threshold(img)) as f32;
+
+        // Shift intensities so the threshold sits halfway between the two output levels
+        let values = img
+            .pixels()
+            .map(|p| p[0] as f32 + 127.5 - threshold)
+            .collect();
+
+        dithering::quantize(
+            values,
+            img.width() as usize,
+            2,
+            config.dithering,
+            config.serpentine,
+        )
+        .into_iter()
+        .map(|level| (level == 1) != config.invert)
+        .collect()
+    }
+
+    /// Compute the threshold that best separates the histogram into two classes (Otsu's method)
+    fn otsu_threshold(&self, img: &GrayImage) -> u8 {
+        let mut histogram = [0u64; 256];
+        for pixel in img.pixels() {
+            histogram[pixel[0] as usize] += 1;
         }
 
-        // Apply histogram equalization with level quantization
-        for y in 0..height {
-            for x in 0..width {
-                let intensity = img.get_pixel(x, y)[0] as usize;
-                let equalized = (cumulative[intensity] * 255.0) as u8;
+        let total: u64 = histogram.iter().sum();
+        let weighted_total: f64 = histogram
+            .iter()
+            .enumerate()
+            .map(|(i, &count)| i as f64 * count as f64)
+            .sum();
 
-                // Quantize to the specified number of levels
-                let level = (equalized as f32 / 255.0 * (levels - 1) as f32).round() as usize;
-                let quantized = (level as f32 / (levels - 1) as f32 * 255.0) as u8;
+        let mut background_weight = 0u64;
+        let mut background_sum = 0.0;
+        let mut best_threshold = 127u8;
+        let mut best_variance = 0.0;
 
-                result.put_pixel(x, y, Luma([quantized]));
+        for (i, &count) in histogram.iter().enumerate() {
+            background_weight += count;
+            if background_weight == 0 {
+                continue;
+            }
+            let foreground_weight = total - background_weight;
+            if foreground_weight == 0 {
+                break;
+            }
+
+            background_sum += i as f64 * count as f64;
+            let background_mean = background_sum / background_weight as f64;
+            let foreground_mean = (weighted_total - background_sum) / foreground_weight as f64;
+            let variance = background_weight as f64
+                * foreground_weight as f64
+                * (background_mean - foreground_mean).powi(2);
+
+            if variance > best_variance {
+                best_variance = variance;
+                best_threshold = i as u8;
             }
         }
 
-        result
+        best_threshold
     }
 
     /// Map intensity value to character index using perceptual weighting
@@ -245,6 +919,10 @@ impl Default for AsciiConversionService {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{
+        entities::ascii_art::DetailLevel,
+        value_objects::{CharRamp, ColorMode, Dithering, EdgeFill, EdgeOptions, RenderMode},
+    };
     // use crate::domain::entities::ascii_art::DetailLevel;
 
     #[test]
@@ -266,23 +944,250 @@ mod tests {
         assert!((1..=3).contains(&middle_char));
     }
 
+    #[tokio::test]
+    async fn truecolor_merges_runs_of_identical_colors() {
+        let service = AsciiConversionService::new();
+        let img = DynamicImage::ImageRgb8(RgbImage::from_pixel(40, 40, Rgb([200, 30, 30])));
+        let config =
+            ConversionConfig::new(20, DetailLevel::Low).with_color_mode(ColorMode::TrueColor);
+
+        let rendering = service
+            .convert_image_to_ascii(&img, &config, Proceed(&|| true))
+            .unwrap();
+
+        assert_eq!(rendering.columns, 20);
+        for line in rendering.content.lines() {
+            assert_eq!(line.matches("\x1b[38;2;200;30;30m").count(), 1);
+            assert!(line.ends_with(crate::application::services::ansi::ANSI_RESET));
+        }
+    }
+
     #[test]
-    fn enhance_contrast_preserves_dimensions() {
+    fn ansi256_quantization_round_trips_palette_colors() {
+        use crate::application::services::ansi::{ansi256_to_rgb, rgb_to_ansi256};
+
+        for code in 16..=255u8 {
+            assert_eq!(rgb_to_ansi256(ansi256_to_rgb(code)), code);
+        }
+    }
+
+    #[tokio::test]
+    async fn braille_packs_pixels_into_cells() {
         let service = AsciiConversionService::new();
+        // Left half white, right half black
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(80, 80, |x, _| {
+            Luma([if x < 40 { 255 } else { 0 }])
+        }));
+        let config = ConversionConfig::with_params(10, DetailLevel::High, 1.0, 0.0)
+            .with_render_mode(RenderMode::Braille);
 
-        // Create a simple test image
-        let img = DynamicImage::new_rgb8(10, 10);
-        let enhanced = service.enhance_contrast(&img, 1.2);
+        let rendering = service
+            .convert_image_to_ascii(&img, &config, Proceed(&|| true))
+            .unwrap();
 
-        assert_eq!(img.dimensions(), enhanced.dimensions());
+        assert_eq!(rendering.columns, 10);
+        assert_eq!(rendering.rows as usize, rendering.content.lines().count());
+        for line in rendering.content.lines() {
+            let cells: Vec<char> = line.chars().collect();
+            assert_eq!(cells.len(), 10);
+            assert_eq!(cells[0], '\u{28FF}');
+            assert_eq!(cells[9], '\u{2800}');
+        }
     }
 
     #[test]
-    fn gaussian_blur_with_zero_sigma_returns_same() {
+    fn best_partition_splits_two_color_cells() {
         let service = AsciiConversionService::new();
-        let original = GrayImage::new(5, 5);
-        let blurred = service.gaussian_blur(&original, 0.0);
+        let red = [255, 0, 0];
+        let blue = [0, 0, 255];
+
+        // Diagonal split: top-left and bottom-right red
+        let (mask, foreground, background) = service.best_partition(&[red, blue, blue, red]);
+
+        assert_eq!(QUADRANT_GLYPHS[mask], '▚');
+        assert_eq!(foreground, red);
+        assert_eq!(background, blue);
+    }
+
+    #[test]
+    fn custom_ramp_sorts_by_ink_density() {
+        let service = AsciiConversionService::new();
+        let ramp = CharRamp::new("█@ .▒", true).unwrap();
+        let config = ConversionConfig::default().with_custom_ramp(Some(ramp));
+
+        assert_eq!(service.ramp_glyphs(&config), vec![' ', '.', '@', '▒', '█']);
+
+        // Glyphs without a bitmap stay where they were given
+        let ramp = CharRamp::new("█あ .", true).unwrap();
+        let config = ConversionConfig::default().with_custom_ramp(Some(ramp));
+        assert_eq!(service.ramp_glyphs(&config), vec![' ', 'あ', '.', '█']);
+        assert!(CharRamp::new("", false).is_err());
+    }
+
+    #[test]
+    fn glyph_atlas_matches_cell_shape() {
+        let atlas = GlyphAtlas::new(
+            BitmapFont::builtin(),
+            &[' ', '|', '-', '/', '\\'],
+            STRUCTURAL_CELL_WIDTH,
+            STRUCTURAL_CELL_HEIGHT,
+        )
+        .unwrap();
+
+        // Horizontal stroke across the middle of the cell
+        let patch: Vec<f32> = (0..STRUCTURAL_CELL_WIDTH * STRUCTURAL_CELL_HEIGHT)
+            .map(|i| {
+                if i / STRUCTURAL_CELL_WIDTH == 5 {
+                    1.0
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        assert_eq!(atlas.best_match(&patch), '-');
+        assert_eq!(atlas.best_match(&vec![0.0; patch.len()]), ' ');
+    }
+
+    #[test]
+    fn dithering_preserves_average_intensity() {
+        let flat_gray = vec![127.5; 32 * 32];
+
+        for method in [
+            Dithering::FloydSteinberg,
+            Dithering::Atkinson,
+            Dithering::JarvisJudiceNinke,
+            Dithering::Sierra,
+            Dithering::Bayer2,
+            Dithering::Bayer4,
+            Dithering::Bayer8,
+        ] {
+            for serpentine in [false, true] {
+                let levels = dithering::quantize(flat_gray.clone(), 32, 2, method, serpentine);
+                let on = levels.iter().filter(|&&level| level == 1).count();
+                assert!(
+                    (400..=624).contains(&on),
+                    "{method}: {on} of 1024 pixels on"
+                );
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn edges_draw_orientation_glyphs() {
+        let service = AsciiConversionService::new();
+        // Dark left half, bright right half: a single vertical edge
+        let img = DynamicImage::ImageLuma8(GrayImage::from_fn(80, 80, |x, _| {
+            Luma([if x < 40 { 0 } else { 255 }])
+        }));
+        let config = ConversionConfig::with_params(20, DetailLevel::Low, 1.0, 0.0)
+            .with_render_mode(RenderMode::Edges)
+            .with_edges(EdgeOptions {
+                fill: EdgeFill::Blank,
+                ..EdgeOptions::default()
+            });
+
+        let rendering = service
+            .convert_image_to_ascii(&img, &config, Proceed(&|| true))
+            .unwrap();
+
+        for line in rendering.content.lines() {
+            assert_eq!(line.trim(), "|", "unexpected edge line {line:?}");
+        }
+    }
+
+    #[tokio::test]
+    async fn animated_gif_converts_every_frame() {
+        use image::{codecs::gif::GifEncoder, Delay, RgbaImage};
+
+        let mut data = Vec::new();
+        {
+            let mut encoder = GifEncoder::new(&mut data);
+            for (shade, delay_ms) in [(0u8, 50u32), (255, 200)] {
+                let frame = Frame::from_parts(
+                    RgbaImage::from_pixel(16, 16, image::Rgba([shade, shade, shade, 255])),
+                    0,
+                    0,
+                    Delay::from_numer_denom_ms(delay_ms, 1),
+                );
+                encoder.encode_frame(frame).unwrap();
+            }
+        }
+        let image_data = ImageData::new("anim.gif".into(), "image/gif".into(), data, 16, 16);
+        let service = AsciiConversionService::new();
+
+        let rendering = service
+            .convert_with_progress(
+                &image_data,
+                &ConversionConfig::new(8, DetailLevel::Low),
+                |_, _| true,
+            )
+            .unwrap();
+
+        let delays: Vec<u32> = rendering.frames.iter().map(|f| f.delay_ms).collect();
+        assert_eq!(delays, vec![50, 200]);
+        assert_eq!(rendering.content, rendering.frames[0].content);
+    }
+
+    #[test]
+    fn still_images_stop_mid_conversion_once_cancelled() {
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma8(GrayImage::new(64, 64))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image_data = ImageData::new("a.png".into(), "image/png".into(), png, 64, 64);
+        let config = ConversionConfig::new(40, DetailLevel::High);
+        let service = AsciiConversionService::new();
+
+        let mut checks = 0;
+        service
+            .convert_with_progress(&image_data, &config, |_, _| {
+                checks += 1;
+                true
+            })
+            .unwrap();
+        assert!(checks > 10, "only {} checks", checks);
+
+        // Cancelling after decoding stops the conversion before it finishes
+        let mut remaining = 3;
+        let result = service.convert_with_progress(&image_data, &config, |done, _| {
+            assert_eq!(done, 0);
+            remaining -= 1;
+            remaining > 0
+        });
+        assert!(matches!(result, Err(ConversionError::Cancelled)));
+    }
+
+    #[test]
+    fn outputs_sampling_beyond_the_limits_are_refused_before_resizing() {
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma8(GrayImage::new(1, 16_384))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        let image_data = ImageData::new("a.png".into(), "image/png".into(), png, 1, 16_384);
+        let service = AsciiConversionService::new();
+
+        for mode in [
+            RenderMode::Ascii,
+            RenderMode::Braille,
+            RenderMode::HalfBlock,
+            RenderMode::Quadrant,
+            RenderMode::Structural,
+            RenderMode::Edges,
+        ] {
+            let config = ConversionConfig::new(1000, DetailLevel::High).with_render_mode(mode);
+            let result = service.convert_with_progress(&image_data, &config, |_, _| true);
+            assert!(
+                matches!(result, Err(ConversionError::OutputTooLarge { .. })),
+                "{mode:?}"
+            );
+        }
 
-        assert_eq!(original.dimensions(), blurred.dimensions());
+        // One pixel per cell at a few columns stays within them
+        let config =
+            ConversionConfig::new(2, DetailLevel::High).with_render_mode(RenderMode::Ascii);
+        assert!(service
+            .convert_with_progress(&image_data, &config, |_, _| true)
+            .is_ok());
     }
 }
@@ -0,0 +1,122 @@
+use std::{collections::HashMap, sync::OnceLock};
+use thiserror::Error;
+
+/// Public domain X11 misc-fixed 8x13 font, subset to the glyphs the renderers need
+const BUILTIN_FONT: &str = include_str!("../../../assets/fonts/misc-fixed-8x13.bdf");
+
+#[derive(Error, Debug)]
+pub enum FontError {
+    #[error("Malformed BDF font: {0}")]
+    Malformed(String),
+}
+
+/// Monospace bitmap font with every glyph rasterized into a fixed-size cell
+pub struct BitmapFont {
+    cell_width: u32,
+    cell_height: u32,
+    glyphs: HashMap<char, Vec<bool>>,
+}
+
+impl BitmapFont {
+    /// Get the bundled font, parsed on first use
+    pub fn builtin() -> &'static BitmapFont {
+        static FONT: OnceLock<BitmapFont> = OnceLock::new();
+        FONT.get_or_init(|| Self::parse_bdf(BUILTIN_FONT).expect("bundled font is valid BDF"))
+    }
+
+    /// Parse a font in Glyph Bitmap Distribution Format (BDF)
+    pub fn parse_bdf(source: &str) -> Result<Self, FontError> {
+        let malformed = |what: &str| FontError::Malformed(what.to_string());
+        let numbers = |line: &str| -> Vec<i32> {
+            line.split_whitespace()
+                .skip(1)
+                .filter_map(|n| n.parse().ok())
+                .collect()
+        };
+
+        let bounding_box = source
+            .lines()
+            .find(|line| line.starts_with("FONTBOUNDINGBOX"))
+            .map(numbers)
+            .filter(|n| n.len() == 4)
+            .ok_or_else(|| malformed("missing FONTBOUNDINGBOX"))?;
+        let (cell_width, cell_height) = (bounding_box[0], bounding_box[1]);
+        let (origin_x, origin_y) = (bounding_box[2], bounding_box[3]);
+
+        let mut glyphs = HashMap::new();
+        let mut lines = source.lines();
+
+        while let Some(line) = lines.next() {
+            if !line.starts_with("STARTCHAR") {
+                continue;
+            }
+
+            let mut encoding = None;
+            let mut bbx = None;
+            for line in lines.by_ref() {
+                if line.starts_with("ENCODING") {
+                    encoding = numbers(line).first().copied();
+                } else if line.starts_with("BBX") {
+                    bbx = Some(numbers(line)).filter(|n| n.len() == 4);
+                } else if line.starts_with("BITMAP") {
+                    break;
+                }
+            }
+            let bbx = bbx.ok_or_else(|| malformed("glyph without BBX"))?;
+            let (width, height, offset_x, offset_y) = (bbx[0], bbx[1], bbx[2], bbx[3]);
+
+            // Position of the glyph box inside the cell, counted from the top-left corner
+            let left = offset_x - origin_x;
+            let top = (origin_y + cell_height) - (offset_y + height);
+
+            let mut pixels = vec![false; (cell_width * cell_height) as usize];
+            for (row, line) in lines.by_ref().take_while(|l| *l != "ENDCHAR").enumerate() {
+                let bits = u32::from_str_radix(line.trim(), 16)
+                    .map_err(|_| malformed("invalid bitmap row"))?;
+                let row_bits = line.trim().len() as i32 * 4;
+                for column in 0..width {
+                    let y = top + row as i32;
+                    let x = left + column;
+                    let set = bits & (1 << (row_bits - 1 - column)) != 0;
+                    if set && (0..cell_width).contains(&x) && (0..cell_height).contains(&y) {
+                        pixels[(y * cell_width + x) as usize] = true;
+                    }
+                }
+            }
+
+            if let Some(c) = encoding
+                .and_then(|code| u32::try_from(code).ok())
+                .and_then(char::from_u32)
+            {
+                glyphs.insert(c, pixels);
+            }
+        }
+
+        Ok(Self {
+            cell_width: cell_width as u32,
+            cell_height: cell_height as u32,
+            glyphs,
+        })
+    }
+
+    /// Width of a glyph cell in pixels
+    pub fn cell_width(&self) -> u32 {
+        self.cell_width
+    }
+
+    /// Height of a glyph cell in pixels
+    pub fn cell_height(&self) -> u32 {
+        self.cell_height
+    }
+
+    /// Get the row-major pixel mask of a glyph, if the font covers it
+    pub fn glyph(&self, c: char) -> Option<&[bool]> {
+        self.glyphs.get(&c).map(Vec::as_slice)
+    }
+
+    /// Fraction of the cell covered by the glyph's ink
+    pub fn ink_density(&self, c: char) -> Option<f32> {
+        self.glyph(c)
+            .map(|pixels| pixels.iter().filter(|&&set| set).count() as f32 / pixels.len() as f32)
+    }
+}
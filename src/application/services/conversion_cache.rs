@@ -0,0 +1,129 @@
+use crate::domain::value_objects::ConversionConfig;
+use hashlink::LruCache;
+use std::sync::{Mutex, MutexGuard};
+use uuid::Uuid;
+
+/// Image and [`ConversionConfig::cache_key`] a conversion was made from
+type CacheKey = (Uuid, String);
+
+/// Remembers which stored conversion an image and configuration produced, so that
+/// repeating a conversion reuses it. Holds at most `capacity` entries, dropping the
+/// least recently used one first; a capacity of zero disables caching.
+pub struct ConversionCache {
+    entries: Mutex<LruCache<CacheKey, Uuid>>,
+}
+
+impl ConversionCache {
+    /// Default number of conversions remembered
+    pub const DEFAULT_CAPACITY: usize = 1024;
+
+    /// Create a cache remembering at most `capacity` conversions
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(LruCache::new(capacity)),
+        }
+    }
+
+    /// ID of the ASCII art an earlier conversion of the image with this configuration
+    /// stored, marking it as recently used
+    pub fn get(&self, image_id: Uuid, config: &ConversionConfig) -> Option<Uuid> {
+        self.lock().get(&(image_id, config.cache_key())).copied()
+    }
+
+    /// Remember the ASCII art a conversion of the image with this configuration stored
+    pub fn insert(&self, image_id: Uuid, config: &ConversionConfig, ascii_art_id: Uuid) {
+        let mut entries = self.lock();
+        if entries.capacity() > 0 {
+            entries.insert((image_id, config.cache_key()), ascii_art_id);
+        }
+    }
+
+    /// Forget a conversion of the image with this configuration
+    pub fn remove(&self, image_id: Uuid, config: &ConversionConfig) {
+        self.lock().remove(&(image_id, config.cache_key()));
+    }
+
+    /// Forget every conversion of an image, once it is deleted
+    pub fn invalidate_image(&self, image_id: Uuid) {
+        let mut entries = self.lock();
+        let stale: Vec<CacheKey> = entries
+            .iter()
+            .filter(|((id, _), _)| *id == image_id)
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in stale {
+            entries.remove(&key);
+        }
+    }
+
+    /// Number of conversions currently remembered
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether no conversion is remembered
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, LruCache<CacheKey, Uuid>> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl Default for ConversionCache {
+    fn default() -> Self {
+        Self::new(Self::DEFAULT_CAPACITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::entities::ascii_art::DetailLevel;
+
+    #[test]
+    fn cache_evicts_least_recently_used_and_invalidates_images() {
+        let cache = ConversionCache::new(2);
+        let (image, other) = (Uuid::new_v4(), Uuid::new_v4());
+        let narrow = ConversionConfig::new(40, DetailLevel::High);
+        let wide = ConversionConfig::new(80, DetailLevel::High);
+        let (first, second, third) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        cache.insert(image, &narrow, first);
+        cache.insert(image, &wide, second);
+        // Using the first entry leaves the second as the one to evict
+        assert_eq!(cache.get(image, &narrow), Some(first));
+        cache.insert(other, &narrow, third);
+        assert_eq!(cache.get(image, &wide), None);
+        assert_eq!(cache.len(), 2);
+
+        // Equal configurations share an entry, whatever the sign of a zero
+        let mut unblurred = narrow.clone();
+        unblurred.blur_sigma = 0.0;
+        cache.insert(image, &unblurred, second);
+        unblurred.blur_sigma = -0.0;
+        assert_eq!(cache.get(image, &unblurred), Some(second));
+        assert_ne!(narrow.cache_key(), unblurred.cache_key());
+
+        // NaN is never a valid parameter, and whatever its bits it maps to one key
+        let mut nan = narrow.clone();
+        nan.contrast_factor = f32::NAN;
+        assert!(!nan.is_valid());
+        let key = nan.cache_key();
+        nan.contrast_factor = -f32::NAN;
+        assert_eq!(nan.cache_key(), key);
+        nan.edges.low_threshold = f32::NAN;
+        assert!(!nan.is_valid());
+
+        cache.invalidate_image(image);
+        assert_eq!(cache.get(image, &unblurred), None);
+        assert_eq!(cache.get(other, &narrow), Some(third));
+
+        let disabled = ConversionCache::new(0);
+        disabled.insert(image, &narrow, first);
+        assert!(disabled.is_empty());
+    }
+}
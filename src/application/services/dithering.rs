@@ -0,0 +1,148 @@
+use crate::domain::value_objects::Dithering;
+
+/// Error-diffusion kernel: (dx, dy, weight) offsets plus the common divisor
+struct DiffusionKernel {
+    taps: &'static [(isize, usize, f32)],
+    divisor: f32,
+}
+
+const FLOYD_STEINBERG: DiffusionKernel = DiffusionKernel {
+    taps: &[(1, 0, 7.0), (-1, 1, 3.0), (0, 1, 5.0), (1, 1, 1.0)],
+    divisor: 16.0,
+};
+
+/// Atkinson only diffuses 6/8 of the error, trading shadow detail for crisper output
+const ATKINSON: DiffusionKernel = DiffusionKernel {
+    taps: &[
+        (1, 0, 1.0),
+        (2, 0, 1.0),
+        (-1, 1, 1.0),
+        (0, 1, 1.0),
+        (1, 1, 1.0),
+        (0, 2, 1.0),
+    ],
+    divisor: 8.0,
+};
+
+const JARVIS_JUDICE_NINKE: DiffusionKernel = DiffusionKernel {
+    taps: &[
+        (1, 0, 7.0),
+        (2, 0, 5.0),
+        (-2, 1, 3.0),
+        (-1, 1, 5.0),
+        (0, 1, 7.0),
+        (1, 1, 5.0),
+        (2, 1, 3.0),
+        (-2, 2, 1.0),
+        (-1, 2, 3.0),
+        (0, 2, 5.0),
+        (1, 2, 3.0),
+        (2, 2, 1.0),
+    ],
+    divisor: 48.0,
+};
+
+const SIERRA: DiffusionKernel = DiffusionKernel {
+    taps: &[
+        (1, 0, 5.0),
+        (2, 0, 3.0),
+        (-2, 1, 2.0),
+        (-1, 1, 4.0),
+        (0, 1, 5.0),
+        (1, 1, 4.0),
+        (2, 1, 2.0),
+        (-1, 2, 2.0),
+        (0, 2, 3.0),
+        (1, 2, 2.0),
+    ],
+    divisor: 32.0,
+};
+
+/// Quantize row-major intensities in 0..=255 to `levels` evenly spaced levels, returning
+/// the level index of every pixel. Serpentine scanning alternates the direction of each
+/// row for error-diffusion methods, which avoids diagonal "worm" artifacts.
+pub fn quantize(
+    mut values: Vec<f32>,
+    width: usize,
+    levels: usize,
+    dithering: Dithering,
+    serpentine: bool,
+) -> Vec<usize> {
+    if width == 0 || levels < 2 {
+        return vec![0; values.len()];
+    }
+
+    let step = 255.0 / (levels - 1) as f32;
+    let nearest = |value: f32| (value / step).round().clamp(0.0, (levels - 1) as f32) as usize;
+    let height = values.len() / width;
+
+    let kernel = match dithering {
+        Dithering::None => return values.iter().map(|&v| nearest(v)).collect(),
+        Dithering::Bayer2 | Dithering::Bayer4 | Dithering::Bayer8 => {
+            let size = match dithering {
+                Dithering::Bayer2 => 2,
+                Dithering::Bayer4 => 4,
+                _ => 8,
+            };
+            let matrix = bayer_matrix(size);
+            return values
+                .iter()
+                .enumerate()
+                .map(|(i, &v)| {
+                    let (x, y) = (i % width, i / width);
+                    nearest(v + matrix[(y % size) * size + x % size] * step)
+                })
+                .collect();
+        }
+        Dithering::FloydSteinberg => &FLOYD_STEINBERG,
+        Dithering::Atkinson => &ATKINSON,
+        Dithering::JarvisJudiceNinke => &JARVIS_JUDICE_NINKE,
+        Dithering::Sierra => &SIERRA,
+    };
+
+    let mut result = vec![0; values.len()];
+    for y in 0..height {
+        let reverse = serpentine && y % 2 == 1;
+        for i in 0..width {
+            let x = if reverse { width - 1 - i } else { i };
+            let index = y * width + x;
+            let level = nearest(values[index]);
+            result[index] = level;
+
+            let error = values[index] - level as f32 * step;
+            for &(dx, dy, weight) in kernel.taps {
+                let dx = if reverse { -dx } else { dx };
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx >= 0 && (nx as usize) < width && ny < height {
+                    values[ny * width + nx as usize] += error * weight / kernel.divisor;
+                }
+            }
+        }
+    }
+
+    result
+}
+
+/// Ordered-dithering threshold offsets in -0.5..0.5 for a power-of-two Bayer matrix
+fn bayer_matrix(size: usize) -> Vec<f32> {
+    let mut matrix = vec![0usize];
+    let mut n = 1;
+    while n < size {
+        let mut next = vec![0; 4 * n * n];
+        for y in 0..2 * n {
+            for x in 0..2 * n {
+                let quadrant = [0, 2, 3, 1][(y / n) * 2 + x / n];
+                next[y * 2 * n + x] = 4 * matrix[(y % n) * n + x % n] + quadrant;
+            }
+        }
+        matrix = next;
+        n *= 2;
+    }
+
+    let cells = (size * size) as f32;
+    matrix
+        .into_iter()
+        .map(|m| (m as f32 + 0.5) / cells - 0.5)
+        .collect()
+}
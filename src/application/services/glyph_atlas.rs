@@ -0,0 +1,121 @@
+use crate::application::services::bitmap_font::BitmapFont;
+
+/// SSIM stabilizing constants for intensities in the 0..1 range
+const SSIM_C1: f32 = 0.01 * 0.01;
+const SSIM_C2: f32 = 0.03 * 0.03;
+
+/// Glyph masks downsampled to a fixed cell size, used to match image cells by shape
+pub struct GlyphAtlas {
+    cell_width: u32,
+    cell_height: u32,
+    glyphs: Vec<AtlasGlyph>,
+}
+
+struct AtlasGlyph {
+    glyph: char,
+    coverage: Vec<f32>,
+    mean: f32,
+    variance: f32,
+}
+
+impl GlyphAtlas {
+    /// Rasterize the glyphs the font covers into `cell_width`x`cell_height` coverage masks.
+    /// Returns `None` if none of the glyphs are available.
+    pub fn new(
+        font: &BitmapFont,
+        glyphs: &[char],
+        cell_width: u32,
+        cell_height: u32,
+    ) -> Option<Self> {
+        let glyphs: Vec<AtlasGlyph> = glyphs
+            .iter()
+            .filter_map(|&glyph| {
+                let pixels = font.glyph(glyph)?;
+                let coverage = Self::downsample(font, pixels, cell_width, cell_height);
+                let (mean, variance) = mean_and_variance(&coverage);
+                Some(AtlasGlyph {
+                    glyph,
+                    coverage,
+                    mean,
+                    variance,
+                })
+            })
+            .collect();
+
+        (!glyphs.is_empty()).then_some(Self {
+            cell_width,
+            cell_height,
+            glyphs,
+        })
+    }
+
+    /// Width of a cell in samples
+    pub fn cell_width(&self) -> u32 {
+        self.cell_width
+    }
+
+    /// Height of a cell in samples
+    pub fn cell_height(&self) -> u32 {
+        self.cell_height
+    }
+
+    /// Pick the glyph with the highest structural similarity (SSIM) to a row-major patch
+    /// of ink values in 0..1
+    pub fn best_match(&self, patch: &[f32]) -> char {
+        let (patch_mean, patch_variance) = mean_and_variance(patch);
+
+        self.glyphs
+            .iter()
+            .map(|glyph| {
+                let covariance = patch
+                    .iter()
+                    .zip(&glyph.coverage)
+                    .map(|(p, g)| (p - patch_mean) * (g - glyph.mean))
+                    .sum::<f32>()
+                    / patch.len() as f32;
+                let ssim = ((2.0 * patch_mean * glyph.mean + SSIM_C1)
+                    * (2.0 * covariance + SSIM_C2))
+                    / ((patch_mean.powi(2) + glyph.mean.powi(2) + SSIM_C1)
+                        * (patch_variance + glyph.variance + SSIM_C2));
+                (glyph.glyph, ssim)
+            })
+            .max_by(|a, b| a.1.total_cmp(&b.1))
+            .map(|(glyph, _)| glyph)
+            .unwrap_or(' ')
+    }
+
+    /// Box-filter a font glyph down to the atlas cell size
+    fn downsample(font: &BitmapFont, pixels: &[bool], width: u32, height: u32) -> Vec<f32> {
+        let (source_width, source_height) = (font.cell_width(), font.cell_height());
+        let mut coverage = Vec::with_capacity((width * height) as usize);
+
+        for y in 0..height {
+            let y0 = y * source_height / height;
+            let y1 = ((y + 1) * source_height / height).max(y0 + 1);
+            for x in 0..width {
+                let x0 = x * source_width / width;
+                let x1 = ((x + 1) * source_width / width).max(x0 + 1);
+
+                let mut ink = 0;
+                for sy in y0..y1 {
+                    for sx in x0..x1 {
+                        if pixels[(sy * source_width + sx) as usize] {
+                            ink += 1;
+                        }
+                    }
+                }
+                coverage.push(ink as f32 / ((y1 - y0) * (x1 - x0)) as f32);
+            }
+        }
+
+        coverage
+    }
+}
+
+/// Mean and population variance of a sample
+fn mean_and_variance(values: &[f32]) -> (f32, f32) {
+    let count = values.len().max(1) as f32;
+    let mean = values.iter().sum::<f32>() / count;
+    let variance = values.iter().map(|v| (v - mean).powi(2)).sum::<f32>() / count;
+    (mean, variance)
+}
@@ -0,0 +1,104 @@
+use crate::domain::value_objects::ImageLimits;
+use image::{
+    error::{LimitError, LimitErrorKind},
+    DynamicImage, ImageError, ImageReader, Limits,
+};
+use std::io::Cursor;
+
+/// Width and height from the image header, without decoding any pixels
+pub fn probe_dimensions(data: &[u8]) -> Result<(u32, u32), ImageError> {
+    ImageReader::new(Cursor::new(data))
+        .with_guessed_format()?
+        .into_dimensions()
+}
+
+/// Decoder limits enforcing `limits`. Allocations may take up to 8 bytes per pixel,
+/// enough for 16-bit RGBA output.
+pub fn decoder_limits(limits: &ImageLimits) -> Limits {
+    let mut decoder_limits = Limits::default();
+    decoder_limits.max_image_width = Some(limits.max_width);
+    decoder_limits.max_image_height = Some(limits.max_height);
+    decoder_limits.max_alloc = Some(limits.max_pixels.saturating_mul(8));
+    decoder_limits
+}
+
+/// Decode an image, refusing one whose header declares dimensions beyond `limits`
+/// before decoding anything
+pub fn decode(data: &[u8], limits: &ImageLimits) -> Result<DynamicImage, ImageError> {
+    let (width, height) = probe_dimensions(data)?;
+    if !limits.allows(width, height) {
+        return Err(limits_exceeded());
+    }
+
+    let mut reader = ImageReader::new(Cursor::new(data)).with_guessed_format()?;
+    reader.limits(decoder_limits(limits));
+    reader.decode()
+}
+
+/// Error for an image too large to decode, as the decoders report it
+pub fn limits_exceeded() -> ImageError {
+    ImageError::Limits(LimitError::from_kind(LimitErrorKind::DimensionError))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+
+    /// PNG declaring a huge image, with a few bytes standing in for its pixel data
+    fn png_bomb(width: u32, height: u32) -> Vec<u8> {
+        let mut header = width.to_be_bytes().to_vec();
+        header.extend(height.to_be_bytes());
+        header.extend([8, 2, 0, 0, 0]);
+
+        let mut data = b"\x89PNG\r\n\x1a\n".to_vec();
+        for (kind, content) in [
+            (b"IHDR", header.as_slice()),
+            (b"IDAT", &[0x78, 0x9c, 0x03, 0x00][..]),
+            (b"IEND", &[][..]),
+        ] {
+            let chunk = [&kind[..], content].concat();
+            data.extend((content.len() as u32).to_be_bytes());
+            data.extend(&chunk);
+            data.extend(crc32(&chunk).to_be_bytes());
+        }
+        data
+    }
+
+    fn crc32(bytes: &[u8]) -> u32 {
+        let mut crc = !0u32;
+        for &byte in bytes {
+            crc ^= byte as u32;
+            for _ in 0..8 {
+                crc = if crc & 1 == 1 {
+                    (crc >> 1) ^ 0xedb8_8320
+                } else {
+                    crc >> 1
+                };
+            }
+        }
+        !crc
+    }
+
+    #[test]
+    fn oversized_images_are_refused_from_their_header() {
+        let bomb = png_bomb(50_000, 50_000);
+        assert_eq!(probe_dimensions(&bomb).unwrap(), (50_000, 50_000));
+        assert!(matches!(
+            decode(&bomb, &ImageLimits::default()),
+            Err(ImageError::Limits(_))
+        ));
+
+        let mut small = Vec::new();
+        PngEncoder::new(&mut small)
+            .write_image(&[0; 4 * 3 * 3], 4, 3, ExtendedColorType::Rgb8)
+            .unwrap();
+        assert_eq!(decode(&small, &ImageLimits::default()).unwrap().height(), 3);
+        let tight = ImageLimits {
+            max_pixels: 11,
+            ..ImageLimits::default()
+        };
+        assert!(!tight.allows(4, 3));
+        assert!(matches!(decode(&small, &tight), Err(ImageError::Limits(_))));
+    }
+}
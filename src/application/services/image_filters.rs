@@ -0,0 +1,345 @@
+use image::{DynamicImage, GrayImage, RgbImage};
+use rayon::prelude::*;
+
+/// Enhance contrast of an image
+pub fn enhance_contrast(img: &DynamicImage, factor: f32) -> DynamicImage {
+    let rgb_img = img.to_rgb8();
+    let (width, height) = rgb_img.dimensions();
+    let mut enhanced = RgbImage::new(width, height);
+
+    let row_len = width as usize * 3;
+    enhanced
+        .par_chunks_mut(row_len.max(1))
+        .zip(rgb_img.par_chunks(row_len.max(1)))
+        .for_each(|(out, row)| {
+            // Apply contrast enhancement to each channel
+            for (out, &value) in out.iter_mut().zip(row) {
+                *out = ((value as f32 - 128.0) * factor + 128.0).clamp(0.0, 255.0) as u8;
+            }
+        });
+
+    DynamicImage::ImageRgb8(enhanced)
+}
+
+/// Apply Gaussian blur to reduce noise while preserving edges
+pub fn gaussian_blur(img: &GrayImage, sigma: f32) -> GrayImage {
+    if sigma <= 0.0 {
+        return img.clone();
+    }
+
+    let (width, height) = img.dimensions();
+    let (width, height) = (width as usize, height as usize);
+
+    // Create Gaussian kernel
+    let kernel_size = (6.0 * sigma).ceil() as i32;
+    let kernel_size = if kernel_size % 2 == 0 {
+        kernel_size + 1
+    } else {
+        kernel_size
+    };
+    let half_kernel = kernel_size / 2;
+
+    let mut kernel = Vec::new();
+    let mut sum = 0.0;
+
+    for i in -half_kernel..=half_kernel {
+        let value = (-0.5 * (i as f32 / sigma).powi(2)).exp();
+        kernel.push(value);
+        sum += value;
+    }
+
+    // Normalize kernel
+    for value in &mut kernel {
+        *value /= sum;
+    }
+
+    // Horizontal pass
+    let mut temp = GrayImage::new(width as u32, height as u32);
+    temp.par_chunks_mut(width.max(1))
+        .zip(img.par_chunks(width.max(1)))
+        .for_each(|(out, row)| {
+            for (x, out) in out.iter_mut().enumerate() {
+                *out = convolve(&kernel, half_kernel, width, x, |x| row[x]);
+            }
+        });
+
+    // Vertical pass, a whole row at a time so that the source rows are read in order.
+    // Each pixel still takes its taps in kernel order, as in `convolve`.
+    let temp: &[u8] = &temp;
+    let mut result = GrayImage::new(width as u32, height as u32);
+    result
+        .par_chunks_mut(width.max(1))
+        .enumerate()
+        .for_each(|(y, out)| {
+            let mut weighted_sums = vec![0.0f32; out.len()];
+            let mut weight_sum = 0.0;
+
+            for (i, &weight) in kernel.iter().enumerate() {
+                let offset_y = y as i32 + i as i32 - half_kernel;
+                if offset_y >= 0 && offset_y < height as i32 {
+                    let row = &temp[offset_y as usize * width..][..out.len()];
+                    for (sum, &pixel_value) in weighted_sums.iter_mut().zip(row) {
+                        *sum += pixel_value as f32 * weight;
+                    }
+                    weight_sum += weight;
+                }
+            }
+
+            let row = &temp[y * width..][..out.len()];
+            for ((out, weighted_sum), &original) in out.iter_mut().zip(weighted_sums).zip(row) {
+                *out = if weight_sum > 0.0 {
+                    (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8
+                } else {
+                    original
+                };
+            }
+        });
+
+    result
+}
+
+/// Weighted average of the kernel taps around `at` that fall within `0..len`. Taps
+/// are accumulated in kernel order, which keeps the result independent of how the
+/// rows are split between threads.
+fn convolve(
+    kernel: &[f32],
+    half_kernel: i32,
+    len: usize,
+    at: usize,
+    sample: impl Fn(usize) -> u8,
+) -> u8 {
+    let mut weighted_sum = 0.0;
+    let mut weight_sum = 0.0;
+
+    for (i, &weight) in kernel.iter().enumerate() {
+        let offset = at as i32 + i as i32 - half_kernel;
+        if offset >= 0 && offset < len as i32 {
+            weighted_sum += sample(offset as usize) as f32 * weight;
+            weight_sum += weight;
+        }
+    }
+
+    if weight_sum > 0.0 {
+        (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8
+    } else {
+        sample(at)
+    }
+}
+
+/// Apply adaptive thresholding for better character mapping
+pub fn adaptive_threshold(img: &GrayImage, levels: usize) -> GrayImage {
+    let (width, height) = img.dimensions();
+    let row_len = (width as usize).max(1);
+
+    // Calculate histogram
+    let histogram = img
+        .par_chunks(row_len)
+        .fold(
+            || vec![0usize; 256],
+            |mut histogram, row| {
+                for &intensity in row {
+                    histogram[intensity as usize] += 1;
+                }
+                histogram
+            },
+        )
+        .reduce(
+            || vec![0usize; 256],
+            |mut total, partial| {
+                for (total, count) in total.iter_mut().zip(partial) {
+                    *total += count;
+                }
+                total
+            },
+        );
+
+    // Calculate cumulative distribution
+    let total_pixels = (width * height) as f32;
+    let mut cumulative = vec![0.0; 256];
+    cumulative[0] = histogram[0] as f32 / total_pixels;
+
+    for i in 1..256 {
+        cumulative[i] = cumulative[i - 1] + histogram[i] as f32 / total_pixels;
+    }
+
+    // Apply histogram equalization with level quantization
+    let mut result = GrayImage::new(width, height);
+    result
+        .par_chunks_mut(row_len)
+        .zip(img.par_chunks(row_len))
+        .for_each(|(out, row)| {
+            for (out, &intensity) in out.iter_mut().zip(row) {
+                let equalized = (cumulative[intensity as usize] * 255.0) as u8;
+
+                // Quantize to the specified number of levels
+                let level = (equalized as f32 / 255.0 * (levels - 1) as f32).round() as usize;
+                *out = (level as f32 / (levels - 1) as f32 * 255.0) as u8;
+            }
+        });
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{GenericImageView, Luma, Rgb};
+
+    /// Pixel-by-pixel versions the parallel filters must reproduce exactly
+    mod serial {
+        use image::{DynamicImage, GrayImage, Luma, Rgb, RgbImage};
+
+        pub fn enhance_contrast(img: &DynamicImage, factor: f32) -> DynamicImage {
+            let rgb_img = img.to_rgb8();
+            let (width, height) = rgb_img.dimensions();
+            let mut enhanced = RgbImage::new(width, height);
+            for (x, y, pixel) in rgb_img.enumerate_pixels() {
+                let Rgb(channels) = *pixel;
+                enhanced.put_pixel(
+                    x,
+                    y,
+                    Rgb(channels
+                        .map(|c| ((c as f32 - 128.0) * factor + 128.0).clamp(0.0, 255.0) as u8)),
+                );
+            }
+            DynamicImage::ImageRgb8(enhanced)
+        }
+
+        pub fn gaussian_blur(img: &GrayImage, sigma: f32) -> GrayImage {
+            let (width, height) = img.dimensions();
+            let mut result = img.clone();
+            let kernel_size = (6.0 * sigma).ceil() as i32;
+            let kernel_size = if kernel_size % 2 == 0 {
+                kernel_size + 1
+            } else {
+                kernel_size
+            };
+            let half_kernel = kernel_size / 2;
+            let mut kernel = Vec::new();
+            let mut sum = 0.0;
+            for i in -half_kernel..=half_kernel {
+                let value = (-0.5 * (i as f32 / sigma).powi(2)).exp();
+                kernel.push(value);
+                sum += value;
+            }
+            for value in &mut kernel {
+                *value /= sum;
+            }
+
+            let mut temp = GrayImage::new(width, height);
+            for y in 0..height {
+                for x in 0..width {
+                    let (mut weighted_sum, mut weight_sum) = (0.0, 0.0);
+                    for (i, &weight) in kernel.iter().enumerate() {
+                        let offset_x = x as i32 + i as i32 - half_kernel;
+                        if offset_x >= 0 && offset_x < width as i32 {
+                            weighted_sum += img.get_pixel(offset_x as u32, y)[0] as f32 * weight;
+                            weight_sum += weight;
+                        }
+                    }
+                    let value = (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8;
+                    temp.put_pixel(x, y, Luma([value]));
+                }
+            }
+            for y in 0..height {
+                for x in 0..width {
+                    let (mut weighted_sum, mut weight_sum) = (0.0, 0.0);
+                    for (i, &weight) in kernel.iter().enumerate() {
+                        let offset_y = y as i32 + i as i32 - half_kernel;
+                        if offset_y >= 0 && offset_y < height as i32 {
+                            weighted_sum += temp.get_pixel(x, offset_y as u32)[0] as f32 * weight;
+                            weight_sum += weight;
+                        }
+                    }
+                    let value = (weighted_sum / weight_sum).clamp(0.0, 255.0) as u8;
+                    result.put_pixel(x, y, Luma([value]));
+                }
+            }
+            result
+        }
+
+        pub fn adaptive_threshold(img: &GrayImage, levels: usize) -> GrayImage {
+            let (width, height) = img.dimensions();
+            let mut result = img.clone();
+            let mut histogram = vec![0; 256];
+            for pixel in img.pixels() {
+                histogram[pixel[0] as usize] += 1;
+            }
+            let total_pixels = (width * height) as f32;
+            let mut cumulative = vec![0.0; 256];
+            cumulative[0] = histogram[0] as f32 / total_pixels;
+            for i in 1..256 {
+                cumulative[i] = cumulative[i - 1] + histogram[i] as f32 / total_pixels;
+            }
+            for (x, y, pixel) in img.enumerate_pixels() {
+                let equalized = (cumulative[pixel[0] as usize] * 255.0) as u8;
+                let level = (equalized as f32 / 255.0 * (levels - 1) as f32).round() as usize;
+                let quantized = (level as f32 / (levels - 1) as f32 * 255.0) as u8;
+                result.put_pixel(x, y, Luma([quantized]));
+            }
+            result
+        }
+    }
+
+    /// Deterministic noise with some structure, so that every filter has work to do
+    fn test_image(width: u32, height: u32) -> RgbImage {
+        let mut state = 0x2545_f491_u32;
+        RgbImage::from_fn(width, height, |x, y| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let noise = (state >> 24) as u8;
+            Rgb([noise, (x * 255 / width) as u8, noise / 2 + (y % 128) as u8])
+        })
+    }
+
+    #[test]
+    fn enhance_contrast_preserves_dimensions() {
+        // Create a simple test image
+        let img = DynamicImage::new_rgb8(10, 10);
+        let enhanced = enhance_contrast(&img, 1.2);
+
+        assert_eq!(img.dimensions(), enhanced.dimensions());
+    }
+
+    #[test]
+    fn gaussian_blur_with_zero_sigma_returns_same() {
+        let original = GrayImage::new(5, 5);
+        let blurred = gaussian_blur(&original, 0.0);
+
+        assert_eq!(original.dimensions(), blurred.dimensions());
+    }
+
+    #[test]
+    fn parallel_filters_match_serial_output() {
+        for (width, height) in [(1, 1), (3, 200), (257, 131)] {
+            let img = DynamicImage::ImageRgb8(test_image(width, height));
+            for factor in [0.5, 1.2, 3.0] {
+                assert_eq!(
+                    enhance_contrast(&img, factor).as_bytes(),
+                    serial::enhance_contrast(&img, factor).as_bytes()
+                );
+            }
+
+            let gray = img.to_luma8();
+            for sigma in [0.3, 0.5, 1.7, 4.0] {
+                assert_eq!(
+                    gaussian_blur(&gray, sigma),
+                    serial::gaussian_blur(&gray, sigma),
+                    "blur {width}x{height}, sigma {sigma}"
+                );
+            }
+            for levels in [2, 10, 70, 256] {
+                assert_eq!(
+                    adaptive_threshold(&gray, levels),
+                    serial::adaptive_threshold(&gray, levels),
+                    "threshold {width}x{height}, {levels} levels"
+                );
+            }
+        }
+
+        let flat = GrayImage::from_pixel(4, 0, Luma([9]));
+        assert_eq!(gaussian_blur(&flat, 1.0).dimensions(), (4, 0));
+        assert_eq!(adaptive_threshold(&flat, 10).dimensions(), (4, 0));
+    }
+}
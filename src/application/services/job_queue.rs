@@ -0,0 +1,214 @@
+use crate::domain::entities::{ConversionJob, JobStatus};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::Duration,
+};
+use thiserror::Error;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use uuid::Uuid;
+
+/// How long finished jobs stay around to be polled
+const FINISHED_JOB_RETENTION: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Error, Debug)]
+pub enum JobQueueError {
+    #[error("Too many pending jobs (max: {max})")]
+    QueueFull { max: usize },
+}
+
+struct JobEntry {
+    job: ConversionJob,
+    cancelled: Arc<AtomicBool>,
+}
+
+type JobTable = Arc<Mutex<HashMap<Uuid, JobEntry>>>;
+
+/// Tracks background jobs and limits how many of them run at once. Jobs live in memory
+/// only; finished ones are forgotten after an hour.
+pub struct JobQueue {
+    jobs: JobTable,
+    workers: Arc<Semaphore>,
+    max_pending: usize,
+}
+
+/// Worker side of a job, used to report its progress and outcome
+#[derive(Clone)]
+pub struct JobHandle {
+    id: Uuid,
+    jobs: JobTable,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl JobQueue {
+    /// Default limit on jobs that are queued or running
+    pub const DEFAULT_MAX_PENDING: usize = 256;
+
+    /// Create a queue running at most `workers` jobs at a time and accepting at most
+    /// `max_pending` unfinished jobs
+    pub fn new(workers: usize, max_pending: usize) -> Self {
+        Self {
+            jobs: Arc::new(Mutex::new(HashMap::new())),
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+            max_pending,
+        }
+    }
+
+    /// Register a new queued job for the given image
+    pub fn enqueue(&self, image_id: Uuid) -> Result<(ConversionJob, JobHandle), JobQueueError> {
+        let mut jobs = lock(&self.jobs);
+
+        let now = chrono::Utc::now();
+        jobs.retain(|_, entry| {
+            !entry.job.status.is_finished()
+                || (now - entry.job.updated_at)
+                    .to_std()
+                    .is_ok_and(|age| age < FINISHED_JOB_RETENTION)
+        });
+        let pending = jobs
+            .values()
+            .filter(|entry| !entry.job.status.is_finished())
+            .count();
+        if pending >= self.max_pending {
+            return Err(JobQueueError::QueueFull {
+                max: self.max_pending,
+            });
+        }
+
+        let job = ConversionJob::new(image_id);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        jobs.insert(
+            job.id,
+            JobEntry {
+                job: job.clone(),
+                cancelled: Arc::clone(&cancelled),
+            },
+        );
+
+        let handle = JobHandle {
+            id: job.id,
+            jobs: Arc::clone(&self.jobs),
+            cancelled,
+        };
+        Ok((job, handle))
+    }
+
+    /// Get the current state of a job
+    pub fn get(&self, id: Uuid) -> Option<ConversionJob> {
+        lock(&self.jobs).get(&id).map(|entry| entry.job.clone())
+    }
+
+    /// Ask a job to stop. Queued jobs are cancelled at once, running ones before their
+    /// next frame; finished jobs are left alone.
+    pub fn cancel(&self, id: Uuid) -> Option<ConversionJob> {
+        let mut jobs = lock(&self.jobs);
+        let entry = jobs.get_mut(&id)?;
+
+        if !entry.job.status.is_finished() {
+            entry.cancelled.store(true, Ordering::Relaxed);
+            if entry.job.status == JobStatus::Queued {
+                entry.job.status = JobStatus::Cancelled;
+                entry.job.updated_at = chrono::Utc::now();
+            }
+        }
+        Some(entry.job.clone())
+    }
+
+    /// Wait for a free worker slot, held until the permit is dropped
+    pub async fn acquire_worker(&self) -> OwnedSemaphorePermit {
+        Arc::clone(&self.workers)
+            .acquire_owned()
+            .await
+            .expect("worker semaphore is never closed")
+    }
+}
+
+impl JobHandle {
+    /// Whether the job was asked to stop
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Mark the job as running; false if it was cancelled while queued
+    pub fn start(&self) -> bool {
+        let mut jobs = lock(&self.jobs);
+        match jobs.get_mut(&self.id) {
+            Some(entry) if entry.job.status == JobStatus::Queued => {
+                entry.job.status = JobStatus::Running;
+                entry.job.updated_at = chrono::Utc::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Record the fraction of the work done
+    pub fn set_progress(&self, progress: f32) {
+        self.update(|job| job.progress = progress.clamp(0.0, 1.0));
+    }
+
+    /// Mark the job as done with its stored result
+    pub fn finish(&self, ascii_art_id: Uuid) {
+        self.update(|job| {
+            job.status = JobStatus::Done;
+            job.progress = 1.0;
+            job.ascii_art_id = Some(ascii_art_id);
+        });
+    }
+
+    /// Mark the job as failed
+    pub fn fail(&self, error: String) {
+        self.update(|job| {
+            job.status = JobStatus::Failed;
+            job.error = Some(error);
+        });
+    }
+
+    /// Mark the job as stopped on request
+    pub fn mark_cancelled(&self) {
+        self.update(|job| job.status = JobStatus::Cancelled);
+    }
+
+    fn update(&self, change: impl FnOnce(&mut ConversionJob)) {
+        if let Some(entry) = lock(&self.jobs).get_mut(&self.id) {
+            change(&mut entry.job);
+            entry.job.updated_at = chrono::Utc::now();
+        }
+    }
+}
+
+/// Lock the job table; a panic while holding the lock cannot leave an entry half-updated
+fn lock(jobs: &JobTable) -> MutexGuard<'_, HashMap<Uuid, JobEntry>> {
+    jobs.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cancelling_a_queued_job_stops_it_before_it_starts() {
+        let queue = JobQueue::new(1, 1);
+        let (job, handle) = queue.enqueue(Uuid::new_v4()).unwrap();
+        assert!(matches!(
+            queue.enqueue(Uuid::new_v4()),
+            Err(JobQueueError::QueueFull { max: 1 })
+        ));
+
+        let cancelled = queue.cancel(job.id).unwrap();
+        assert_eq!(cancelled.status, JobStatus::Cancelled);
+        assert!(handle.is_cancelled());
+        assert!(!handle.start());
+
+        // Finished jobs no longer count against the limit
+        let (_, handle) = queue.enqueue(Uuid::new_v4()).unwrap();
+        assert!(handle.start());
+        handle.set_progress(0.5);
+        handle.finish(Uuid::new_v4());
+        let done = queue.get(handle.id).unwrap();
+        assert_eq!((done.status, done.progress), (JobStatus::Done, 1.0));
+    }
+}
@@ -1,3 +1,16 @@
+pub mod ansi;
+pub mod art_renderer;
 pub mod ascii_conversion_service;
+pub mod bitmap_font;
+pub mod conversion_cache;
+pub mod dithering;
+pub mod glyph_atlas;
+pub mod image_decoding;
+pub mod image_filters;
+pub mod job_queue;
 
-pub use ascii_conversion_service::AsciiConversionService;
+pub use art_renderer::{ArtRenderer, RenderError};
+pub use ascii_conversion_service::{AsciiConversionService, AsciiRendering};
+pub use bitmap_font::BitmapFont;
+pub use conversion_cache::ConversionCache;
+pub use job_queue::{JobHandle, JobQueue, JobQueueError};
@@ -0,0 +1,137 @@
+use crate::{
+    application::use_cases::{
+        convert_image_to_ascii::ConvertImageError,
+        upload_and_convert::{
+            UploadAndConvertError, UploadAndConvertRequest, UploadAndConvertResponse,
+        },
+        upload_image::UploadImageRequest,
+        UploadAndConvertUseCase,
+    },
+    domain::{
+        repositories::{AsciiArtRepository, ImageRepository},
+        value_objects::ConversionConfig,
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::{sync::Semaphore, task::JoinSet};
+
+/// Most files accepted in one batch
+pub const MAX_BATCH_FILES: usize = 500;
+
+#[derive(Error, Debug)]
+pub enum BatchConvertError {
+    #[error("No images in the batch")]
+    Empty,
+    #[error("Too many images in the batch (max: {max})")]
+    TooManyFiles { max: usize },
+    #[error("Invalid conversion configuration")]
+    InvalidConfig,
+}
+
+/// Use case for uploading and converting several images with the same settings
+pub struct BatchConvertUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
+    upload_and_convert_use_case: Arc<UploadAndConvertUseCase<IR, AR>>,
+    workers: Arc<Semaphore>,
+}
+
+#[derive(Debug)]
+pub struct BatchConvertRequest {
+    pub files: Vec<UploadImageRequest>,
+    pub config: ConversionConfig,
+    /// Keep the uploaded originals, as for a single upload and convert
+    pub persist_images: bool,
+}
+
+/// Outcome for one file of a batch
+#[derive(Debug)]
+pub struct BatchItemResult {
+    pub filename: String,
+    pub result: Result<UploadAndConvertResponse, UploadAndConvertError>,
+}
+
+#[derive(Debug)]
+pub struct BatchConvertResponse {
+    /// One result per file, in the order of the request
+    pub items: Vec<BatchItemResult>,
+}
+
+impl<IR, AR> BatchConvertUseCase<IR, AR>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
+    /// Create a new batch convert use case converting at most `workers` files at a time
+    pub fn new(
+        upload_and_convert_use_case: Arc<UploadAndConvertUseCase<IR, AR>>,
+        workers: usize,
+    ) -> Self {
+        Self {
+            upload_and_convert_use_case,
+            workers: Arc::new(Semaphore::new(workers.max(1))),
+        }
+    }
+
+    /// Execute the batch convert use case. Files are converted in parallel, and one
+    /// failing does not affect the others.
+    pub async fn execute(
+        &self,
+        request: BatchConvertRequest,
+    ) -> Result<BatchConvertResponse, BatchConvertError> {
+        if request.files.is_empty() {
+            return Err(BatchConvertError::Empty);
+        }
+        if request.files.len() > MAX_BATCH_FILES {
+            return Err(BatchConvertError::TooManyFiles {
+                max: MAX_BATCH_FILES,
+            });
+        }
+        if !request.config.is_valid() {
+            return Err(BatchConvertError::InvalidConfig);
+        }
+
+        let mut tasks = JoinSet::new();
+        let mut filenames = Vec::with_capacity(request.files.len());
+        for (index, upload) in request.files.into_iter().enumerate() {
+            filenames.push(upload.filename.clone());
+
+            let use_case = Arc::clone(&self.upload_and_convert_use_case);
+            let workers = Arc::clone(&self.workers);
+            let item = UploadAndConvertRequest {
+                upload,
+                config: request.config.clone(),
+                persist_image: request.persist_images,
+            };
+            tasks.spawn(async move {
+                let _worker = workers
+                    .acquire_owned()
+                    .await
+                    .expect("worker semaphore is never closed");
+                (index, use_case.execute(item).await)
+            });
+        }
+
+        let mut results: Vec<Option<_>> = filenames.iter().map(|_| None).collect();
+        while let Some(joined) = tasks.join_next().await {
+            // Tasks only end early by panicking, which leaves their slot empty
+            if let Ok((index, result)) = joined {
+                results[index] = Some(result);
+            }
+        }
+
+        let items = filenames
+            .into_iter()
+            .zip(results)
+            .map(|(filename, result)| BatchItemResult {
+                filename,
+                result: result.unwrap_or_else(|| {
+                    Err(UploadAndConvertError::Convert(
+                        ConvertImageError::ConversionFailed("Conversion task failed".to_string()),
+                    ))
+                }),
+            })
+            .collect();
+
+        Ok(BatchConvertResponse { items })
+    }
+}
@@ -0,0 +1,28 @@
+use crate::{application::services::JobQueue, domain::entities::ConversionJob};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum CancelJobError {
+    #[error("Job not found")]
+    NotFound,
+}
+
+/// Use case for cancelling a background job
+pub struct CancelJobUseCase {
+    job_queue: Arc<JobQueue>,
+}
+
+impl CancelJobUseCase {
+    /// Create a new cancel job use case
+    pub fn new(job_queue: Arc<JobQueue>) -> Self {
+        Self { job_queue }
+    }
+
+    /// Execute the cancel job use case, returning the job as it stands. A running job
+    /// stops before its next frame, so it may still finish if it was nearly done.
+    pub fn execute(&self, id: Uuid) -> Result<ConversionJob, CancelJobError> {
+        self.job_queue.cancel(id).ok_or(CancelJobError::NotFound)
+    }
+}
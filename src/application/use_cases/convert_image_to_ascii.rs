@@ -1,11 +1,15 @@
 use crate::{
-    application::services::AsciiConversionService,
+    application::services::{
+        ascii_conversion_service::ConversionError, AsciiConversionService, AsciiRendering,
+        ConversionCache,
+    },
     domain::{
-        entities::AsciiArt,
+        entities::{AsciiArt, AsciiFrame, ImageData},
         repositories::{AsciiArtRepository, ImageRepository},
         value_objects::ConversionConfig,
     },
 };
+use image::ImageError;
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -18,15 +22,22 @@ pub enum ConvertImageError {
     InvalidConfig,
     #[error("Conversion failed: {0}")]
     ConversionFailed(String),
+    #[error("Conversion cancelled")]
+    Cancelled,
+    /// The image or the requested output is too large to convert within the limits
+    #[error("{0}")]
+    LimitsExceeded(String),
     #[error("Repository error: {0}")]
     Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
 
-/// Use case for converting images to ASCII art
+/// Use case for converting images to ASCII art. Repeating a conversion of an image
+/// with the same configuration returns the stored result instead of converting again.
 pub struct ConvertImageToAsciiUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
     image_repository: Arc<IR>,
     ascii_art_repository: Arc<AR>,
     conversion_service: Arc<AsciiConversionService>,
+    cache: Arc<ConversionCache>,
 }
 
 #[derive(Debug)]
@@ -37,10 +48,15 @@ pub struct ConvertImageRequest {
 
 #[derive(Debug)]
 pub struct ConvertImageResponse {
-    pub ascii_art_id: Uuid,
+    /// ID of the stored conversion, `None` for conversions of images that aren't stored
+    pub ascii_art_id: Option<Uuid>,
     pub content: String,
     pub width: u32,
     pub height: u32,
+    /// Frames of an animated source, empty for still images
+    pub frames: Vec<AsciiFrame>,
+    /// An earlier identical conversion was reused rather than converting again
+    pub cached: bool,
 }
 
 impl<IR: ImageRepository, AR: AsciiArtRepository> ConvertImageToAsciiUseCase<IR, AR> {
@@ -49,25 +65,42 @@ impl<IR: ImageRepository, AR: AsciiArtRepository> ConvertImageToAsciiUseCase<IR,
         image_repository: Arc<IR>,
         ascii_art_repository: Arc<AR>,
         conversion_service: Arc<AsciiConversionService>,
+        cache: Arc<ConversionCache>,
     ) -> Self {
         Self {
             image_repository,
             ascii_art_repository,
             conversion_service,
+            cache,
         }
     }
 
-    /// Execute the convert image use case
+    /// Forget the cached conversions of a deleted image
+    pub fn invalidate_image(&self, image_id: Uuid) {
+        self.cache.invalidate_image(image_id);
+    }
+
+    /// Execute the convert image use case, see [`Self::execute_with_progress`]
     pub async fn execute(
         &self,
         request: ConvertImageRequest,
     ) -> Result<ConvertImageResponse, ConvertImageError> {
-        // Validate configuration
+        self.execute_with_progress(request, |_, _| true).await
+    }
+
+    /// Execute the convert image use case on the blocking thread pool, keeping the async
+    /// runtime responsive. `on_progress` is called with the number of finished and total
+    /// frames and cancels the conversion by returning false; it isn't called when the
+    /// result comes from the cache.
+    pub async fn execute_with_progress(
+        &self,
+        request: ConvertImageRequest,
+        on_progress: impl FnMut(usize, usize) -> bool + Send + 'static,
+    ) -> Result<ConvertImageResponse, ConvertImageError> {
         if !request.config.is_valid() {
             return Err(ConvertImageError::InvalidConfig);
         }
 
-        // Get image from repository
         let image_data = self
             .image_repository
             .find_by_id(request.image_id)
@@ -75,38 +108,121 @@ impl<IR: ImageRepository, AR: AsciiArtRepository> ConvertImageToAsciiUseCase<IR,
             .map_err(|e| ConvertImageError::Repository(Box::new(e)))?
             .ok_or(ConvertImageError::ImageNotFound)?;
 
-        // Convert image to ASCII
-        let ascii_content = self
-            .conversion_service
-            .convert_to_ascii(&image_data, &request.config)
+        if let Some(response) = self.cached(&request).await? {
+            return Ok(response);
+        }
+
+        let rendering = self
+            .render(image_data, request.config.clone(), on_progress)
+            .await?;
+        self.save(request, rendering).await
+    }
+
+    /// Convert an image that isn't stored, without storing or caching the conversion
+    pub async fn convert_unsaved(
+        &self,
+        image_data: ImageData,
+        config: ConversionConfig,
+    ) -> Result<ConvertImageResponse, ConvertImageError> {
+        if !config.is_valid() {
+            return Err(ConvertImageError::InvalidConfig);
+        }
+
+        let rendering = self.render(image_data, config, |_, _| true).await?;
+        Ok(ConvertImageResponse {
+            ascii_art_id: None,
+            content: rendering.content,
+            width: rendering.columns,
+            height: rendering.rows,
+            frames: rendering.frames,
+            cached: false,
+        })
+    }
+
+    /// Run the conversion on the blocking thread pool
+    async fn render(
+        &self,
+        image_data: ImageData,
+        config: ConversionConfig,
+        on_progress: impl FnMut(usize, usize) -> bool + Send + 'static,
+    ) -> Result<AsciiRendering, ConvertImageError> {
+        let conversion_service = Arc::clone(&self.conversion_service);
+        tokio::task::spawn_blocking(move || {
+            conversion_service.convert_with_progress(&image_data, &config, on_progress)
+        })
+        .await
+        .map_err(|e| ConvertImageError::ConversionFailed(e.to_string()))?
+        .map_err(|e| match e {
+            ConversionError::Cancelled => ConvertImageError::Cancelled,
+            e @ (ConversionError::OutputTooLarge { .. }
+            | ConversionError::ImageDecodeError(ImageError::Limits(_))) => {
+                ConvertImageError::LimitsExceeded(e.to_string())
+            }
+            e => ConvertImageError::ConversionFailed(e.to_string()),
+        })
+    }
+
+    /// Stored result of an earlier identical conversion, if it is still around
+    async fn cached(
+        &self,
+        request: &ConvertImageRequest,
+    ) -> Result<Option<ConvertImageResponse>, ConvertImageError> {
+        let Some(ascii_art_id) = self.cache.get(request.image_id, &request.config) else {
+            return Ok(None);
+        };
+
+        let ascii_art = self
+            .ascii_art_repository
+            .find_by_id(ascii_art_id)
             .await
-            .map_err(|e| ConvertImageError::ConversionFailed(e.to_string()))?;
+            .map_err(|e| ConvertImageError::Repository(Box::new(e)))?;
+        let Some(ascii_art) = ascii_art else {
+            // The conversion was deleted since
+            self.cache.remove(request.image_id, &request.config);
+            return Ok(None);
+        };
 
-        // Calculate ASCII dimensions
-        let lines: Vec<&str> = ascii_content.lines().collect();
-        let height = lines.len() as u32;
-        let width = lines.first().map(|line| line.len()).unwrap_or(0) as u32;
+        Ok(Some(ConvertImageResponse {
+            ascii_art_id: Some(ascii_art.id),
+            content: ascii_art.content,
+            width: ascii_art.width,
+            height: ascii_art.height,
+            frames: ascii_art.frames,
+            cached: true,
+        }))
+    }
 
-        // Create ASCII art entity
+    /// Store a finished conversion
+    async fn save(
+        &self,
+        request: ConvertImageRequest,
+        rendering: AsciiRendering,
+    ) -> Result<ConvertImageResponse, ConvertImageError> {
+        // Create ASCII art entity, sized in character cells rather than bytes
         let ascii_art = AsciiArt::new(
             request.image_id,
-            ascii_content,
-            width,
-            height,
+            rendering.content,
+            rendering.columns,
+            rendering.rows,
             request.config.detail_level,
-        );
+        )
+        .with_frames(rendering.frames);
 
         // Save ASCII art
         self.ascii_art_repository
             .save(&ascii_art)
             .await
             .map_err(|e| ConvertImageError::Repository(Box::new(e)))?;
+        self.cache
+            .insert(request.image_id, &request.config, ascii_art.id);
 
         Ok(ConvertImageResponse {
-            ascii_art_id: ascii_art.id,
+            ascii_art_id: Some(ascii_art.id),
             content: ascii_art.content,
             width: ascii_art.width,
             height: ascii_art.height,
+            frames: ascii_art.frames,
+            cached: false,
         })
     }
 }
@@ -0,0 +1,73 @@
+use crate::domain::repositories::AsciiArtRepository;
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum DeleteAsciiArtError {
+    #[error("ASCII art not found")]
+    NotFound,
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for deleting a stored conversion
+pub struct DeleteAsciiArtUseCase<AR: AsciiArtRepository> {
+    ascii_art_repository: Arc<AR>,
+}
+
+impl<AR: AsciiArtRepository> DeleteAsciiArtUseCase<AR> {
+    /// Create a new delete ASCII art use case
+    pub fn new(ascii_art_repository: Arc<AR>) -> Self {
+        Self {
+            ascii_art_repository,
+        }
+    }
+
+    /// Execute the delete ASCII art use case
+    pub async fn execute(&self, id: Uuid) -> Result<(), DeleteAsciiArtError> {
+        let repository_error = |e: AR::Error| DeleteAsciiArtError::Repository(Box::new(e));
+
+        self.ascii_art_repository
+            .find_by_id(id)
+            .await
+            .map_err(repository_error)?
+            .ok_or(DeleteAsciiArtError::NotFound)?;
+
+        self.ascii_art_repository
+            .delete(id)
+            .await
+            .map_err(repository_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::use_cases::{get_ascii_art::GetAsciiArtError, GetAsciiArtUseCase},
+        domain::entities::{ascii_art::DetailLevel, AsciiArt},
+        infrastructure::repositories::InMemoryAsciiArtRepository,
+    };
+
+    #[tokio::test]
+    async fn deleted_ascii_art_is_no_longer_found() {
+        let repository = Arc::new(InMemoryAsciiArtRepository::new());
+        let get = GetAsciiArtUseCase::new(Arc::clone(&repository));
+        let delete = DeleteAsciiArtUseCase::new(Arc::clone(&repository));
+
+        let art = AsciiArt::new(Uuid::new_v4(), "@".into(), 1, 1, DetailLevel::Low);
+        repository.save(&art).await.unwrap();
+        assert_eq!(get.execute(art.id).await.unwrap().content, "@");
+
+        delete.execute(art.id).await.unwrap();
+        assert!(matches!(
+            get.execute(art.id).await,
+            Err(GetAsciiArtError::NotFound)
+        ));
+        assert!(matches!(
+            delete.execute(art.id).await,
+            Err(DeleteAsciiArtError::NotFound)
+        ));
+    }
+}
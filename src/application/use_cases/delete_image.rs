@@ -0,0 +1,218 @@
+use crate::{
+    application::services::ConversionCache,
+    domain::repositories::{AsciiArtRepository, ImageRepository},
+};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum DeleteImageError {
+    #[error("Image not found")]
+    NotFound,
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for deleting an uploaded image together with its conversions
+pub struct DeleteImageUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
+    image_repository: Arc<IR>,
+    ascii_art_repository: Arc<AR>,
+    conversion_cache: Arc<ConversionCache>,
+}
+
+#[derive(Debug)]
+pub struct DeleteImageResponse {
+    pub deleted_conversions: usize,
+    /// Uploads still sharing the image; it is only deleted once this reaches zero
+    pub remaining_references: u32,
+}
+
+impl<IR: ImageRepository, AR: AsciiArtRepository> DeleteImageUseCase<IR, AR> {
+    /// Create a new delete image use case
+    pub fn new(
+        image_repository: Arc<IR>,
+        ascii_art_repository: Arc<AR>,
+        conversion_cache: Arc<ConversionCache>,
+    ) -> Self {
+        Self {
+            image_repository,
+            ascii_art_repository,
+            conversion_cache,
+        }
+    }
+
+    /// Execute the delete image use case. An image shared by several identical uploads
+    /// only loses a reference, and keeps its conversions, until the last one is deleted.
+    pub async fn execute(&self, id: Uuid) -> Result<DeleteImageResponse, DeleteImageError> {
+        // Give up the reference before anything else, so that only the request taking the
+        // count to zero removes the conversions, however many deletes run at once
+        let remaining_references = self
+            .image_repository
+            .release(id)
+            .await
+            .map_err(|e| DeleteImageError::Repository(Box::new(e)))?
+            .ok_or(DeleteImageError::NotFound)?;
+        if remaining_references > 0 {
+            return Ok(DeleteImageResponse {
+                deleted_conversions: 0,
+                remaining_references,
+            });
+        }
+
+        let deleted_conversions = self.delete_conversions(id).await?;
+        Ok(DeleteImageResponse {
+            deleted_conversions,
+            remaining_references,
+        })
+    }
+
+    /// Delete the conversions of images the repository expires or evicts on its own, as
+    /// if the images had been deleted
+    pub fn cascade_evictions(self: &Arc<Self>)
+    where
+        IR: 'static,
+        AR: 'static,
+    {
+        // The repository holds the callback, so it mustn't keep the use case alive
+        let use_case = Arc::downgrade(self);
+        self.image_repository.on_evict(Arc::new(move |id| {
+            let Some(use_case) = use_case.upgrade() else {
+                return;
+            };
+            tokio::spawn(async move {
+                if let Err(e) = use_case.delete_conversions(id).await {
+                    tracing::warn!(
+                        "Failed to delete the conversions of evicted image {}: {}",
+                        id,
+                        e
+                    );
+                }
+            });
+        }));
+    }
+
+    /// Forget the cached conversions of an image that is gone and delete the stored ones,
+    /// returning how many there were
+    async fn delete_conversions(&self, id: Uuid) -> Result<usize, DeleteImageError> {
+        self.conversion_cache.invalidate_image(id);
+        let conversions = self
+            .ascii_art_repository
+            .find_by_image_id(id)
+            .await
+            .map_err(|e| DeleteImageError::Repository(Box::new(e)))?;
+        for ascii_art in &conversions {
+            self.ascii_art_repository
+                .delete(ascii_art.id)
+                .await
+                .map_err(|e| DeleteImageError::Repository(Box::new(e)))?;
+        }
+        Ok(conversions.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::{
+            entities::{ascii_art::DetailLevel, AsciiArt, ImageData},
+            value_objects::ConversionConfig,
+        },
+        infrastructure::repositories::{
+            InMemoryAsciiArtRepository, InMemoryImageRepository, MemoryLimits,
+        },
+    };
+
+    #[tokio::test]
+    async fn shared_images_keep_their_conversions_until_the_last_delete() {
+        let images = Arc::new(InMemoryImageRepository::new());
+        let conversions = Arc::new(InMemoryAsciiArtRepository::new());
+        let use_case = DeleteImageUseCase::new(
+            Arc::clone(&images),
+            Arc::clone(&conversions),
+            Arc::new(ConversionCache::default()),
+        );
+
+        let image = ImageData::new("a.png".into(), "image/png".into(), vec![1, 2, 3], 1, 1);
+        images.save(&image).await.unwrap();
+        images.retain(image.id).await.unwrap();
+        let ascii_art = AsciiArt::new(image.id, "#".into(), 1, 1, DetailLevel::Low);
+        conversions.save(&ascii_art).await.unwrap();
+
+        let response = use_case.execute(image.id).await.unwrap();
+        assert_eq!(
+            (response.remaining_references, response.deleted_conversions),
+            (1, 0)
+        );
+        assert!(conversions
+            .find_by_id(ascii_art.id)
+            .await
+            .unwrap()
+            .is_some());
+
+        let response = use_case.execute(image.id).await.unwrap();
+        assert_eq!(
+            (response.remaining_references, response.deleted_conversions),
+            (0, 1)
+        );
+        assert!(images.find_by_id(image.id).await.unwrap().is_none());
+        assert!(conversions
+            .find_by_id(ascii_art.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert!(matches!(
+            use_case.execute(image.id).await,
+            Err(DeleteImageError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn evicted_images_take_their_conversions_and_cache_entries_along() {
+        let image = |byte| ImageData::new("a.png".into(), "image/png".into(), vec![byte; 64], 1, 1);
+        let (first, second) = (image(1), image(2));
+        // A quota with room for one of the images
+        let measure = InMemoryImageRepository::new();
+        measure.save(&first).await.unwrap();
+        let images = Arc::new(InMemoryImageRepository::with_limits(MemoryLimits {
+            ttl: None,
+            max_bytes: Some(measure.storage_stats().unwrap().bytes + 1),
+        }));
+        let conversions = Arc::new(InMemoryAsciiArtRepository::new());
+        let cache = Arc::new(ConversionCache::default());
+        let use_case = Arc::new(DeleteImageUseCase::new(
+            Arc::clone(&images),
+            Arc::clone(&conversions),
+            Arc::clone(&cache),
+        ));
+        use_case.cascade_evictions();
+
+        images.save(&first).await.unwrap();
+        let ascii_art = AsciiArt::new(first.id, "#".into(), 1, 1, DetailLevel::Low);
+        conversions.save(&ascii_art).await.unwrap();
+        let config = ConversionConfig::default();
+        cache.insert(first.id, &config, ascii_art.id);
+
+        // Storing the second image evicts the first
+        images.save(&second).await.unwrap();
+        assert!(images.find_by_id(first.id).await.unwrap().is_none());
+        for _ in 0..100 {
+            if conversions
+                .find_by_id(ascii_art.id)
+                .await
+                .unwrap()
+                .is_none()
+            {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(conversions
+            .find_by_id(ascii_art.id)
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(cache.get(first.id, &config), None);
+    }
+}
@@ -0,0 +1,121 @@
+use crate::{
+    application::services::{ansi::strip_ansi, ArtRenderer, RenderError},
+    domain::{
+        repositories::AsciiArtRepository,
+        value_objects::{ExportFormat, ExportOptions},
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::task::JoinError;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum ExportAsciiArtError {
+    #[error("ASCII art not found")]
+    NotFound,
+    #[error("Invalid export options")]
+    InvalidOptions,
+    #[error("Export failed: {0}")]
+    RenderFailed(#[from] RenderError),
+    #[error("Export task failed: {0}")]
+    TaskFailed(#[from] JoinError),
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for exporting stored ASCII art as text, an image or HTML
+pub struct ExportAsciiArtUseCase<AR: AsciiArtRepository> {
+    ascii_art_repository: Arc<AR>,
+    renderer: Arc<ArtRenderer>,
+}
+
+#[derive(Debug)]
+pub struct ExportAsciiArtRequest {
+    pub ascii_art_id: Uuid,
+    pub format: ExportFormat,
+    pub options: ExportOptions,
+}
+
+#[derive(Debug)]
+pub struct ExportAsciiArtResponse {
+    pub data: Vec<u8>,
+    pub content_type: &'static str,
+}
+
+impl<AR: AsciiArtRepository> ExportAsciiArtUseCase<AR> {
+    /// Create a new export ASCII art use case
+    pub fn new(ascii_art_repository: Arc<AR>, renderer: Arc<ArtRenderer>) -> Self {
+        Self {
+            ascii_art_repository,
+            renderer,
+        }
+    }
+
+    /// Execute the export ASCII art use case; animations export their first frame
+    pub async fn execute(
+        &self,
+        request: ExportAsciiArtRequest,
+    ) -> Result<ExportAsciiArtResponse, ExportAsciiArtError> {
+        if !request.options.is_valid() {
+            return Err(ExportAsciiArtError::InvalidOptions);
+        }
+
+        let ascii_art = self
+            .ascii_art_repository
+            .find_by_id(request.ascii_art_id)
+            .await
+            .map_err(|e| ExportAsciiArtError::Repository(Box::new(e)))?
+            .ok_or(ExportAsciiArtError::NotFound)?;
+
+        self.export_content(ascii_art.content, request.format, request.options)
+            .await
+    }
+
+    /// Export ASCII art that isn't stored, such as a conversion of a discarded upload
+    pub async fn export_content(
+        &self,
+        content: String,
+        format: ExportFormat,
+        options: ExportOptions,
+    ) -> Result<ExportAsciiArtResponse, ExportAsciiArtError> {
+        if !options.is_valid() {
+            return Err(ExportAsciiArtError::InvalidOptions);
+        }
+
+        let data = match format {
+            ExportFormat::Text | ExportFormat::Ansi => {
+                render(&self.renderer, format, &content, &options)?
+            }
+            _ => {
+                // Rasterizing and encoding large art takes a while, so keep it off the
+                // async executor
+                let renderer = Arc::clone(&self.renderer);
+                tokio::task::spawn_blocking(move || render(&renderer, format, &content, &options))
+                    .await??
+            }
+        };
+
+        Ok(ExportAsciiArtResponse {
+            data,
+            content_type: format.mime_type(),
+        })
+    }
+}
+
+/// Draw the art in an export format
+fn render(
+    renderer: &ArtRenderer,
+    format: ExportFormat,
+    content: &str,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, RenderError> {
+    Ok(match format {
+        ExportFormat::Png => renderer.render_png(content, options)?,
+        ExportFormat::Svg => renderer.render_svg(content, options).into_bytes(),
+        ExportFormat::Html => renderer.render_html(content, options).into_bytes(),
+        ExportFormat::HtmlSnippet => renderer.render_html_snippet(content, options).into_bytes(),
+        ExportFormat::Text => strip_ansi(content).into_bytes(),
+        ExportFormat::Ansi => content.as_bytes().to_vec(),
+    })
+}
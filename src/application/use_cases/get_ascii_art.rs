@@ -0,0 +1,35 @@
+use crate::domain::{entities::AsciiArt, repositories::AsciiArtRepository};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum GetAsciiArtError {
+    #[error("ASCII art not found")]
+    NotFound,
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for fetching a stored conversion
+pub struct GetAsciiArtUseCase<AR: AsciiArtRepository> {
+    ascii_art_repository: Arc<AR>,
+}
+
+impl<AR: AsciiArtRepository> GetAsciiArtUseCase<AR> {
+    /// Create a new get ASCII art use case
+    pub fn new(ascii_art_repository: Arc<AR>) -> Self {
+        Self {
+            ascii_art_repository,
+        }
+    }
+
+    /// Execute the get ASCII art use case
+    pub async fn execute(&self, id: Uuid) -> Result<AsciiArt, GetAsciiArtError> {
+        self.ascii_art_repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| GetAsciiArtError::Repository(Box::new(e)))?
+            .ok_or(GetAsciiArtError::NotFound)
+    }
+}
@@ -0,0 +1,33 @@
+use crate::domain::{entities::ImageData, repositories::ImageRepository};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum GetImageError {
+    #[error("Image not found")]
+    NotFound,
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for fetching an uploaded image
+pub struct GetImageUseCase<IR: ImageRepository> {
+    image_repository: Arc<IR>,
+}
+
+impl<IR: ImageRepository> GetImageUseCase<IR> {
+    /// Create a new get image use case
+    pub fn new(image_repository: Arc<IR>) -> Self {
+        Self { image_repository }
+    }
+
+    /// Execute the get image use case
+    pub async fn execute(&self, id: Uuid) -> Result<ImageData, GetImageError> {
+        self.image_repository
+            .find_by_id(id)
+            .await
+            .map_err(|e| GetImageError::Repository(Box::new(e)))?
+            .ok_or(GetImageError::NotFound)
+    }
+}
@@ -0,0 +1,27 @@
+use crate::{application::services::JobQueue, domain::entities::ConversionJob};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum GetJobError {
+    #[error("Job not found")]
+    NotFound,
+}
+
+/// Use case for polling a background job
+pub struct GetJobUseCase {
+    job_queue: Arc<JobQueue>,
+}
+
+impl GetJobUseCase {
+    /// Create a new get job use case
+    pub fn new(job_queue: Arc<JobQueue>) -> Self {
+        Self { job_queue }
+    }
+
+    /// Execute the get job use case
+    pub fn execute(&self, id: Uuid) -> Result<ConversionJob, GetJobError> {
+        self.job_queue.get(id).ok_or(GetJobError::NotFound)
+    }
+}
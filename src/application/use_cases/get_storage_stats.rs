@@ -0,0 +1,33 @@
+use crate::domain::repositories::{AsciiArtRepository, ImageRepository, StorageStats};
+use std::sync::Arc;
+
+/// Use case for reporting how full the repositories are and what they evicted
+pub struct GetStorageStatsUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
+    image_repository: Arc<IR>,
+    ascii_art_repository: Arc<AR>,
+}
+
+/// Counters of each repository, `None` for backends that never drop entries themselves
+#[derive(Debug)]
+pub struct StorageStatsResponse {
+    pub images: Option<StorageStats>,
+    pub ascii_art: Option<StorageStats>,
+}
+
+impl<IR: ImageRepository, AR: AsciiArtRepository> GetStorageStatsUseCase<IR, AR> {
+    /// Create a new get storage stats use case
+    pub fn new(image_repository: Arc<IR>, ascii_art_repository: Arc<AR>) -> Self {
+        Self {
+            image_repository,
+            ascii_art_repository,
+        }
+    }
+
+    /// Execute the get storage stats use case
+    pub fn execute(&self) -> StorageStatsResponse {
+        StorageStatsResponse {
+            images: self.image_repository.storage_stats(),
+            ascii_art: self.ascii_art_repository.storage_stats(),
+        }
+    }
+}
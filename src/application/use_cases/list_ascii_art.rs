@@ -0,0 +1,117 @@
+use crate::domain::{
+    entities::AsciiArt,
+    repositories::{AsciiArtQuery, AsciiArtRepository},
+};
+use std::sync::Arc;
+use thiserror::Error;
+
+/// Page size used when the caller does not ask for one
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// Largest page a caller may ask for
+pub const MAX_PAGE_SIZE: usize = 200;
+
+#[derive(Error, Debug)]
+pub enum ListAsciiArtError {
+    #[error("Invalid date range: start must be before end")]
+    InvalidDateRange,
+    #[error("Invalid page size (must be between 1 and {max})")]
+    InvalidLimit { max: usize },
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for searching previously converted ASCII art
+pub struct ListAsciiArtUseCase<AR: AsciiArtRepository> {
+    ascii_art_repository: Arc<AR>,
+}
+
+impl<AR: AsciiArtRepository> ListAsciiArtUseCase<AR> {
+    /// Create a new list ASCII art use case
+    pub fn new(ascii_art_repository: Arc<AR>) -> Self {
+        Self {
+            ascii_art_repository,
+        }
+    }
+
+    /// Execute the list ASCII art use case, returning matches newest first
+    pub async fn execute(&self, query: AsciiArtQuery) -> Result<Vec<AsciiArt>, ListAsciiArtError> {
+        if query
+            .limit
+            .is_some_and(|limit| limit == 0 || limit > MAX_PAGE_SIZE)
+        {
+            return Err(ListAsciiArtError::InvalidLimit { max: MAX_PAGE_SIZE });
+        }
+
+        if let (Some(after), Some(before)) = (query.created_after, query.created_before) {
+            if after >= before {
+                return Err(ListAsciiArtError::InvalidDateRange);
+            }
+        }
+
+        self.ascii_art_repository
+            .list(&query)
+            .await
+            .map_err(|e| ListAsciiArtError::Repository(Box::new(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::entities::ascii_art::DetailLevel,
+        infrastructure::repositories::InMemoryAsciiArtRepository,
+    };
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn pages_are_newest_first_and_bad_queries_are_refused() {
+        let repository = Arc::new(InMemoryAsciiArtRepository::new());
+        let use_case = ListAsciiArtUseCase::new(Arc::clone(&repository));
+        let (image_id, start) = (Uuid::new_v4(), chrono::Utc::now());
+
+        let mut ids = Vec::new();
+        for minutes in 0..3 {
+            let mut art = AsciiArt::new(image_id, String::new(), 1, 1, DetailLevel::High);
+            art.created_at = start + chrono::Duration::minutes(minutes);
+            repository.save(&art).await.unwrap();
+            ids.push(art.id);
+        }
+        // Another image's conversion is filtered out
+        let other = AsciiArt::new(Uuid::new_v4(), String::new(), 1, 1, DetailLevel::High);
+        repository.save(&other).await.unwrap();
+
+        let page = use_case
+            .execute(AsciiArtQuery {
+                image_id: Some(image_id),
+                offset: 1,
+                limit: Some(5),
+                ..AsciiArtQuery::default()
+            })
+            .await
+            .unwrap();
+        let page: Vec<_> = page.into_iter().map(|art| art.id).collect();
+        assert_eq!(page, vec![ids[1], ids[0]]);
+
+        for limit in [0, MAX_PAGE_SIZE + 1] {
+            let query = AsciiArtQuery {
+                limit: Some(limit),
+                ..AsciiArtQuery::default()
+            };
+            assert!(matches!(
+                use_case.execute(query).await,
+                Err(ListAsciiArtError::InvalidLimit { .. })
+            ));
+        }
+        let query = AsciiArtQuery {
+            created_after: Some(start),
+            created_before: Some(start),
+            ..AsciiArtQuery::default()
+        };
+        assert!(matches!(
+            use_case.execute(query).await,
+            Err(ListAsciiArtError::InvalidDateRange)
+        ));
+    }
+}
@@ -1,5 +1,31 @@
+pub mod batch_convert;
+pub mod cancel_job;
 pub mod convert_image_to_ascii;
+pub mod delete_ascii_art;
+pub mod delete_image;
+pub mod export_ascii_art;
+pub mod get_ascii_art;
+pub mod get_image;
+pub mod get_job;
+pub mod get_storage_stats;
+pub mod list_ascii_art;
+pub mod preview_conversion;
+pub mod submit_conversion_job;
+pub mod upload_and_convert;
 pub mod upload_image;
 
+pub use batch_convert::BatchConvertUseCase;
+pub use cancel_job::CancelJobUseCase;
 pub use convert_image_to_ascii::ConvertImageToAsciiUseCase;
+pub use delete_ascii_art::DeleteAsciiArtUseCase;
+pub use delete_image::DeleteImageUseCase;
+pub use export_ascii_art::ExportAsciiArtUseCase;
+pub use get_ascii_art::GetAsciiArtUseCase;
+pub use get_image::GetImageUseCase;
+pub use get_job::GetJobUseCase;
+pub use get_storage_stats::GetStorageStatsUseCase;
+pub use list_ascii_art::ListAsciiArtUseCase;
+pub use preview_conversion::PreviewConversionUseCase;
+pub use submit_conversion_job::SubmitConversionJobUseCase;
+pub use upload_and_convert::UploadAndConvertUseCase;
 pub use upload_image::UploadImageUseCase;
@@ -0,0 +1,58 @@
+use crate::{
+    application::services::{
+        ascii_conversion_service::ConversionError, AsciiConversionService, AsciiRendering,
+    },
+    domain::{entities::ImageData, value_objects::ConversionConfig},
+};
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum PreviewConversionError {
+    #[error("Invalid conversion configuration")]
+    InvalidConfig,
+    #[error("Conversion failed: {0}")]
+    ConversionFailed(String),
+    #[error("Conversion cancelled")]
+    Cancelled,
+}
+
+/// Use case for converting an image without storing the result, for live previews
+pub struct PreviewConversionUseCase {
+    conversion_service: Arc<AsciiConversionService>,
+}
+
+impl PreviewConversionUseCase {
+    /// Create a new preview conversion use case
+    pub fn new(conversion_service: Arc<AsciiConversionService>) -> Self {
+        Self { conversion_service }
+    }
+
+    /// Execute the preview conversion use case on the blocking thread pool. Setting
+    /// `cancelled` stops the conversion at its next stage or row of output.
+    pub async fn execute(
+        &self,
+        image: Arc<ImageData>,
+        config: ConversionConfig,
+        cancelled: Arc<AtomicBool>,
+    ) -> Result<AsciiRendering, PreviewConversionError> {
+        if !config.is_valid() {
+            return Err(PreviewConversionError::InvalidConfig);
+        }
+
+        let conversion_service = Arc::clone(&self.conversion_service);
+        tokio::task::spawn_blocking(move || {
+            conversion_service
+                .convert_with_progress(&image, &config, |_, _| !cancelled.load(Ordering::Relaxed))
+        })
+        .await
+        .map_err(|e| PreviewConversionError::ConversionFailed(e.to_string()))?
+        .map_err(|e| match e {
+            ConversionError::Cancelled => PreviewConversionError::Cancelled,
+            e => PreviewConversionError::ConversionFailed(e.to_string()),
+        })
+    }
+}
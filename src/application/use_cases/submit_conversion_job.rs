@@ -0,0 +1,104 @@
+use crate::{
+    application::{
+        services::{JobQueue, JobQueueError},
+        use_cases::{
+            convert_image_to_ascii::{
+                ConvertImageError, ConvertImageRequest, ConvertImageResponse,
+            },
+            ConvertImageToAsciiUseCase,
+        },
+    },
+    domain::{
+        entities::ConversionJob,
+        repositories::{AsciiArtRepository, ImageRepository},
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum SubmitConversionJobError {
+    #[error("Image not found")]
+    ImageNotFound,
+    #[error("Invalid conversion configuration")]
+    InvalidConfig,
+    #[error(transparent)]
+    QueueFull(#[from] JobQueueError),
+    #[error("Repository error: {0}")]
+    Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
+}
+
+/// Use case for converting an image in the background
+pub struct SubmitConversionJobUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
+    image_repository: Arc<IR>,
+    convert_use_case: Arc<ConvertImageToAsciiUseCase<IR, AR>>,
+    job_queue: Arc<JobQueue>,
+}
+
+impl<IR, AR> SubmitConversionJobUseCase<IR, AR>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
+    /// Create a new submit conversion job use case
+    pub fn new(
+        image_repository: Arc<IR>,
+        convert_use_case: Arc<ConvertImageToAsciiUseCase<IR, AR>>,
+        job_queue: Arc<JobQueue>,
+    ) -> Self {
+        Self {
+            image_repository,
+            convert_use_case,
+            job_queue,
+        }
+    }
+
+    /// Execute the submit conversion job use case, returning the queued job. The
+    /// conversion runs once a worker is free and stores its result like a direct one.
+    pub async fn execute(
+        &self,
+        request: ConvertImageRequest,
+    ) -> Result<ConversionJob, SubmitConversionJobError> {
+        if !request.config.is_valid() {
+            return Err(SubmitConversionJobError::InvalidConfig);
+        }
+
+        // Reject unknown images up front rather than with a failed job
+        self.image_repository
+            .find_by_id(request.image_id)
+            .await
+            .map_err(|e| SubmitConversionJobError::Repository(Box::new(e)))?
+            .ok_or(SubmitConversionJobError::ImageNotFound)?;
+
+        let (job, handle) = self.job_queue.enqueue(request.image_id)?;
+
+        let convert_use_case = Arc::clone(&self.convert_use_case);
+        let job_queue = Arc::clone(&self.job_queue);
+        tokio::spawn(async move {
+            let _worker = job_queue.acquire_worker().await;
+            if !handle.start() {
+                return;
+            }
+
+            let progress = handle.clone();
+            let result = convert_use_case
+                .execute_with_progress(request, move |done, total| {
+                    progress.set_progress(done as f32 / total.max(1) as f32);
+                    !progress.is_cancelled()
+                })
+                .await;
+
+            match result {
+                Ok(ConvertImageResponse {
+                    ascii_art_id: Some(ascii_art_id),
+                    ..
+                }) => handle.finish(ascii_art_id),
+                Ok(_) => handle.fail("Conversion was not stored".to_string()),
+                Err(ConvertImageError::Cancelled) => handle.mark_cancelled(),
+                Err(e) => handle.fail(e.to_string()),
+            }
+        });
+
+        Ok(job)
+    }
+}
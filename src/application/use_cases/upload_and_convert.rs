@@ -0,0 +1,281 @@
+use crate::{
+    application::use_cases::{
+        convert_image_to_ascii::{ConvertImageError, ConvertImageRequest, ConvertImageResponse},
+        upload_image::{UploadImageError, UploadImageRequest},
+        ConvertImageToAsciiUseCase, UploadImageUseCase,
+    },
+    domain::{
+        repositories::{AsciiArtRepository, ImageRepository},
+        value_objects::ConversionConfig,
+    },
+};
+use std::sync::Arc;
+use thiserror::Error;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+pub enum UploadAndConvertError {
+    #[error(transparent)]
+    Upload(#[from] UploadImageError),
+    #[error(transparent)]
+    Convert(#[from] ConvertImageError),
+}
+
+/// Use case for uploading an image and converting it in one step
+pub struct UploadAndConvertUseCase<IR: ImageRepository, AR: AsciiArtRepository> {
+    upload_use_case: Arc<UploadImageUseCase<IR>>,
+    convert_use_case: Arc<ConvertImageToAsciiUseCase<IR, AR>>,
+    image_repository: Arc<IR>,
+}
+
+#[derive(Debug)]
+pub struct UploadAndConvertRequest {
+    pub upload: UploadImageRequest,
+    pub config: ConversionConfig,
+    /// Keep the original image and its conversion for later use; otherwise the image is
+    /// converted without storing either
+    pub persist_image: bool,
+}
+
+#[derive(Debug)]
+pub struct UploadAndConvertResponse {
+    /// ID of the stored original, if it was persisted
+    pub image_id: Option<Uuid>,
+    pub conversion: ConvertImageResponse,
+}
+
+impl<IR: ImageRepository, AR: AsciiArtRepository> UploadAndConvertUseCase<IR, AR> {
+    /// Create a new upload and convert use case
+    pub fn new(
+        upload_use_case: Arc<UploadImageUseCase<IR>>,
+        convert_use_case: Arc<ConvertImageToAsciiUseCase<IR, AR>>,
+        image_repository: Arc<IR>,
+    ) -> Self {
+        Self {
+            upload_use_case,
+            convert_use_case,
+            image_repository,
+        }
+    }
+
+    /// Execute the upload and convert use case
+    pub async fn execute(
+        &self,
+        request: UploadAndConvertRequest,
+    ) -> Result<UploadAndConvertResponse, UploadAndConvertError> {
+        // Fail before storing anything the client would never learn the ID of
+        if !request.config.is_valid() {
+            return Err(ConvertImageError::InvalidConfig.into());
+        }
+
+        if !request.persist_image {
+            let image_data = self.upload_use_case.validate(request.upload)?;
+            let conversion = self
+                .convert_use_case
+                .convert_unsaved(image_data, request.config)
+                .await?;
+            return Ok(UploadAndConvertResponse {
+                image_id: None,
+                conversion,
+            });
+        }
+
+        let upload = self.upload_use_case.execute(request.upload).await?;
+
+        let conversion = self
+            .convert_use_case
+            .execute(ConvertImageRequest {
+                image_id: upload.image_id,
+                config: request.config,
+            })
+            .await;
+
+        // A failed conversion never reports the image ID, so don't keep the image either.
+        // Releasing only drops this upload's reference to an image shared with identical
+        // uploads, and failing to only leaves an unreferenced image behind.
+        if conversion.is_err() {
+            if let Ok(Some(0)) = self.image_repository.release(upload.image_id).await {
+                self.convert_use_case.invalidate_image(upload.image_id);
+            }
+        }
+
+        Ok(UploadAndConvertResponse {
+            image_id: Some(upload.image_id),
+            conversion: conversion?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::services::{AsciiConversionService, ConversionCache},
+        domain::{
+            entities::{ascii_art::DetailLevel, ImageData},
+            value_objects::ImageLimits,
+        },
+        infrastructure::repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
+    };
+    use async_trait::async_trait;
+    use image::{DynamicImage, GrayImage};
+    use std::{
+        io::Cursor,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+
+    /// In-memory image repository counting the writes made to it
+    #[derive(Default)]
+    struct CountingImages {
+        images: InMemoryImageRepository,
+        writes: AtomicUsize,
+    }
+
+    impl CountingImages {
+        fn write(&self) {
+            self.writes.fetch_add(1, Ordering::SeqCst);
+        }
+
+        fn writes(&self) -> usize {
+            self.writes.load(Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl ImageRepository for CountingImages {
+        type Error = <InMemoryImageRepository as ImageRepository>::Error;
+
+        async fn save(&self, image: &ImageData) -> Result<(), Self::Error> {
+            self.write();
+            self.images.save(image).await
+        }
+
+        async fn find_by_id(&self, id: Uuid) -> Result<Option<ImageData>, Self::Error> {
+            self.images.find_by_id(id).await
+        }
+
+        async fn find_by_content_hash(&self, hash: &str) -> Result<Option<ImageData>, Self::Error> {
+            self.images.find_by_content_hash(hash).await
+        }
+
+        async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
+            self.write();
+            self.images.delete(id).await
+        }
+
+        async fn retain(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+            self.write();
+            self.images.retain(id).await
+        }
+
+        async fn release(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+            self.write();
+            self.images.release(id).await
+        }
+    }
+
+    type TestUseCase = UploadAndConvertUseCase<CountingImages, InMemoryAsciiArtRepository>;
+
+    /// Upload and convert use case over new repositories, converting within
+    /// `conversion_limits`
+    fn use_case(
+        conversion_limits: ImageLimits,
+    ) -> (
+        TestUseCase,
+        Arc<CountingImages>,
+        Arc<InMemoryAsciiArtRepository>,
+    ) {
+        let images = Arc::new(CountingImages::default());
+        let ascii_art = Arc::new(InMemoryAsciiArtRepository::new());
+        let upload =
+            UploadImageUseCase::new(Arc::clone(&images), 1024 * 1024, ImageLimits::default());
+        let convert = ConvertImageToAsciiUseCase::new(
+            Arc::clone(&images),
+            Arc::clone(&ascii_art),
+            Arc::new(AsciiConversionService::with_limits(conversion_limits)),
+            Arc::new(ConversionCache::new(ConversionCache::DEFAULT_CAPACITY)),
+        );
+        let use_case =
+            UploadAndConvertUseCase::new(Arc::new(upload), Arc::new(convert), Arc::clone(&images));
+        (use_case, images, ascii_art)
+    }
+
+    fn request(persist_image: bool) -> UploadAndConvertRequest {
+        let mut png = Vec::new();
+        DynamicImage::ImageLuma8(GrayImage::new(16, 16))
+            .write_to(&mut Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+        UploadAndConvertRequest {
+            upload: UploadImageRequest {
+                filename: "a.png".into(),
+                content_type: "image/png".into(),
+                data: png,
+            },
+            config: ConversionConfig::new(8, DetailLevel::High),
+            persist_image,
+        }
+    }
+
+    #[tokio::test]
+    async fn unpersisted_uploads_are_converted_without_storing_anything() {
+        let (use_case, images, ascii_art) = use_case(ImageLimits::default());
+
+        let response = use_case.execute(request(false)).await.unwrap();
+        assert_eq!(response.image_id, None);
+        assert_eq!(response.conversion.ascii_art_id, None);
+        assert!(!response.conversion.content.is_empty());
+        assert_eq!(images.writes(), 0);
+        assert_eq!(ascii_art.storage_stats().unwrap().entries, 0);
+
+        // Nor do they touch an identical persisted upload
+        let persisted = use_case.execute(request(true)).await.unwrap();
+        let image_id = persisted.image_id.unwrap();
+        let writes = images.writes();
+        use_case.execute(request(false)).await.unwrap();
+        assert_eq!(images.writes(), writes);
+        let image = images.find_by_id(image_id).await.unwrap().unwrap();
+        assert_eq!(image.ref_count, 1);
+        assert_eq!(ascii_art.storage_stats().unwrap().entries, 1);
+    }
+
+    #[tokio::test]
+    async fn failed_conversions_leave_no_image_behind() {
+        let tiny = ImageLimits {
+            max_width: 4,
+            max_height: 4,
+            max_pixels: 16,
+        };
+        let (use_case, images, _) = use_case(tiny);
+
+        for persist_image in [true, false] {
+            let result = use_case.execute(request(persist_image)).await;
+            assert!(matches!(
+                result,
+                Err(UploadAndConvertError::Convert(
+                    ConvertImageError::LimitsExceeded(_)
+                ))
+            ));
+            assert_eq!(images.images.storage_stats().unwrap().entries, 0);
+        }
+
+        // Invalid settings and uploads are refused before anything is written
+        let writes = images.writes();
+        let mut invalid = request(true);
+        invalid.config.width = 0;
+        assert!(matches!(
+            use_case.execute(invalid).await,
+            Err(UploadAndConvertError::Convert(
+                ConvertImageError::InvalidConfig
+            ))
+        ));
+        let mut invalid = request(false);
+        invalid.upload.data.truncate(40);
+        assert!(matches!(
+            use_case.execute(invalid).await,
+            Err(UploadAndConvertError::Upload(
+                UploadImageError::InvalidImageData
+            ))
+        ));
+        assert_eq!(images.writes(), writes);
+    }
+}
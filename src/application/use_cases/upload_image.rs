@@ -1,7 +1,12 @@
-use crate::domain::{
-    entities::ImageData, repositories::ImageRepository, value_objects::ImageFormat,
+use crate::{
+    application::services::image_decoding,
+    domain::{
+        entities::ImageData,
+        repositories::ImageRepository,
+        value_objects::{ImageFormat, ImageLimits},
+    },
 };
-use image::GenericImageView;
+use image::{GenericImageView, ImageError};
 use std::sync::Arc;
 use thiserror::Error;
 use uuid::Uuid;
@@ -14,6 +19,12 @@ pub enum UploadImageError {
     InvalidImageData,
     #[error("Image too large (max size: {max_size} bytes)")]
     ImageTooLarge { max_size: usize },
+    #[error("Image dimensions {width}x{height} exceed the limits (max: {limits})")]
+    DimensionsTooLarge {
+        width: u32,
+        height: u32,
+        limits: ImageLimits,
+    },
     #[error("Repository error: {0}")]
     Repository(#[from] Box<dyn std::error::Error + Send + Sync>),
 }
@@ -22,6 +33,7 @@ pub enum UploadImageError {
 pub struct UploadImageUseCase<R: ImageRepository> {
     repository: Arc<R>,
     max_file_size: usize,
+    limits: ImageLimits,
 }
 
 #[derive(Debug)]
@@ -34,17 +46,23 @@ pub struct UploadImageRequest {
 #[derive(Debug)]
 pub struct UploadImageResponse {
     pub image_id: Uuid,
+    /// Format detected from the data
     pub format: ImageFormat,
+    /// Format the upload claimed to be, if it named a supported one
+    pub declared_format: Option<ImageFormat>,
     pub width: u32,
     pub height: u32,
+    /// The same bytes were already stored, and the upload now shares that image
+    pub deduplicated: bool,
 }
 
 impl<R: ImageRepository> UploadImageUseCase<R> {
     /// Create a new upload image use case
-    pub fn new(repository: Arc<R>, max_file_size: usize) -> Self {
+    pub fn new(repository: Arc<R>, max_file_size: usize, limits: ImageLimits) -> Self {
         Self {
             repository,
             max_file_size,
+            limits,
         }
     }
 
@@ -53,6 +71,66 @@ impl<R: ImageRepository> UploadImageUseCase<R> {
         &self,
         request: UploadImageRequest,
     ) -> Result<UploadImageResponse, UploadImageError> {
+        let (format, declared_format) = self.check_format(&request)?;
+
+        // Share the stored copy of identical bytes; the hash only narrows the search
+        let content_hash = ImageData::hash_content(&request.data);
+        if let Some(existing) = self
+            .repository
+            .find_by_content_hash(&content_hash)
+            .await
+            .map_err(|e| UploadImageError::Repository(Box::new(e)))?
+            .filter(|existing| existing.data == request.data)
+        {
+            // A concurrent delete may have removed it since, in which case store it anew
+            let retained = self
+                .repository
+                .retain(existing.id)
+                .await
+                .map_err(|e| UploadImageError::Repository(Box::new(e)))?;
+            if retained.is_some() {
+                return Ok(UploadImageResponse {
+                    image_id: existing.id,
+                    format,
+                    declared_format,
+                    width: existing.width,
+                    height: existing.height,
+                    deduplicated: true,
+                });
+            }
+        }
+
+        let image_data = self.decode(request, format, declared_format)?;
+
+        // Save to repository
+        self.repository
+            .save(&image_data)
+            .await
+            .map_err(|e| UploadImageError::Repository(Box::new(e)))?;
+
+        Ok(UploadImageResponse {
+            image_id: image_data.id,
+            format,
+            declared_format,
+            width: image_data.width,
+            height: image_data.height,
+            deduplicated: false,
+        })
+    }
+
+    /// Validate an upload like [`UploadImageUseCase::execute`], returning the image it
+    /// would store without storing it
+    pub fn validate(&self, request: UploadImageRequest) -> Result<ImageData, UploadImageError> {
+        let (format, declared_format) = self.check_format(&request)?;
+        self.decode(request, format, declared_format)
+    }
+
+    /// Refuse oversized uploads and detect the format, returning it along with the format
+    /// the upload claims to be
+    fn check_format(
+        &self,
+        request: &UploadImageRequest,
+    ) -> Result<(ImageFormat, Option<ImageFormat>), UploadImageError> {
         // Validate file size
         if request.data.len() > self.max_file_size {
             return Err(UploadImageError::ImageTooLarge {
@@ -60,36 +138,48 @@ impl<R: ImageRepository> UploadImageUseCase<R> {
             });
         }
 
-        // Validate content type
-        let format = ImageFormat::from_mime_type(&request.content_type)
+        // Go by the file signature; the content type, or failing that the extension, is
+        // only what the client claims and is recorded as such
+        let format = ImageFormat::from_signature(&request.data)
             .ok_or(UploadImageError::UnsupportedFormat)?;
+        let declared_format = ImageFormat::from_mime_type(&request.content_type)
+            .or_else(|| ImageFormat::from_filename(&request.filename));
+        Ok((format, declared_format))
+    }
 
-        // Validate image data by attempting to decode it
-        let img = image::load_from_memory(&request.data)
+    /// Decode the upload within the limits and create the image entity
+    fn decode(
+        &self,
+        request: UploadImageRequest,
+        format: ImageFormat,
+        declared_format: Option<ImageFormat>,
+    ) -> Result<ImageData, UploadImageError> {
+        // Refuse images that would decode into too many pixels from their header alone,
+        // then validate the image data by decoding it within the same limits
+        let (width, height) = image_decoding::probe_dimensions(&request.data)
             .map_err(|_| UploadImageError::InvalidImageData)?;
+        let too_large = UploadImageError::DimensionsTooLarge {
+            width,
+            height,
+            limits: self.limits,
+        };
+        if !self.limits.allows(width, height) {
+            return Err(too_large);
+        }
+        let img = image_decoding::decode(&request.data, &self.limits).map_err(|e| match e {
+            ImageError::Limits(_) => too_large,
+            _ => UploadImageError::InvalidImageData,
+        })?;
 
         let (width, height) = img.dimensions();
 
-        // Create image entity
-        let image_data = ImageData::new(
+        Ok(ImageData::new(
             request.filename,
-            request.content_type,
+            format.mime_type().to_string(),
             request.data,
             width,
             height,
-        );
-
-        // Save to repository
-        self.repository
-            .save(&image_data)
-            .await
-            .map_err(|e| UploadImageError::Repository(Box::new(e)))?;
-
-        Ok(UploadImageResponse {
-            image_id: image_data.id,
-            format,
-            width,
-            height,
-        })
+        )
+        .with_formats(declared_format, format))
     }
 }
@@ -0,0 +1,468 @@
+use ascii_converter::{
+    application::{
+        services::{AsciiConversionService, ConversionCache},
+        use_cases::{
+            convert_image_to_ascii::{ConvertImageError, ConvertImageRequest},
+            upload_image::{UploadImageError, UploadImageRequest},
+            ConvertImageToAsciiUseCase, UploadImageUseCase,
+        },
+    },
+    domain::{
+        entities::{ascii_art::DetailLevel, AsciiFrame},
+        repositories::ImageRepository,
+        value_objects::{
+            ColorMode, ConversionConfig, ConversionParams, Dithering, EdgeFill, EdgeOptions,
+            ImageFormat, ImageLimits, RenderMode,
+        },
+    },
+    infrastructure::repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
+};
+use clap::Parser;
+use std::{
+    collections::HashSet,
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+    sync::Arc,
+};
+use thiserror::Error;
+
+/// Convert images to ASCII art without running the server
+#[derive(Parser, Debug)]
+#[command(name = "ascii-cli", version)]
+struct Cli {
+    /// Image files or glob patterns; reads stdin when empty or "-"
+    inputs: Vec<String>,
+
+    /// Write `<name>.txt` per input into this directory instead of printing to stdout.
+    /// Animations are written as one `<name>.<frame>.txt` file per frame, and inputs
+    /// sharing a name are numbered, e.g. `<name>-2.txt`.
+    #[arg(short, long)]
+    output_dir: Option<PathBuf>,
+
+    /// ASCII width in characters
+    #[arg(long, default_value_t = ConversionConfig::default().width)]
+    width: u32,
+
+    /// Detail level: high or low
+    #[arg(long, default_value = "high", value_parser = named(DetailLevel::from_name))]
+    detail: DetailLevel,
+
+    /// Contrast factor (0.1-3.0)
+    #[arg(long, default_value_t = ConversionConfig::default().contrast_factor)]
+    contrast: f32,
+
+    /// Blur sigma (0.0-5.0)
+    #[arg(long, default_value_t = ConversionConfig::default().blur_sigma)]
+    blur: f32,
+
+    /// ANSI color mode: none, 256 or truecolor
+    #[arg(long, default_value = "none", value_parser = named(ColorMode::from_name))]
+    color: ColorMode,
+
+    /// Render mode: ascii, braille, halfblock, quadrant, structural or edges
+    #[arg(long, default_value = "ascii", value_parser = named(RenderMode::from_name))]
+    mode: RenderMode,
+
+    /// Pixel threshold 0-255 for monochrome Braille and block output (default: automatic)
+    #[arg(long)]
+    threshold: Option<u8>,
+
+    /// Dithering: none, floyd-steinberg, atkinson, jarvis, sierra, bayer2, bayer4 or bayer8
+    #[arg(long, default_value = "none", value_parser = named(Dithering::from_name))]
+    dither: Dithering,
+
+    /// Alternate the scan direction of each row for error diffusion
+    #[arg(long)]
+    serpentine: bool,
+
+    /// Swap light and dark, e.g. for light terminal backgrounds
+    #[arg(long)]
+    invert: bool,
+
+    /// Custom glyph ramp from lightest to densest; overrides --detail
+    #[arg(long)]
+    ramp: Option<String>,
+
+    /// Reorder the custom ramp by ink density
+    #[arg(long)]
+    sort_ramp: bool,
+
+    /// Edges mode: gradient magnitude (0.0-1.0) above which a pixel is a strong edge
+    #[arg(long)]
+    edge_high: Option<f32>,
+
+    /// Edges mode: weaker edges above this magnitude are kept when connected to a strong edge
+    #[arg(long)]
+    edge_low: Option<f32>,
+
+    /// Edges mode: thin edges with non-maximum suppression
+    #[arg(long, default_value_t = EdgeOptions::default().thin, action = clap::ArgAction::Set)]
+    edge_thin: bool,
+
+    /// Edges mode: ramp or blank fill for cells that are not part of an edge
+    #[arg(long, default_value = "ramp", value_parser = named(EdgeFill::from_name))]
+    edge_fill: EdgeFill,
+
+    /// Largest accepted input file in bytes
+    #[arg(long, default_value_t = 10 * 1024 * 1024)]
+    max_file_size: usize,
+
+    /// Largest accepted image width in pixels
+    #[arg(long, default_value_t = ImageLimits::default().max_width)]
+    max_width: u32,
+
+    /// Largest accepted image height in pixels
+    #[arg(long, default_value_t = ImageLimits::default().max_height)]
+    max_height: u32,
+
+    /// Largest accepted number of pixels, shared by the frames of an animation
+    #[arg(long, default_value_t = ImageLimits::default().max_pixels)]
+    max_pixels: u64,
+}
+
+/// Build a clap value parser from a `from_name` constructor
+fn named<T>(from_name: fn(&str) -> Option<T>) -> impl Fn(&str) -> Result<T, String> + Clone {
+    move |name| from_name(name).ok_or_else(|| format!("unknown value '{}'", name))
+}
+
+#[derive(Error, Debug)]
+enum CliError {
+    #[error("{0}")]
+    Usage(String),
+    #[error("{path}: {source}")]
+    Io { path: String, source: io::Error },
+    #[error("{path}: {source}")]
+    Upload {
+        path: String,
+        source: UploadImageError,
+    },
+    #[error("{path}: {source}")]
+    Convert {
+        path: String,
+        source: ConvertImageError,
+    },
+}
+
+impl CliError {
+    /// Process exit code, one per variant of the underlying error enums
+    fn exit_code(&self) -> u8 {
+        match self {
+            CliError::Io { .. } => 1,
+            CliError::Usage(_) => 2,
+            CliError::Upload { source, .. } => match source {
+                UploadImageError::UnsupportedFormat => 3,
+                UploadImageError::InvalidImageData => 4,
+                UploadImageError::ImageTooLarge { .. } => 5,
+                UploadImageError::DimensionsTooLarge { .. } => 7,
+                UploadImageError::Repository(_) => 10,
+            },
+            CliError::Convert { source, .. } => match source {
+                ConvertImageError::InvalidConfig => 2,
+                ConvertImageError::LimitsExceeded(_) => 7,
+                ConvertImageError::ConversionFailed(_) | ConvertImageError::Cancelled => 6,
+                ConvertImageError::ImageNotFound | ConvertImageError::Repository(_) => 10,
+            },
+        }
+    }
+}
+
+/// Image to convert, read from a file or stdin
+struct Input {
+    /// Shown in messages and used to name output files
+    name: String,
+    data: Vec<u8>,
+    content_type: String,
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    let config = match build_config(&cli) {
+        Ok(config) => config,
+        Err(e) => return report(&e),
+    };
+    let inputs = match expand_inputs(&cli.inputs) {
+        Ok(inputs) => inputs,
+        Err(e) => return report(&e),
+    };
+
+    // The use cases validate and convert exactly as the server does; nothing outlives
+    // the process, so the in-memory repositories suffice
+    let image_repo = Arc::new(InMemoryImageRepository::new());
+    let limits = ImageLimits {
+        max_width: cli.max_width,
+        max_height: cli.max_height,
+        max_pixels: cli.max_pixels,
+    };
+    let upload_use_case =
+        UploadImageUseCase::new(Arc::clone(&image_repo), cli.max_file_size, limits);
+    let convert_use_case = ConvertImageToAsciiUseCase::new(
+        Arc::clone(&image_repo),
+        Arc::new(InMemoryAsciiArtRepository::new()),
+        Arc::new(AsciiConversionService::with_limits(limits)),
+        // Every input is released once converted, so there is nothing to reuse
+        Arc::new(ConversionCache::new(0)),
+    );
+
+    // Keep going after a failure, exiting with the code of the first one
+    let mut first_failure = None;
+    let mut used_stems = HashSet::new();
+    for path in inputs {
+        let stem = output_stem(path.as_deref(), &mut used_stems);
+        let result = async {
+            let input = read_input(path.as_deref())?;
+            let name = input.name.clone();
+
+            let upload = upload_use_case
+                .execute(UploadImageRequest {
+                    filename: input.name,
+                    content_type: input.content_type,
+                    data: input.data,
+                })
+                .await
+                .map_err(|source| CliError::Upload {
+                    path: name.clone(),
+                    source,
+                })?;
+
+            let conversion = convert_use_case
+                .execute(ConvertImageRequest {
+                    image_id: upload.image_id,
+                    config: config.clone(),
+                })
+                .await;
+            // Don't hold on to every decoded input of a large batch
+            let _ = image_repo.release(upload.image_id).await;
+            let conversion = conversion.map_err(|source| CliError::Convert {
+                path: name.clone(),
+                source,
+            })?;
+
+            write_output(
+                cli.output_dir.as_deref(),
+                &stem,
+                &conversion.content,
+                &conversion.frames,
+            )
+        }
+        .await;
+
+        if let Err(e) = result {
+            first_failure.get_or_insert(report(&e));
+        }
+    }
+
+    first_failure.unwrap_or(ExitCode::SUCCESS)
+}
+
+/// Print an error and get its exit code
+fn report(error: &CliError) -> ExitCode {
+    eprintln!("ascii-cli: {}", error);
+    ExitCode::from(error.exit_code())
+}
+
+/// Assemble the conversion configuration from the flags, like the convert endpoint does
+fn build_config(cli: &Cli) -> Result<ConversionConfig, CliError> {
+    ConversionParams {
+        width: Some(cli.width),
+        detail_level: Some(cli.detail),
+        contrast_factor: Some(cli.contrast),
+        blur_sigma: Some(cli.blur),
+        color_mode: Some(cli.color),
+        render_mode: Some(cli.mode),
+        threshold: cli.threshold,
+        dithering: Some(cli.dither),
+        serpentine: Some(cli.serpentine),
+        invert: Some(cli.invert),
+        ramp: cli.ramp.clone(),
+        sort_ramp: Some(cli.sort_ramp),
+        edge_high: cli.edge_high,
+        edge_low: cli.edge_low,
+        edge_thin: Some(cli.edge_thin),
+        edge_fill: Some(cli.edge_fill),
+    }
+    .into_config()
+    .map_err(|e| CliError::Usage(e.to_string()))
+}
+
+/// Resolve the positional arguments to file paths, where `None` stands for stdin
+fn expand_inputs(inputs: &[String]) -> Result<Vec<Option<PathBuf>>, CliError> {
+    if inputs.is_empty() {
+        return Ok(vec![None]);
+    }
+
+    let mut paths = Vec::new();
+    for input in inputs {
+        if input == "-" {
+            paths.push(None);
+        } else if input.contains(['*', '?', '[']) {
+            let matches = glob::glob(input)
+                .map_err(|e| CliError::Usage(format!("{}: {}", input, e)))?
+                .filter_map(Result::ok)
+                .filter(|path| path.is_file())
+                .map(Some)
+                .collect::<Vec<_>>();
+            if matches.is_empty() {
+                return Err(CliError::Usage(format!("{}: no matching files", input)));
+            }
+            paths.extend(matches);
+        } else {
+            paths.push(Some(PathBuf::from(input)));
+        }
+    }
+    Ok(paths)
+}
+
+fn read_input(path: Option<&Path>) -> Result<Input, CliError> {
+    let (name, data) = match path {
+        Some(path) => {
+            let data = std::fs::read(path).map_err(|source| CliError::Io {
+                path: path.display().to_string(),
+                source,
+            })?;
+            (path.display().to_string(), data)
+        }
+        None => {
+            let mut data = Vec::new();
+            io::stdin()
+                .read_to_end(&mut data)
+                .map_err(|source| CliError::Io {
+                    path: "<stdin>".to_string(),
+                    source,
+                })?;
+            ("<stdin>".to_string(), data)
+        }
+    };
+
+    // Declare the format by file extension; the upload detects it from the data anyway
+    let format = path
+        .and_then(|path| path.extension())
+        .and_then(|extension| extension.to_str())
+        .and_then(ImageFormat::from_extension);
+
+    Ok(Input {
+        name,
+        content_type: format
+            .map(|format| format.mime_type())
+            .unwrap_or("application/octet-stream")
+            .to_string(),
+        data,
+    })
+}
+
+/// Name of the output files for an input, without extension, numbered when an earlier
+/// input of the run already took it
+fn output_stem(path: Option<&Path>, used_stems: &mut HashSet<String>) -> String {
+    let stem = path
+        .and_then(|path| path.file_stem())
+        .and_then(|stem| stem.to_str())
+        .unwrap_or("stdin");
+    let mut unique = stem.to_string();
+    let mut copy = 1;
+    while !used_stems.insert(unique.clone()) {
+        copy += 1;
+        unique = format!("{}-{}", stem, copy);
+    }
+    unique
+}
+
+fn write_output(
+    output_dir: Option<&Path>,
+    stem: &str,
+    content: &str,
+    frames: &[AsciiFrame],
+) -> Result<(), CliError> {
+    let io_error = |path: &Path| {
+        let path = path.display().to_string();
+        move |source| CliError::Io { path, source }
+    };
+
+    let Some(output_dir) = output_dir else {
+        let mut stdout = io::stdout().lock();
+        return writeln!(stdout, "{}", content).map_err(io_error(Path::new("<stdout>")));
+    };
+
+    std::fs::create_dir_all(output_dir).map_err(io_error(output_dir))?;
+    if frames.is_empty() {
+        let path = output_dir.join(format!("{}.txt", stem));
+        return std::fs::write(&path, content).map_err(io_error(&path));
+    }
+    for (index, frame) in frames.iter().enumerate() {
+        let path = output_dir.join(format!("{}.{:04}.txt", stem, index + 1));
+        std::fs::write(&path, &frame.content).map_err(io_error(&path))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inputs_sharing_a_name_get_numbered_outputs() {
+        let mut used_stems = HashSet::new();
+        let names: Vec<String> = [
+            Some("a/x.png"),
+            Some("b/x.png"),
+            Some("x.jpg"),
+            Some("x-2.gif"),
+            Some("y.png"),
+            None,
+            None,
+        ]
+        .into_iter()
+        .map(|path| output_stem(path.map(Path::new), &mut used_stems))
+        .collect();
+        assert_eq!(names, ["x", "x-2", "x-3", "x-2-2", "y", "stdin", "stdin-2"]);
+    }
+
+    #[test]
+    fn errors_map_to_distinct_exit_codes() {
+        let upload = |source| CliError::Upload {
+            path: "x.png".to_string(),
+            source,
+        };
+        let convert = |source| CliError::Convert {
+            path: "x.png".to_string(),
+            source,
+        };
+        let codes: Vec<u8> = [
+            CliError::Io {
+                path: "x.png".to_string(),
+                source: io::ErrorKind::NotFound.into(),
+            },
+            CliError::Usage("bad flag".to_string()),
+            upload(UploadImageError::UnsupportedFormat),
+            upload(UploadImageError::InvalidImageData),
+            upload(UploadImageError::ImageTooLarge { max_size: 1 }),
+            convert(ConvertImageError::ConversionFailed("broken".to_string())),
+            upload(UploadImageError::DimensionsTooLarge {
+                width: 2,
+                height: 2,
+                limits: ImageLimits::default(),
+            }),
+            convert(ConvertImageError::LimitsExceeded("too tall".to_string())),
+            upload(UploadImageError::Repository("full".into())),
+            convert(ConvertImageError::InvalidConfig),
+            convert(ConvertImageError::ImageNotFound),
+        ]
+        .iter()
+        .map(CliError::exit_code)
+        .collect();
+        assert_eq!(codes, [1, 2, 3, 4, 5, 6, 7, 7, 10, 2, 10]);
+    }
+
+    #[test]
+    fn invalid_flags_are_usage_errors() {
+        let config = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["ascii-cli"], args].concat()).unwrap();
+            build_config(&cli)
+        };
+        assert_eq!(config(&["--width", "60"]).unwrap().width, 60);
+        for args in [&["--width", "0"][..], &["--ramp", ""], &["--contrast", "9"]] {
+            assert_eq!(config(args).unwrap_err().exit_code(), 2, "{:?}", args);
+        }
+    }
+}
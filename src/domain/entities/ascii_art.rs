@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use std::fmt;
 use uuid::Uuid;
 
 /// ASCII art entity representing the converted result
@@ -11,16 +12,36 @@ pub struct AsciiArt {
     pub height: u32,
     pub detail_level: DetailLevel,
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// All frames of an animated source, empty for still images
+    #[serde(default)]
+    pub frames: Vec<AsciiFrame>,
+}
+
+/// Single frame of animated ASCII art
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AsciiFrame {
+    pub content: String,
+    /// How long the frame stays on screen, in milliseconds
+    pub delay_ms: u32,
 }
 
 /// Level of detail for ASCII art conversion
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DetailLevel {
     Low,
     High,
 }
 
 impl DetailLevel {
+    /// Parse detail level from its query parameter name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "low" => Some(DetailLevel::Low),
+            "high" => Some(DetailLevel::High),
+            _ => None,
+        }
+    }
+
     /// Get the ASCII character set for this detail level
     pub fn char_set(&self) -> &'static str {
         match self {
@@ -32,6 +53,15 @@ impl DetailLevel {
     }
 }
 
+impl fmt::Display for DetailLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DetailLevel::Low => write!(f, "low"),
+            DetailLevel::High => write!(f, "high"),
+        }
+    }
+}
+
 impl AsciiArt {
     /// Create a new AsciiArt instance
     pub fn new(
@@ -49,9 +79,21 @@ impl AsciiArt {
             height,
             detail_level,
             created_at: chrono::Utc::now(),
+            frames: Vec::new(),
         }
     }
 
+    /// Attach the frames of an animated source
+    pub fn with_frames(mut self, frames: Vec<AsciiFrame>) -> Self {
+        self.frames = frames;
+        self
+    }
+
+    /// Check if the ASCII art has more than one frame
+    pub fn is_animated(&self) -> bool {
+        self.frames.len() > 1
+    }
+
     /// Check if the ASCII art is valid
     pub fn is_valid(&self) -> bool {
         !self.content.is_empty() && self.width > 0 && self.height > 0
@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use uuid::Uuid;
+
+/// Background conversion of a stored image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversionJob {
+    pub id: Uuid,
+    pub image_id: Uuid,
+    pub status: JobStatus,
+    /// Fraction of the work done, from 0.0 to 1.0
+    pub progress: f32,
+    /// Stored result once the job is done
+    pub ascii_art_id: Option<Uuid>,
+    /// Reason the job failed
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Stage in the life of a conversion job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum JobStatus {
+    Queued,
+    Running,
+    Done,
+    Failed,
+    Cancelled,
+}
+
+impl JobStatus {
+    /// Whether the job has stopped for good
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            JobStatus::Done | JobStatus::Failed | JobStatus::Cancelled
+        )
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            JobStatus::Queued => write!(f, "queued"),
+            JobStatus::Running => write!(f, "running"),
+            JobStatus::Done => write!(f, "done"),
+            JobStatus::Failed => write!(f, "failed"),
+            JobStatus::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+impl ConversionJob {
+    /// Create a new queued job for the given image
+    pub fn new(image_id: Uuid) -> Self {
+        let now = chrono::Utc::now();
+        Self {
+            id: Uuid::new_v4(),
+            image_id,
+            status: JobStatus::Queued,
+            progress: 0.0,
+            ascii_art_id: None,
+            error: None,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
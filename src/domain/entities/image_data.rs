@@ -1,4 +1,6 @@
+use crate::domain::value_objects::ImageFormat;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
 /// Image data entity representing an uploaded image
@@ -6,10 +8,27 @@ use uuid::Uuid;
 pub struct ImageData {
     pub id: Uuid,
     pub original_filename: String,
+    /// MIME type of the detected format
     pub content_type: String,
     pub data: Vec<u8>,
     pub width: u32,
     pub height: u32,
+    /// Hex SHA-256 of `data`, used to store identical uploads once
+    #[serde(default)]
+    pub content_hash: String,
+    /// Number of uploads sharing this image; it is deleted with the last one
+    #[serde(default = "default_ref_count")]
+    pub ref_count: u32,
+    /// Format the client claimed, by content type or else by file extension
+    #[serde(default)]
+    pub declared_format: Option<ImageFormat>,
+    /// Format found from the file signature; `None` for images stored before detection
+    #[serde(default)]
+    pub detected_format: Option<ImageFormat>,
+}
+
+fn default_ref_count() -> u32 {
+    1
 }
 
 impl ImageData {
@@ -25,12 +44,35 @@ impl ImageData {
             id: Uuid::new_v4(),
             original_filename,
             content_type,
+            content_hash: Self::hash_content(&data),
             data,
             width,
             height,
+            ref_count: 1,
+            declared_format: None,
+            detected_format: None,
         }
     }
 
+    /// Record the format the client declared and the one detected from the data
+    pub fn with_formats(
+        mut self,
+        declared_format: Option<ImageFormat>,
+        detected_format: ImageFormat,
+    ) -> Self {
+        self.declared_format = declared_format;
+        self.detected_format = Some(detected_format);
+        self
+    }
+
+    /// Hash identifying image bytes, as stored in `content_hash`
+    pub fn hash_content(data: &[u8]) -> String {
+        Sha256::digest(data)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+
     /// Get the aspect ratio of the image
     pub fn aspect_ratio(&self) -> f32 {
         self.height as f32 / self.width as f32
@@ -1,5 +1,7 @@
 pub mod ascii_art;
+pub mod conversion_job;
 pub mod image_data;
 
-pub use ascii_art::AsciiArt;
+pub use ascii_art::{AsciiArt, AsciiFrame};
+pub use conversion_job::{ConversionJob, JobStatus};
 pub use image_data::ImageData;
@@ -1,8 +1,51 @@
-use crate::domain::entities::AsciiArt;
+use crate::domain::{
+    entities::{ascii_art::DetailLevel, AsciiArt},
+    repositories::StorageStats,
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use std::error::Error;
 use uuid::Uuid;
 
+/// Filter and page for listing ASCII art; unset filters match everything
+#[derive(Debug, Clone, Default)]
+pub struct AsciiArtQuery {
+    pub image_id: Option<Uuid>,
+    pub detail_level: Option<DetailLevel>,
+    /// Only include ASCII art created at or after this instant
+    pub created_after: Option<DateTime<Utc>>,
+    /// Only include ASCII art created strictly before this instant
+    pub created_before: Option<DateTime<Utc>>,
+    /// Number of matches to skip
+    pub offset: usize,
+    /// Maximum number of matches to return
+    pub limit: Option<usize>,
+}
+
+impl AsciiArtQuery {
+    /// Check if ASCII art with the given attributes passes the filter
+    pub fn matches(
+        &self,
+        image_id: Uuid,
+        detail_level: DetailLevel,
+        created_at: DateTime<Utc>,
+    ) -> bool {
+        self.image_id.is_none_or(|id| id == image_id)
+            && self.detail_level.is_none_or(|level| level == detail_level)
+            && self.created_after.is_none_or(|after| created_at >= after)
+            && self.created_before.is_none_or(|before| created_at < before)
+    }
+
+    /// Apply the offset and limit to matches that are already in order
+    pub fn paginate<T>(&self, matches: Vec<T>) -> Vec<T> {
+        matches
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect()
+    }
+}
+
 /// Repository interface for ASCII art storage
 #[async_trait]
 pub trait AsciiArtRepository: Send + Sync {
@@ -17,6 +60,14 @@ pub trait AsciiArtRepository: Send + Sync {
     /// Find ASCII art by image ID
     async fn find_by_image_id(&self, image_id: Uuid) -> Result<Vec<AsciiArt>, Self::Error>;
 
+    /// List ASCII art matching a query, newest first
+    async fn list(&self, query: &AsciiArtQuery) -> Result<Vec<AsciiArt>, Self::Error>;
+
     /// Delete ASCII art by ID
     async fn delete(&self, id: Uuid) -> Result<(), Self::Error>;
+
+    /// Usage and eviction counters, for stores that drop entries on their own
+    fn storage_stats(&self) -> Option<StorageStats> {
+        None
+    }
 }
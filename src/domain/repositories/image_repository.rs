@@ -1,8 +1,11 @@
-use crate::domain::entities::ImageData;
+use crate::domain::{entities::ImageData, repositories::StorageStats};
 use async_trait::async_trait;
-use std::error::Error;
+use std::{error::Error, sync::Arc};
 use uuid::Uuid;
 
+/// Called with the ID of every image a store drops on its own
+pub type EvictionCallback = Arc<dyn Fn(Uuid) + Send + Sync>;
+
 /// Repository interface for image storage
 #[async_trait]
 pub trait ImageRepository: Send + Sync {
@@ -14,6 +17,27 @@ pub trait ImageRepository: Send + Sync {
     /// Find image by ID
     async fn find_by_id(&self, id: Uuid) -> Result<Option<ImageData>, Self::Error>;
 
-    /// Delete image by ID
+    /// Find an image by the hash of its bytes (see [`ImageData::hash_content`])
+    async fn find_by_content_hash(&self, hash: &str) -> Result<Option<ImageData>, Self::Error>;
+
+    /// Delete image by ID, however many references it has
     async fn delete(&self, id: Uuid) -> Result<(), Self::Error>;
+
+    /// Add a reference to a stored image, returning the new count, or `None` if the
+    /// image doesn't exist
+    async fn retain(&self, id: Uuid) -> Result<Option<u32>, Self::Error>;
+
+    /// Drop a reference to a stored image, deleting it along with the last one.
+    /// Returns the references left, or `None` if the image doesn't exist.
+    async fn release(&self, id: Uuid) -> Result<Option<u32>, Self::Error>;
+
+    /// Usage and eviction counters, for stores that drop entries on their own
+    fn storage_stats(&self) -> Option<StorageStats> {
+        None
+    }
+
+    /// Have `callback` called for every image that expires or is evicted, for stores
+    /// that drop entries on their own; images removed by `delete` or `release` aren't
+    /// reported
+    fn on_evict(&self, _callback: EvictionCallback) {}
 }
@@ -1,5 +1,7 @@
 pub mod ascii_art_repository;
 pub mod image_repository;
+pub mod storage_stats;
 
-pub use ascii_art_repository::AsciiArtRepository;
-pub use image_repository::ImageRepository;
+pub use ascii_art_repository::{AsciiArtQuery, AsciiArtRepository};
+pub use image_repository::{EvictionCallback, ImageRepository};
+pub use storage_stats::StorageStats;
@@ -0,0 +1,14 @@
+use serde::Serialize;
+
+/// Usage of a store that evicts entries on its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct StorageStats {
+    /// Entries currently stored
+    pub entries: usize,
+    /// Approximate memory taken by the stored entries
+    pub bytes: usize,
+    /// Entries removed because their time to live ran out
+    pub expired: u64,
+    /// Entries removed to stay within the memory quota
+    pub evicted: u64,
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+#[derive(Error, Debug, PartialEq, Eq)]
+pub enum CharRampError {
+    #[error("Character ramp must not be empty")]
+    Empty,
+    #[error("Character ramp contains a control character ({0:?})")]
+    ControlCharacter(char),
+}
+
+/// User-defined glyph ramp ordered from lightest to densest
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CharRamp {
+    glyphs: Vec<char>,
+    /// Reorder glyphs by measured ink density instead of trusting the given order
+    pub sort_by_density: bool,
+}
+
+impl CharRamp {
+    /// Create a ramp from a string, one glyph per Unicode scalar value
+    pub fn new(glyphs: &str, sort_by_density: bool) -> Result<Self, CharRampError> {
+        if let Some(c) = glyphs.chars().find(|c| c.is_control()) {
+            return Err(CharRampError::ControlCharacter(c));
+        }
+
+        let glyphs: Vec<char> = glyphs.chars().collect();
+        if glyphs.is_empty() {
+            return Err(CharRampError::Empty);
+        }
+
+        Ok(Self {
+            glyphs,
+            sort_by_density,
+        })
+    }
+
+    /// Get the glyphs in the order given by the caller
+    pub fn glyphs(&self) -> &[char] {
+        &self.glyphs
+    }
+}
@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Color output mode for ASCII art conversion
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ColorMode {
+    /// Plain monochrome text
+    #[default]
+    None,
+    /// Colors quantized to the xterm 256-color palette
+    Ansi256,
+    /// 24-bit RGB colors
+    TrueColor,
+}
+
+impl ColorMode {
+    /// Parse color mode from its query parameter name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "none" | "mono" => Some(ColorMode::None),
+            "256" | "ansi256" => Some(ColorMode::Ansi256),
+            "truecolor" | "24bit" => Some(ColorMode::TrueColor),
+            _ => None,
+        }
+    }
+
+    /// Check if this mode emits ANSI color escape sequences
+    pub fn is_colored(&self) -> bool {
+        !matches!(self, ColorMode::None)
+    }
+}
+
+impl fmt::Display for ColorMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ColorMode::None => write!(f, "none"),
+            ColorMode::Ansi256 => write!(f, "256"),
+            ColorMode::TrueColor => write!(f, "truecolor"),
+        }
+    }
+}
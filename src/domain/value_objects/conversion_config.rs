@@ -1,5 +1,9 @@
-use crate::domain::entities::ascii_art::DetailLevel;
+use crate::domain::{
+    entities::ascii_art::DetailLevel,
+    value_objects::{CharRamp, ColorMode, Dithering, EdgeOptions, RenderMode},
+};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 /// Configuration for ASCII art conversion
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -8,17 +12,32 @@ pub struct ConversionConfig {
     pub detail_level: DetailLevel,
     pub contrast_factor: f32,
     pub blur_sigma: f32,
+    #[serde(default)]
+    pub color_mode: ColorMode,
+    #[serde(default)]
+    pub render_mode: RenderMode,
+    /// Fixed on/off threshold for binary renderers; `None` picks one automatically
+    #[serde(default)]
+    pub threshold: Option<u8>,
+    #[serde(default)]
+    pub dithering: Dithering,
+    /// Alternate the scan direction of each row during error diffusion
+    #[serde(default)]
+    pub serpentine: bool,
+    /// Swap light and dark, for light terminal backgrounds
+    #[serde(default)]
+    pub invert: bool,
+    /// Glyph ramp replacing the detail level's character set
+    #[serde(default)]
+    pub custom_ramp: Option<CharRamp>,
+    #[serde(default)]
+    pub edges: EdgeOptions,
 }
 
 impl ConversionConfig {
     /// Create a new conversion configuration
     pub fn new(width: u32, detail_level: DetailLevel) -> Self {
-        Self {
-            width,
-            detail_level,
-            contrast_factor: 1.2,
-            blur_sigma: 0.5,
-        }
+        Self::with_params(width, detail_level, 1.2, 0.5)
     }
 
     /// Create a configuration with custom parameters
@@ -33,10 +52,66 @@ impl ConversionConfig {
             detail_level,
             contrast_factor,
             blur_sigma,
+            color_mode: ColorMode::None,
+            render_mode: RenderMode::Ascii,
+            threshold: None,
+            dithering: Dithering::None,
+            serpentine: false,
+            invert: false,
+            custom_ramp: None,
+            edges: EdgeOptions::default(),
         }
     }
 
-    /// Validate the configuration
+    /// Set the color output mode
+    pub fn with_color_mode(mut self, color_mode: ColorMode) -> Self {
+        self.color_mode = color_mode;
+        self
+    }
+
+    /// Set the rendering strategy
+    pub fn with_render_mode(mut self, render_mode: RenderMode) -> Self {
+        self.render_mode = render_mode;
+        self
+    }
+
+    /// Set the on/off threshold used by binary renderers
+    pub fn with_threshold(mut self, threshold: Option<u8>) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Set the dithering applied when quantizing pixels
+    pub fn with_dithering(mut self, dithering: Dithering) -> Self {
+        self.dithering = dithering;
+        self
+    }
+
+    /// Enable serpentine scanning for error-diffusion dithering
+    pub fn with_serpentine(mut self, serpentine: bool) -> Self {
+        self.serpentine = serpentine;
+        self
+    }
+
+    /// Swap light and dark in the output
+    pub fn with_invert(mut self, invert: bool) -> Self {
+        self.invert = invert;
+        self
+    }
+
+    /// Use a custom glyph ramp instead of the detail level's character set
+    pub fn with_custom_ramp(mut self, custom_ramp: Option<CharRamp>) -> Self {
+        self.custom_ramp = custom_ramp;
+        self
+    }
+
+    /// Set the edge detection settings used by the edge render mode
+    pub fn with_edges(mut self, edges: EdgeOptions) -> Self {
+        self.edges = edges;
+        self
+    }
+
+    /// Validate the configuration; NaN fails every range check
     pub fn is_valid(&self) -> bool {
         self.width > 0
             && self.width <= 1000 // reasonable upper limit
@@ -44,6 +119,35 @@ impl ConversionConfig {
             && self.contrast_factor <= 3.0 // reasonable range
             && self.blur_sigma >= 0.0
             && self.blur_sigma <= 5.0 // reasonable range
+            && self.edges.is_valid()
+    }
+
+    /// Hex digest identifying the output this configuration produces, for caching.
+    /// Floats are compared by value, so `-0.0` and `0.0` share a key, as do all NaNs,
+    /// and every other field takes part as is.
+    pub fn cache_key(&self) -> String {
+        fn normalize(value: f32) -> f32 {
+            if value == 0.0 {
+                0.0
+            } else if value.is_nan() {
+                f32::NAN
+            } else {
+                value
+            }
+        }
+
+        let mut canonical = self.clone();
+        canonical.contrast_factor = normalize(canonical.contrast_factor);
+        canonical.blur_sigma = normalize(canonical.blur_sigma);
+        canonical.edges.high_threshold = normalize(canonical.edges.high_threshold);
+        canonical.edges.low_threshold = normalize(canonical.edges.low_threshold);
+
+        // Fields serialize in declaration order, and floats in their shortest exact form
+        let json = serde_json::to_vec(&canonical).expect("conversion configs serialize");
+        Sha256::digest(json)
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
     }
 }
 
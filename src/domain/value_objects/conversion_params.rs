@@ -0,0 +1,114 @@
+use crate::domain::{
+    entities::ascii_art::DetailLevel,
+    value_objects::{
+        CharRamp, CharRampError, ColorMode, ConversionConfig, Dithering, EdgeFill, EdgeOptions,
+        RenderMode,
+    },
+};
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum ConversionParamsError {
+    #[error(transparent)]
+    InvalidRamp(#[from] CharRampError),
+    #[error("Invalid conversion parameters")]
+    InvalidConfig,
+}
+
+/// Conversion settings as given by a request or on the command line; unset ones take
+/// the defaults of [`ConversionConfig`]
+#[derive(Debug, Clone, Default)]
+pub struct ConversionParams {
+    pub width: Option<u32>,
+    pub detail_level: Option<DetailLevel>,
+    pub contrast_factor: Option<f32>,
+    pub blur_sigma: Option<f32>,
+    pub color_mode: Option<ColorMode>,
+    pub render_mode: Option<RenderMode>,
+    pub threshold: Option<u8>,
+    pub dithering: Option<Dithering>,
+    pub serpentine: Option<bool>,
+    pub invert: Option<bool>,
+    /// Glyphs of a custom ramp, from lightest to densest
+    pub ramp: Option<String>,
+    /// Reorder the custom ramp by ink density
+    pub sort_ramp: Option<bool>,
+    pub edge_high: Option<f32>,
+    pub edge_low: Option<f32>,
+    pub edge_thin: Option<bool>,
+    pub edge_fill: Option<EdgeFill>,
+}
+
+impl ConversionParams {
+    /// Assemble and validate the conversion configuration
+    pub fn into_config(self) -> Result<ConversionConfig, ConversionParamsError> {
+        let defaults = ConversionConfig::default();
+
+        let custom_ramp = self
+            .ramp
+            .as_deref()
+            .map(|glyphs| CharRamp::new(glyphs, self.sort_ramp.unwrap_or(false)))
+            .transpose()?;
+
+        let edge_high = self.edge_high.unwrap_or(defaults.edges.high_threshold);
+        let edges = EdgeOptions {
+            high_threshold: edge_high,
+            // Keep the default low threshold below a lowered high threshold
+            low_threshold: self
+                .edge_low
+                .unwrap_or(defaults.edges.low_threshold.min(edge_high)),
+            thin: self.edge_thin.unwrap_or(defaults.edges.thin),
+            fill: self.edge_fill.unwrap_or(defaults.edges.fill),
+        };
+
+        let config = ConversionConfig::with_params(
+            self.width.unwrap_or(defaults.width),
+            self.detail_level.unwrap_or(defaults.detail_level),
+            self.contrast_factor.unwrap_or(defaults.contrast_factor),
+            self.blur_sigma.unwrap_or(defaults.blur_sigma),
+        )
+        .with_color_mode(self.color_mode.unwrap_or(defaults.color_mode))
+        .with_render_mode(self.render_mode.unwrap_or(defaults.render_mode))
+        .with_threshold(self.threshold)
+        .with_dithering(self.dithering.unwrap_or(defaults.dithering))
+        .with_serpentine(self.serpentine.unwrap_or(defaults.serpentine))
+        .with_invert(self.invert.unwrap_or(defaults.invert))
+        .with_custom_ramp(custom_ramp)
+        .with_edges(edges);
+
+        if !config.is_valid() {
+            return Err(ConversionParamsError::InvalidConfig);
+        }
+        Ok(config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unset_params_take_the_defaults() {
+        let config = ConversionParams::default().into_config().unwrap();
+        assert_eq!(config.cache_key(), ConversionConfig::default().cache_key());
+
+        // A lowered high threshold pulls the default low threshold down with it
+        let edges = ConversionParams {
+            edge_high: Some(0.05),
+            ..ConversionParams::default()
+        }
+        .into_config()
+        .unwrap()
+        .edges;
+        assert_eq!((edges.high_threshold, edges.low_threshold), (0.05, 0.05));
+
+        let invalid = ConversionParams {
+            blur_sigma: Some(-1.0),
+            ..ConversionParams::default()
+        };
+        assert!(matches!(
+            invalid.into_config(),
+            Err(ConversionParamsError::InvalidConfig)
+        ));
+    }
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Dithering applied when quantizing pixels to a small number of levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Dithering {
+    /// Plain thresholding of every pixel
+    #[default]
+    None,
+    /// Floyd–Steinberg error diffusion
+    FloydSteinberg,
+    /// Atkinson error diffusion (diffuses 3/4 of the error)
+    Atkinson,
+    /// Jarvis–Judice–Ninke error diffusion
+    JarvisJudiceNinke,
+    /// Three-row Sierra error diffusion
+    Sierra,
+    /// Ordered dithering with a 2x2 Bayer matrix
+    Bayer2,
+    /// Ordered dithering with a 4x4 Bayer matrix
+    Bayer4,
+    /// Ordered dithering with an 8x8 Bayer matrix
+    Bayer8,
+}
+
+impl Dithering {
+    /// Parse dithering from its query parameter name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "none" => Some(Dithering::None),
+            "floyd-steinberg" | "fs" => Some(Dithering::FloydSteinberg),
+            "atkinson" => Some(Dithering::Atkinson),
+            "jarvis" | "jarvis-judice-ninke" => Some(Dithering::JarvisJudiceNinke),
+            "sierra" => Some(Dithering::Sierra),
+            "bayer2" => Some(Dithering::Bayer2),
+            "bayer4" => Some(Dithering::Bayer4),
+            "bayer8" => Some(Dithering::Bayer8),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for Dithering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Dithering::None => write!(f, "none"),
+            Dithering::FloydSteinberg => write!(f, "floyd-steinberg"),
+            Dithering::Atkinson => write!(f, "atkinson"),
+            Dithering::JarvisJudiceNinke => write!(f, "jarvis"),
+            Dithering::Sierra => write!(f, "sierra"),
+            Dithering::Bayer2 => write!(f, "bayer2"),
+            Dithering::Bayer4 => write!(f, "bayer4"),
+            Dithering::Bayer8 => write!(f, "bayer8"),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// What to draw in cells that are not part of an edge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum EdgeFill {
+    /// Characters from the intensity ramp
+    #[default]
+    Ramp,
+    /// Blank space, leaving only the outlines
+    Blank,
+}
+
+impl EdgeFill {
+    /// Parse edge fill from its query parameter name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ramp" => Some(EdgeFill::Ramp),
+            "blank" | "none" => Some(EdgeFill::Blank),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for EdgeFill {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EdgeFill::Ramp => write!(f, "ramp"),
+            EdgeFill::Blank => write!(f, "blank"),
+        }
+    }
+}
+
+/// Edge detection settings for the edge render mode. Thresholds are fractions of the
+/// strongest possible gradient magnitude.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct EdgeOptions {
+    /// Gradient magnitude above which a pixel is a strong edge
+    pub high_threshold: f32,
+    /// Weak edges above this magnitude are kept when connected to a strong edge
+    /// (hysteresis); set equal to `high_threshold` to disable
+    pub low_threshold: f32,
+    /// Thin edges to single-pixel lines with non-maximum suppression (Canny)
+    pub thin: bool,
+    pub fill: EdgeFill,
+}
+
+impl EdgeOptions {
+    /// Check that both thresholds are in range and ordered
+    pub fn is_valid(&self) -> bool {
+        (0.0..=1.0).contains(&self.low_threshold)
+            && (0.0..=1.0).contains(&self.high_threshold)
+            && self.low_threshold <= self.high_threshold
+    }
+}
+
+impl Default for EdgeOptions {
+    fn default() -> Self {
+        Self {
+            high_threshold: 0.3,
+            low_threshold: 0.15,
+            thin: true,
+            fill: EdgeFill::Ramp,
+        }
+    }
+}
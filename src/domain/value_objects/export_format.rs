@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Format that stored ASCII art can be exported to, as text, image or HTML
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ExportFormat {
+    /// Plain text with any ANSI color sequences removed
+    Text,
+    /// Text as converted, including ANSI color sequences
+    Ansi,
+    /// Raster image drawn with the bundled bitmap font
+    Png,
+    /// Vector image with one text element per row
+    Svg,
+    /// Self-contained HTML document
+    Html,
+    /// Styled `<pre>` element for embedding into other pages
+    HtmlSnippet,
+}
+
+impl ExportFormat {
+    /// Parse export format from its name or file extension
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "text" | "txt" => Some(ExportFormat::Text),
+            "ansi" | "ans" => Some(ExportFormat::Ansi),
+            "png" => Some(ExportFormat::Png),
+            "svg" => Some(ExportFormat::Svg),
+            "html" => Some(ExportFormat::Html),
+            "html-snippet" | "snippet" => Some(ExportFormat::HtmlSnippet),
+            _ => None,
+        }
+    }
+
+    /// Parse export format from a media type, ignoring any parameters
+    pub fn from_media_type(media_type: &str) -> Option<Self> {
+        let essence = media_type.split(';').next().unwrap_or("").trim();
+        match essence.to_lowercase().as_str() {
+            "text/plain" | "text/*" => Some(ExportFormat::Text),
+            "text/x-ansi" => Some(ExportFormat::Ansi),
+            "image/png" | "image/*" => Some(ExportFormat::Png),
+            "image/svg+xml" => Some(ExportFormat::Svg),
+            "text/html" => Some(ExportFormat::Html),
+            _ => None,
+        }
+    }
+
+    /// Get the MIME type for this format
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "text/plain; charset=utf-8",
+            ExportFormat::Ansi => "text/x-ansi; charset=utf-8",
+            ExportFormat::Png => "image/png",
+            ExportFormat::Svg => "image/svg+xml",
+            ExportFormat::Html | ExportFormat::HtmlSnippet => "text/html; charset=utf-8",
+        }
+    }
+
+    /// Get the file extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ExportFormat::Text => "txt",
+            ExportFormat::Ansi => "ans",
+            ExportFormat::Png => "png",
+            ExportFormat::Svg => "svg",
+            ExportFormat::Html | ExportFormat::HtmlSnippet => "html",
+        }
+    }
+}
+
+impl fmt::Display for ExportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportFormat::Text => write!(f, "text"),
+            ExportFormat::Ansi => write!(f, "ansi"),
+            ExportFormat::Png => write!(f, "png"),
+            ExportFormat::Svg => write!(f, "svg"),
+            ExportFormat::Html => write!(f, "html"),
+            ExportFormat::HtmlSnippet => write!(f, "html-snippet"),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+
+/// Appearance of ASCII art exported as an image
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExportOptions {
+    /// Text color for cells without a color of their own
+    pub foreground: [u8; 3],
+    /// Fill color behind the text and around it
+    pub background: [u8; 3],
+    /// Use the ANSI colors embedded in colored art for each cell
+    pub cell_colors: bool,
+    /// Blank space around the text, in pixels
+    pub padding: u32,
+    /// Height of a text row in pixels
+    pub font_size: u32,
+}
+
+impl ExportOptions {
+    /// Smallest and largest supported font sizes
+    pub const FONT_SIZE_RANGE: std::ops::RangeInclusive<u32> = 6..=96;
+
+    /// Largest supported padding in pixels
+    pub const MAX_PADDING: u32 = 512;
+
+    /// Check that the font size and padding are in range
+    pub fn is_valid(&self) -> bool {
+        Self::FONT_SIZE_RANGE.contains(&self.font_size) && self.padding <= Self::MAX_PADDING
+    }
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            foreground: [255, 255, 255],
+            background: [0, 0, 0],
+            cell_colors: true,
+            padding: 16,
+            font_size: 13,
+        }
+    }
+}
+
+/// Parse a `#rrggbb`, `rrggbb` or `#rgb` hex color
+pub fn parse_hex_color(value: &str) -> Option<[u8; 3]> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    if !hex.is_ascii() {
+        return None;
+    }
+
+    let channel = |digits: &str| u8::from_str_radix(digits, 16).ok();
+    match hex.len() {
+        6 => Some([
+            channel(&hex[0..2])?,
+            channel(&hex[2..4])?,
+            channel(&hex[4..6])?,
+        ]),
+        3 => {
+            let short = |i: usize| channel(&hex[i..i + 1]).map(|v| v * 17);
+            Some([short(0)?, short(1)?, short(2)?])
+        }
+        _ => None,
+    }
+}
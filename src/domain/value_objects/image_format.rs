@@ -57,6 +57,28 @@ impl ImageFormat {
             _ => None,
         }
     }
+
+    /// Parse format from the extension of a file name
+    pub fn from_filename(filename: &str) -> Option<Self> {
+        filename
+            .rsplit_once('.')
+            .and_then(|(_, extension)| Self::from_extension(extension))
+    }
+
+    /// Detect the format from the signature at the start of the file, whatever the
+    /// file claims to be
+    pub fn from_signature(data: &[u8]) -> Option<Self> {
+        match data {
+            [0xff, 0xd8, 0xff, ..] => Some(ImageFormat::Jpeg),
+            [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n', ..] => Some(ImageFormat::Png),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(ImageFormat::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => {
+                Some(ImageFormat::Webp)
+            }
+            [b'B', b'M', ..] => Some(ImageFormat::Bmp),
+            _ => None,
+        }
+    }
 }
 
 impl fmt::Display for ImageFormat {
@@ -70,3 +92,29 @@ impl fmt::Display for ImageFormat {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_identify_formats() {
+        let cases: [(&[u8], Option<ImageFormat>); 7] = [
+            (b"\xff\xd8\xff\xe0\0\x10JFIF", Some(ImageFormat::Jpeg)),
+            (b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", Some(ImageFormat::Png)),
+            (b"GIF89a\x10\0", Some(ImageFormat::Gif)),
+            (b"RIFF\x24\0\0\0WEBPVP8 ", Some(ImageFormat::Webp)),
+            (b"BM\x36\0\0\0", Some(ImageFormat::Bmp)),
+            (b"RIFF\x24\0\0\0WAVEfmt ", None),
+            (b"\x89PNG", None),
+        ];
+        for (data, format) in cases {
+            assert_eq!(ImageFormat::from_signature(data), format, "{:?}", data);
+        }
+        assert_eq!(
+            ImageFormat::from_filename("photos/cat.JPEG"),
+            Some(ImageFormat::Jpeg)
+        );
+        assert_eq!(ImageFormat::from_filename("README"), None);
+    }
+}
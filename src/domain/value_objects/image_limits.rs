@@ -0,0 +1,40 @@
+use std::fmt;
+
+/// Largest images accepted for decoding, checked against the dimensions in the image
+/// header before any pixels are decoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ImageLimits {
+    pub max_width: u32,
+    pub max_height: u32,
+    /// Largest `width * height`; animations share it across their frames
+    pub max_pixels: u64,
+}
+
+impl ImageLimits {
+    /// Whether an image of the given dimensions is within the limits
+    pub fn allows(&self, width: u32, height: u32) -> bool {
+        width <= self.max_width
+            && height <= self.max_height
+            && width as u64 * height as u64 <= self.max_pixels
+    }
+}
+
+impl Default for ImageLimits {
+    fn default() -> Self {
+        Self {
+            max_width: 16_384,
+            max_height: 16_384,
+            max_pixels: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl fmt::Display for ImageLimits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}x{}, {} pixels",
+            self.max_width, self.max_height, self.max_pixels
+        )
+    }
+}
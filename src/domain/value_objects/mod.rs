@@ -1,5 +1,23 @@
+pub mod char_ramp;
+pub mod color_mode;
 pub mod conversion_config;
+pub mod conversion_params;
+pub mod dithering;
+pub mod edge_options;
+pub mod export_format;
+pub mod export_options;
 pub mod image_format;
+pub mod image_limits;
+pub mod render_mode;
 
+pub use char_ramp::{CharRamp, CharRampError};
+pub use color_mode::ColorMode;
 pub use conversion_config::ConversionConfig;
+pub use conversion_params::{ConversionParams, ConversionParamsError};
+pub use dithering::Dithering;
+pub use edge_options::{EdgeFill, EdgeOptions};
+pub use export_format::ExportFormat;
+pub use export_options::{parse_hex_color, ExportOptions};
 pub use image_format::ImageFormat;
+pub use image_limits::ImageLimits;
+pub use render_mode::RenderMode;
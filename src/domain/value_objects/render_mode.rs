@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Rendering strategy used to turn image cells into characters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum RenderMode {
+    /// One character per cell picked from an intensity ramp
+    #[default]
+    Ascii,
+    /// Unicode Braille patterns packing 2x4 pixels into each cell
+    Braille,
+    /// Upper/lower half blocks doubling the vertical resolution
+    HalfBlock,
+    /// Quadrant block elements splitting each cell into 2x2 pixels
+    Quadrant,
+    /// Ramp glyphs matched to each cell by shape using a rasterized font
+    Structural,
+    /// Orientation glyphs along detected edges, filled per the edge options
+    Edges,
+}
+
+impl RenderMode {
+    /// Parse render mode from its query parameter name
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "ascii" => Some(RenderMode::Ascii),
+            "braille" => Some(RenderMode::Braille),
+            "halfblock" | "half-block" => Some(RenderMode::HalfBlock),
+            "quadrant" => Some(RenderMode::Quadrant),
+            "structural" | "glyph" => Some(RenderMode::Structural),
+            "edges" | "edge" => Some(RenderMode::Edges),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RenderMode::Ascii => write!(f, "ascii"),
+            RenderMode::Braille => write!(f, "braille"),
+            RenderMode::HalfBlock => write!(f, "halfblock"),
+            RenderMode::Quadrant => write!(f, "quadrant"),
+            RenderMode::Structural => write!(f, "structural"),
+            RenderMode::Edges => write!(f, "edges"),
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+use tokio::{fs, io::AsyncWriteExt, sync::RwLock};
+use uuid::Uuid;
+
+/// Name of the metadata index inside a store directory
+const INDEX_FILE: &str = "index.json";
+
+/// Extension of files that are still being written
+const TEMP_EXTENSION: &str = "tmp";
+
+#[derive(Error, Debug)]
+pub enum FileStoreError {
+    #[error("Storage I/O error: {0}")]
+    Io(#[from] io::Error),
+    #[error("Corrupt storage index: {0}")]
+    Index(#[from] serde_json::Error),
+}
+
+/// Secondary key an entry can be looked up by, if it has one
+type KeyFn<M> = fn(&M) -> Option<&str>;
+
+/// Metadata of every entry, and the IDs of entries by secondary key when the store has
+/// one
+struct Index<M> {
+    entries: HashMap<Uuid, M>,
+    keys: HashMap<String, Uuid>,
+    key: Option<KeyFn<M>>,
+}
+
+impl<M> Index<M> {
+    fn new(entries: HashMap<Uuid, M>, key: Option<KeyFn<M>>) -> Self {
+        let mut index = Self {
+            entries: HashMap::new(),
+            keys: HashMap::new(),
+            key,
+        };
+        for (id, metadata) in entries {
+            index.insert(id, metadata);
+        }
+        index
+    }
+
+    fn insert(&mut self, id: Uuid, metadata: M) -> Option<M> {
+        if let Some(key) = self.key.and_then(|key| key(&metadata)) {
+            self.keys.insert(key.to_string(), id);
+        }
+        let previous = self.entries.insert(id, metadata)?;
+        self.unindex(&id, &previous);
+        Some(previous)
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Option<M> {
+        let metadata = self.entries.remove(id)?;
+        self.unindex(id, &metadata);
+        Some(metadata)
+    }
+
+    /// Forget the key of metadata no longer stored under `id`
+    fn unindex(&mut self, id: &Uuid, metadata: &M) {
+        if let Some(key) = self.key.and_then(|key| key(metadata)) {
+            let current = self
+                .entries
+                .get(id)
+                .and_then(|metadata| (self.key?)(metadata));
+            if current != Some(key) && self.keys.get(key) == Some(id) {
+                self.keys.remove(key);
+            }
+        }
+    }
+}
+
+/// Directory of blob files named by ID, plus a JSON index holding the metadata of each
+/// blob. Files are replaced atomically (written to a temporary file, synced, then
+/// renamed), and a blob is written before its index entry and removed after it, so an
+/// interrupted write leaves at most an orphaned file that is cleaned up on the next open.
+/// Both happen under the index write lock, so a blob is never removed once another writer
+/// has stored the same ID again.
+pub(crate) struct FileStore<M> {
+    directory: PathBuf,
+    blob_extension: &'static str,
+    index: RwLock<Index<M>>,
+}
+
+impl<M> FileStore<M>
+where
+    M: Serialize + DeserializeOwned + Clone + Send + Sync,
+{
+    /// Open the store in `directory`, creating it if needed and recovering from
+    /// writes that were interrupted by a crash
+    pub async fn open(
+        directory: impl Into<PathBuf>,
+        blob_extension: &'static str,
+    ) -> Result<Self, FileStoreError> {
+        Self::open_with_key(directory, blob_extension, None).await
+    }
+
+    /// Open the store in `directory` like [`FileStore::open`], keeping entries
+    /// retrievable by the key `key` returns with [`FileStore::get_by_key`]. An entry's
+    /// key must not change while it is stored.
+    pub async fn open_with_key(
+        directory: impl Into<PathBuf>,
+        blob_extension: &'static str,
+        key: Option<KeyFn<M>>,
+    ) -> Result<Self, FileStoreError> {
+        let directory = directory.into();
+        fs::create_dir_all(&directory).await?;
+
+        let mut index: HashMap<Uuid, M> = match fs::read(directory.join(INDEX_FILE)).await {
+            Ok(bytes) => serde_json::from_slice(&bytes)?,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => HashMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        // Drop partially written files and blobs whose index entry was never written
+        let mut blobs = HashSet::new();
+        let mut entries = fs::read_dir(&directory).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+            let extension = path.extension().and_then(|e| e.to_str());
+            let id = path
+                .file_stem()
+                .and_then(|s| s.to_str())
+                .and_then(|s| Uuid::parse_str(s).ok());
+
+            if extension == Some(TEMP_EXTENSION) {
+                fs::remove_file(&path).await?;
+            } else if let (Some(id), Some(extension)) = (id, extension) {
+                if extension != blob_extension {
+                    continue;
+                }
+                if index.contains_key(&id) {
+                    blobs.insert(id);
+                } else {
+                    fs::remove_file(&path).await?;
+                }
+            }
+        }
+
+        // Forget entries whose blob has gone missing
+        let indexed = index.len();
+        index.retain(|id, _| blobs.contains(id));
+        if index.len() != indexed {
+            write_index(&directory, &index).await?;
+        }
+
+        Ok(Self {
+            directory,
+            blob_extension,
+            index: RwLock::new(Index::new(index, key)),
+        })
+    }
+
+    /// Get the metadata and blob stored under `id`
+    pub async fn get(&self, id: Uuid) -> Result<Option<(M, Vec<u8>)>, FileStoreError> {
+        let metadata = self.index.read().await.entries.get(&id).cloned();
+        match metadata {
+            Some(metadata) => Ok(self.read_blob(id).await?.map(|blob| (metadata, blob))),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the metadata and blob of the entry with the secondary key `key`
+    pub async fn get_by_key(&self, key: &str) -> Result<Option<(M, Vec<u8>)>, FileStoreError> {
+        let entry = {
+            let index = self.index.read().await;
+            index
+                .keys
+                .get(key)
+                .and_then(|id| Some((*id, index.entries.get(id)?.clone())))
+        };
+        match entry {
+            Some((id, metadata)) => Ok(self.read_blob(id).await?.map(|blob| (metadata, blob))),
+            None => Ok(None),
+        }
+    }
+
+    /// Get the metadata and blob of every entry whose metadata matches `predicate`
+    pub async fn find(
+        &self,
+        predicate: impl Fn(&M) -> bool,
+    ) -> Result<Vec<(M, Vec<u8>)>, FileStoreError> {
+        let matches = self.find_metadata(predicate).await;
+        self.load(matches).await
+    }
+
+    /// Get the id and metadata of every entry whose metadata matches `predicate`,
+    /// without reading any blob
+    pub async fn find_metadata(&self, predicate: impl Fn(&M) -> bool) -> Vec<(Uuid, M)> {
+        self.index
+            .read()
+            .await
+            .entries
+            .iter()
+            .filter(|(_, metadata)| predicate(metadata))
+            .map(|(id, metadata)| (*id, metadata.clone()))
+            .collect()
+    }
+
+    /// Read the blobs of entries found by [`FileStore::find_metadata`], skipping
+    /// entries removed since
+    pub async fn load(&self, entries: Vec<(Uuid, M)>) -> Result<Vec<(M, Vec<u8>)>, FileStoreError> {
+        let mut results = Vec::with_capacity(entries.len());
+        for (id, metadata) in entries {
+            if let Some(blob) = self.read_blob(id).await? {
+                results.push((metadata, blob));
+            }
+        }
+        Ok(results)
+    }
+
+    /// Store a blob and its metadata under `id`, replacing any previous entry
+    pub async fn insert(&self, id: Uuid, metadata: M, blob: &[u8]) -> Result<(), FileStoreError> {
+        let mut index = self.index.write().await;
+        write_atomic(&self.blob_path(id), blob).await?;
+
+        let previous = index.insert(id, metadata);
+        if let Err(e) = write_index(&self.directory, &index.entries).await {
+            match previous {
+                Some(previous) => index.insert(id, previous),
+                None => index.remove(&id),
+            };
+            return Err(e);
+        }
+        Ok(())
+    }
+
+    /// Remove the entry stored under `id`, if any
+    pub async fn remove(&self, id: Uuid) -> Result<(), FileStoreError> {
+        self.update(id, |_| false).await.map(|_| ())
+    }
+
+    /// Change the metadata stored under `id` in place, or remove the entry when `change`
+    /// returns false. Returns the changed metadata, or `None` if there is no such entry.
+    pub async fn update(
+        &self,
+        id: Uuid,
+        change: impl FnOnce(&mut M) -> bool,
+    ) -> Result<Option<M>, FileStoreError> {
+        let mut index = self.index.write().await;
+        let Some(metadata) = index.entries.get_mut(&id) else {
+            return Ok(None);
+        };
+        let previous = metadata.clone();
+        let keep = change(metadata);
+        let updated = metadata.clone();
+        if !keep {
+            index.remove(&id);
+        }
+        if let Err(e) = write_index(&self.directory, &index.entries).await {
+            index.insert(id, previous);
+            return Err(e);
+        }
+
+        if !keep {
+            if let Err(e) = fs::remove_file(self.blob_path(id)).await {
+                if e.kind() != io::ErrorKind::NotFound {
+                    return Err(e.into());
+                }
+            }
+        }
+        Ok(Some(updated))
+    }
+
+    fn blob_path(&self, id: Uuid) -> PathBuf {
+        self.directory
+            .join(format!("{}.{}", id, self.blob_extension))
+    }
+
+    async fn read_blob(&self, id: Uuid) -> Result<Option<Vec<u8>>, FileStoreError> {
+        match fs::read(self.blob_path(id)).await {
+            Ok(blob) => Ok(Some(blob)),
+            // Removed concurrently, after the index entry was read
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+/// Persist the index; callers hold the index write lock, which serializes writers
+async fn write_index<M: Serialize>(
+    directory: &Path,
+    index: &HashMap<Uuid, M>,
+) -> Result<(), FileStoreError> {
+    let bytes = serde_json::to_vec(index)?;
+    write_atomic(&directory.join(INDEX_FILE), &bytes).await?;
+    Ok(())
+}
+
+/// Replace the file at `path` so that readers and crashes only ever observe either the
+/// old or the new contents
+async fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let temp_path = path.with_extension(TEMP_EXTENSION);
+    let mut file = fs::File::create(&temp_path).await?;
+    file.write_all(bytes).await?;
+    file.sync_all().await?;
+    drop(file);
+
+    fs::rename(&temp_path, path).await?;
+    match path.parent() {
+        Some(directory) => sync_directory(directory).await,
+        None => Ok(()),
+    }
+}
+
+/// Make a rename durable by syncing the directory entry
+#[cfg(unix)]
+async fn sync_directory(directory: &Path) -> io::Result<()> {
+    fs::File::open(directory).await?.sync_all().await
+}
+
+#[cfg(not(unix))]
+async fn sync_directory(_directory: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reopening_recovers_from_interrupted_writes() {
+        let directory = std::env::temp_dir().join(format!("file-store-{}", Uuid::new_v4()));
+        let (kept, removed) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let store = FileStore::<String>::open(&directory, "bin").await.unwrap();
+        store
+            .insert(kept, "kept".to_string(), b"abc")
+            .await
+            .unwrap();
+        store
+            .insert(removed, "removed".to_string(), b"def")
+            .await
+            .unwrap();
+        drop(store);
+
+        // Simulate a crash mid-write, an orphaned blob and a lost blob
+        let orphan = Uuid::new_v4();
+        std::fs::write(
+            directory.join(format!("{}.tmp", Uuid::new_v4())),
+            b"partial",
+        )
+        .unwrap();
+        std::fs::write(directory.join(format!("{}.bin", orphan)), b"orphan").unwrap();
+        std::fs::remove_file(directory.join(format!("{}.bin", removed))).unwrap();
+
+        let store = FileStore::<String>::open(&directory, "bin").await.unwrap();
+        assert_eq!(
+            store.get(kept).await.unwrap(),
+            Some(("kept".to_string(), b"abc".to_vec()))
+        );
+        assert_eq!(store.get(removed).await.unwrap(), None);
+        assert_eq!(store.find(|_| true).await.unwrap().len(), 1);
+        assert_eq!(std::fs::read_dir(&directory).unwrap().count(), 2);
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn entries_are_found_by_key_until_removed() {
+        let directory = std::env::temp_dir().join(format!("file-store-{}", Uuid::new_v4()));
+        let key: KeyFn<String> = |metadata| metadata.get(..1);
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+
+        let store = FileStore::open_with_key(&directory, "bin", Some(key))
+            .await
+            .unwrap();
+        store.insert(first, "a1".to_string(), b"abc").await.unwrap();
+        store
+            .insert(second, "b1".to_string(), b"def")
+            .await
+            .unwrap();
+        drop(store);
+
+        let store = FileStore::open_with_key(&directory, "bin", Some(key))
+            .await
+            .unwrap();
+        assert_eq!(
+            store.get_by_key("a").await.unwrap(),
+            Some(("a1".to_string(), b"abc".to_vec()))
+        );
+        store
+            .insert(second, "c1".to_string(), b"ghi")
+            .await
+            .unwrap();
+        assert_eq!(store.get_by_key("b").await.unwrap(), None);
+        store.remove(first).await.unwrap();
+        assert_eq!(store.get_by_key("a").await.unwrap(), None);
+        assert_eq!(
+            store
+                .get_by_key("c")
+                .await
+                .unwrap()
+                .map(|(metadata, _)| metadata),
+            Some("c1".to_string())
+        );
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[tokio::test]
+    async fn removing_and_reinserting_concurrently_keeps_every_indexed_blob() {
+        let directory = std::env::temp_dir().join(format!("file-store-{}", Uuid::new_v4()));
+        let store =
+            std::sync::Arc::new(FileStore::<String>::open(&directory, "bin").await.unwrap());
+        let id = Uuid::new_v4();
+
+        for _ in 0..20 {
+            store.insert(id, "old".to_string(), b"old").await.unwrap();
+            let (remover, inserter) =
+                (std::sync::Arc::clone(&store), std::sync::Arc::clone(&store));
+            let (removed, inserted) = tokio::join!(
+                tokio::spawn(async move { remover.remove(id).await }),
+                tokio::spawn(async move { inserter.insert(id, "new".to_string(), b"new").await }),
+            );
+            removed.unwrap().unwrap();
+            inserted.unwrap().unwrap();
+
+            let indexed = store.find_metadata(|_| true).await.len();
+            assert_eq!(store.find(|_| true).await.unwrap().len(), indexed);
+        }
+
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,177 @@
+use super::file_store::{FileStore, FileStoreError};
+use crate::domain::{
+    entities::{ascii_art::DetailLevel, AsciiArt, AsciiFrame},
+    repositories::{AsciiArtQuery, AsciiArtRepository},
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+use uuid::Uuid;
+
+/// Everything about a conversion except its text, kept in the store index
+#[derive(Clone, Serialize, Deserialize)]
+struct AsciiArtMetadata {
+    id: Uuid,
+    image_id: Uuid,
+    width: u32,
+    height: u32,
+    detail_level: DetailLevel,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Text of a conversion, stored as the blob file
+#[derive(Serialize, Deserialize)]
+struct AsciiArtContent {
+    content: String,
+    #[serde(default)]
+    frames: Vec<AsciiFrame>,
+}
+
+/// Filesystem implementation of AsciiArtRepository, storing the text of each
+/// conversion as a file next to a JSON metadata index
+#[derive(Clone)]
+pub struct FileSystemAsciiArtRepository {
+    store: Arc<FileStore<AsciiArtMetadata>>,
+}
+
+impl FileSystemAsciiArtRepository {
+    /// Open the repository under `root`, creating it if needed
+    pub async fn open(root: impl AsRef<Path>) -> Result<Self, FileStoreError> {
+        let store = FileStore::open(root.as_ref().join("ascii_art"), "json").await?;
+        Ok(Self {
+            store: Arc::new(store),
+        })
+    }
+
+    fn assemble(metadata: AsciiArtMetadata, blob: &[u8]) -> Result<AsciiArt, FileStoreError> {
+        let AsciiArtContent { content, frames } = serde_json::from_slice(blob)?;
+        Ok(AsciiArt {
+            id: metadata.id,
+            image_id: metadata.image_id,
+            content,
+            width: metadata.width,
+            height: metadata.height,
+            detail_level: metadata.detail_level,
+            created_at: metadata.created_at,
+            frames,
+        })
+    }
+}
+
+#[async_trait]
+impl AsciiArtRepository for FileSystemAsciiArtRepository {
+    type Error = FileStoreError;
+
+    async fn save(&self, ascii_art: &AsciiArt) -> Result<(), Self::Error> {
+        let metadata = AsciiArtMetadata {
+            id: ascii_art.id,
+            image_id: ascii_art.image_id,
+            width: ascii_art.width,
+            height: ascii_art.height,
+            detail_level: ascii_art.detail_level,
+            created_at: ascii_art.created_at,
+        };
+        let blob = serde_json::to_vec(&AsciiArtContent {
+            content: ascii_art.content.clone(),
+            frames: ascii_art.frames.clone(),
+        })?;
+        self.store.insert(ascii_art.id, metadata, &blob).await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AsciiArt>, Self::Error> {
+        self.store
+            .get(id)
+            .await?
+            .map(|(metadata, blob)| Self::assemble(metadata, &blob))
+            .transpose()
+    }
+
+    async fn find_by_image_id(&self, image_id: Uuid) -> Result<Vec<AsciiArt>, Self::Error> {
+        self.store
+            .find(|metadata| metadata.image_id == image_id)
+            .await?
+            .into_iter()
+            .map(|(metadata, blob)| Self::assemble(metadata, &blob))
+            .collect()
+    }
+
+    async fn list(&self, query: &AsciiArtQuery) -> Result<Vec<AsciiArt>, Self::Error> {
+        // Page on the index so only the returned conversions are read from disk
+        let mut matches = self
+            .store
+            .find_metadata(|m| query.matches(m.image_id, m.detail_level, m.created_at))
+            .await;
+        matches.sort_by_key(|(id, metadata)| (std::cmp::Reverse(metadata.created_at), *id));
+        self.store
+            .load(query.paginate(matches))
+            .await?
+            .into_iter()
+            .map(|(metadata, blob)| Self::assemble(metadata, &blob))
+            .collect()
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
+        self.store.remove(id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn list_pages_newest_first_reading_only_the_page() {
+        let root = std::env::temp_dir().join(format!("ascii-art-repo-{}", Uuid::new_v4()));
+        let repository = FileSystemAsciiArtRepository::open(&root).await.unwrap();
+        let image_id = Uuid::new_v4();
+        let start = chrono::Utc::now();
+
+        let mut saved = Vec::new();
+        for minutes in 0..4 {
+            let mut art =
+                AsciiArt::new(image_id, format!("art {minutes}"), 5, 1, DetailLevel::High);
+            art.created_at = start + chrono::Duration::minutes(minutes);
+            repository.save(&art).await.unwrap();
+            saved.push(art.id);
+        }
+
+        // A blob outside the requested page is never read
+        std::fs::write(
+            root.join("ascii_art").join(format!("{}.json", saved[0])),
+            b"corrupt",
+        )
+        .unwrap();
+
+        let query = AsciiArtQuery {
+            image_id: Some(image_id),
+            offset: 1,
+            limit: Some(2),
+            ..AsciiArtQuery::default()
+        };
+        let page: Vec<_> = repository
+            .list(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|art| art.id)
+            .collect();
+        assert_eq!(page, vec![saved[2], saved[1]]);
+
+        repository.delete(saved[2]).await.unwrap();
+        assert!(repository.find_by_id(saved[2]).await.unwrap().is_none());
+        let query = AsciiArtQuery {
+            limit: Some(1),
+            ..query
+        };
+        let page: Vec<_> = repository
+            .list(&query)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|art| art.id)
+            .collect();
+        assert_eq!(page, vec![saved[1]]);
+
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
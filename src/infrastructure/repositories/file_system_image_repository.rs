@@ -0,0 +1,125 @@
+use super::file_store::{FileStore, FileStoreError};
+use crate::domain::{
+    entities::ImageData, repositories::ImageRepository, value_objects::ImageFormat,
+};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+use uuid::Uuid;
+
+/// Everything about an image except its bytes, kept in the store index
+#[derive(Clone, Serialize, Deserialize)]
+struct ImageMetadata {
+    id: Uuid,
+    original_filename: String,
+    content_type: String,
+    width: u32,
+    height: u32,
+    // Indexes written before deduplication have neither; their images are never
+    // matched by hash and keep a single reference
+    #[serde(default)]
+    content_hash: String,
+    #[serde(default = "default_ref_count")]
+    ref_count: u32,
+    #[serde(default)]
+    declared_format: Option<ImageFormat>,
+    #[serde(default)]
+    detected_format: Option<ImageFormat>,
+}
+
+fn default_ref_count() -> u32 {
+    1
+}
+
+/// Key images are looked up by to deduplicate uploads
+fn content_hash(metadata: &ImageMetadata) -> Option<&str> {
+    Some(metadata.content_hash.as_str()).filter(|hash| !hash.is_empty())
+}
+
+/// Filesystem implementation of ImageRepository, storing the original bytes of each
+/// image as a file next to a JSON metadata index
+#[derive(Clone)]
+pub struct FileSystemImageRepository {
+    store: Arc<FileStore<ImageMetadata>>,
+}
+
+impl FileSystemImageRepository {
+    /// Open the repository under `root`, creating it if needed
+    pub async fn open(root: impl AsRef<Path>) -> Result<Self, FileStoreError> {
+        let store =
+            FileStore::open_with_key(root.as_ref().join("images"), "bin", Some(content_hash))
+                .await?;
+        Ok(Self {
+            store: Arc::new(store),
+        })
+    }
+}
+
+#[async_trait]
+impl ImageRepository for FileSystemImageRepository {
+    type Error = FileStoreError;
+
+    async fn save(&self, image: &ImageData) -> Result<(), Self::Error> {
+        let metadata = ImageMetadata {
+            id: image.id,
+            original_filename: image.original_filename.clone(),
+            content_type: image.content_type.clone(),
+            width: image.width,
+            height: image.height,
+            content_hash: image.content_hash.clone(),
+            ref_count: image.ref_count,
+            declared_format: image.declared_format,
+            detected_format: image.detected_format,
+        };
+        self.store.insert(image.id, metadata, &image.data).await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<ImageData>, Self::Error> {
+        Ok(self.store.get(id).await?.map(image_data))
+    }
+
+    async fn find_by_content_hash(&self, hash: &str) -> Result<Option<ImageData>, Self::Error> {
+        Ok(self.store.get_by_key(hash).await?.map(image_data))
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
+        self.store.remove(id).await
+    }
+
+    async fn retain(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        let metadata = self
+            .store
+            .update(id, |metadata| {
+                metadata.ref_count += 1;
+                true
+            })
+            .await?;
+        Ok(metadata.map(|metadata| metadata.ref_count))
+    }
+
+    async fn release(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        let metadata = self
+            .store
+            .update(id, |metadata| {
+                metadata.ref_count = metadata.ref_count.saturating_sub(1);
+                metadata.ref_count > 0
+            })
+            .await?;
+        Ok(metadata.map(|metadata| metadata.ref_count))
+    }
+}
+
+fn image_data((metadata, data): (ImageMetadata, Vec<u8>)) -> ImageData {
+    ImageData {
+        id: metadata.id,
+        original_filename: metadata.original_filename,
+        content_type: metadata.content_type,
+        data,
+        width: metadata.width,
+        height: metadata.height,
+        content_hash: metadata.content_hash,
+        ref_count: metadata.ref_count,
+        declared_format: metadata.declared_format,
+        detected_format: metadata.detected_format,
+    }
+}
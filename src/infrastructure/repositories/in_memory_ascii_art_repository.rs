@@ -1,29 +1,46 @@
-use crate::domain::{entities::AsciiArt, repositories::AsciiArtRepository};
+use crate::domain::{
+    entities::AsciiArt,
+    repositories::{AsciiArtQuery, AsciiArtRepository, StorageStats},
+};
+use crate::infrastructure::repositories::memory_store::{MemoryLimits, MemoryStore, QuotaExceeded};
 use async_trait::async_trait;
-use std::{collections::HashMap, sync::Arc};
+use std::{mem, sync::Arc, time::Duration};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 #[derive(Error, Debug)]
 pub enum InMemoryAsciiArtRepositoryError {
     #[error("ASCII art not found")]
     NotFound,
+    #[error(transparent)]
+    QuotaExceeded(#[from] QuotaExceeded),
 }
 
-/// In-memory implementation of AsciiArtRepository for development/testing
+/// In-memory implementation of AsciiArtRepository for development/testing. Conversions
+/// can be given a time to live and a memory quota, see [`MemoryLimits`].
 #[derive(Clone)]
 pub struct InMemoryAsciiArtRepository {
-    storage: Arc<RwLock<HashMap<Uuid, AsciiArt>>>,
+    storage: Arc<MemoryStore<AsciiArt>>,
 }
 
 impl InMemoryAsciiArtRepository {
     /// Create a new in-memory ASCII art repository
     pub fn new() -> Self {
+        Self::with_limits(MemoryLimits::default())
+    }
+
+    /// Create an in-memory ASCII art repository that expires and evicts conversions
+    pub fn with_limits(limits: MemoryLimits) -> Self {
         Self {
-            storage: Arc::new(RwLock::new(HashMap::new())),
+            storage: Arc::new(MemoryStore::new(limits)),
         }
     }
+
+    /// Drop expired conversions every `interval`, for as long as the repository exists
+    pub fn spawn_sweeper(&self, interval: Duration) -> JoinHandle<()> {
+        MemoryStore::spawn_sweeper(&self.storage, interval)
+    }
 }
 
 impl Default for InMemoryAsciiArtRepository {
@@ -32,34 +49,51 @@ impl Default for InMemoryAsciiArtRepository {
     }
 }
 
+/// Approximate memory taken by stored ASCII art
+fn stored_size(ascii_art: &AsciiArt) -> usize {
+    mem::size_of::<AsciiArt>()
+        + ascii_art.content.len()
+        + ascii_art
+            .frames
+            .iter()
+            .map(|frame| mem::size_of_val(frame) + frame.content.len())
+            .sum::<usize>()
+}
+
 #[async_trait]
 impl AsciiArtRepository for InMemoryAsciiArtRepository {
     type Error = InMemoryAsciiArtRepositoryError;
 
     async fn save(&self, ascii_art: &AsciiArt) -> Result<(), Self::Error> {
-        let mut storage = self.storage.write().await;
-        storage.insert(ascii_art.id, ascii_art.clone());
+        self.storage
+            .insert(ascii_art.id, ascii_art.clone(), stored_size(ascii_art))?;
         Ok(())
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<AsciiArt>, Self::Error> {
-        let storage = self.storage.read().await;
-        Ok(storage.get(&id).cloned())
+        Ok(self.storage.get(id))
     }
 
     async fn find_by_image_id(&self, image_id: Uuid) -> Result<Vec<AsciiArt>, Self::Error> {
-        let storage = self.storage.read().await;
-        let results: Vec<AsciiArt> = storage
-            .values()
-            .filter(|ascii_art| ascii_art.image_id == image_id)
-            .cloned()
-            .collect();
-        Ok(results)
+        Ok(self
+            .storage
+            .filter(|ascii_art| ascii_art.image_id == image_id))
+    }
+
+    async fn list(&self, query: &AsciiArtQuery) -> Result<Vec<AsciiArt>, Self::Error> {
+        let mut results = self
+            .storage
+            .filter(|a| query.matches(a.image_id, a.detail_level, a.created_at));
+        results.sort_by_key(|a| (std::cmp::Reverse(a.created_at), a.id));
+        Ok(query.paginate(results))
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
-        let mut storage = self.storage.write().await;
-        storage.remove(&id);
+        self.storage.remove(id);
         Ok(())
     }
+
+    fn storage_stats(&self) -> Option<StorageStats> {
+        Some(self.storage.stats())
+    }
 }
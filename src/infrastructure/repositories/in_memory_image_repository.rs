@@ -1,29 +1,46 @@
-use crate::domain::{entities::ImageData, repositories::ImageRepository};
+use crate::domain::{
+    entities::ImageData,
+    repositories::{EvictionCallback, ImageRepository, StorageStats},
+};
+use crate::infrastructure::repositories::memory_store::{MemoryLimits, MemoryStore, QuotaExceeded};
 use async_trait::async_trait;
-use std::{collections::HashMap, sync::Arc};
+use std::{mem, sync::Arc, time::Duration};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
 #[derive(Error, Debug)]
 pub enum InMemoryImageRepositoryError {
     #[error("Image not found")]
     NotFound,
+    #[error(transparent)]
+    QuotaExceeded(#[from] QuotaExceeded),
 }
 
-/// In-memory implementation of ImageRepository for development/testing
+/// In-memory implementation of ImageRepository for development/testing. Images can be
+/// given a time to live and a memory quota, see [`MemoryLimits`].
 #[derive(Clone)]
 pub struct InMemoryImageRepository {
-    storage: Arc<RwLock<HashMap<Uuid, ImageData>>>,
+    storage: Arc<MemoryStore<ImageData>>,
 }
 
 impl InMemoryImageRepository {
     /// Create a new in-memory image repository
     pub fn new() -> Self {
+        Self::with_limits(MemoryLimits::default())
+    }
+
+    /// Create an in-memory image repository that expires and evicts images
+    pub fn with_limits(limits: MemoryLimits) -> Self {
         Self {
-            storage: Arc::new(RwLock::new(HashMap::new())),
+            storage: Arc::new(MemoryStore::with_key(limits, content_hash)),
         }
     }
+
+    /// Drop expired images every `interval`, for as long as the repository exists
+    pub fn spawn_sweeper(&self, interval: Duration) -> JoinHandle<()> {
+        MemoryStore::spawn_sweeper(&self.storage, interval)
+    }
 }
 
 impl Default for InMemoryImageRepository {
@@ -32,24 +49,68 @@ impl Default for InMemoryImageRepository {
     }
 }
 
+/// Key images are looked up by to deduplicate uploads
+fn content_hash(image: &ImageData) -> Option<&str> {
+    Some(&image.content_hash)
+}
+
+/// Approximate memory taken by a stored image
+fn stored_size(image: &ImageData) -> usize {
+    mem::size_of::<ImageData>()
+        + image.data.len()
+        + image.original_filename.len()
+        + image.content_type.len()
+        + image.content_hash.len()
+}
+
 #[async_trait]
 impl ImageRepository for InMemoryImageRepository {
     type Error = InMemoryImageRepositoryError;
 
     async fn save(&self, image: &ImageData) -> Result<(), Self::Error> {
-        let mut storage = self.storage.write().await;
-        storage.insert(image.id, image.clone());
+        self.storage
+            .insert(image.id, image.clone(), stored_size(image))?;
         Ok(())
     }
 
     async fn find_by_id(&self, id: Uuid) -> Result<Option<ImageData>, Self::Error> {
-        let storage = self.storage.read().await;
-        Ok(storage.get(&id).cloned())
+        Ok(self.storage.get(id))
+    }
+
+    async fn find_by_content_hash(&self, hash: &str) -> Result<Option<ImageData>, Self::Error> {
+        Ok(self.storage.get_by_key(hash))
     }
 
     async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
-        let mut storage = self.storage.write().await;
-        storage.remove(&id);
+        self.storage.remove(id);
         Ok(())
     }
+
+    async fn retain(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        Ok(self
+            .storage
+            .update(id, |image| {
+                image.ref_count += 1;
+                true
+            })
+            .map(|image| image.ref_count))
+    }
+
+    async fn release(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        Ok(self
+            .storage
+            .update(id, |image| {
+                image.ref_count = image.ref_count.saturating_sub(1);
+                image.ref_count > 0
+            })
+            .map(|image| image.ref_count))
+    }
+
+    fn storage_stats(&self) -> Option<StorageStats> {
+        Some(self.storage.stats())
+    }
+
+    fn on_evict(&self, callback: EvictionCallback) {
+        self.storage.on_evict(callback);
+    }
 }
@@ -0,0 +1,434 @@
+use crate::domain::repositories::{EvictionCallback, StorageStats};
+use hashlink::LinkedHashMap;
+use std::{
+    collections::HashMap,
+    mem,
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex, MutexGuard},
+    time::{Duration, Instant},
+};
+use thiserror::Error;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+#[derive(Error, Debug)]
+#[error("Entry of {size} bytes exceeds the memory quota of {max_bytes} bytes")]
+pub struct QuotaExceeded {
+    pub size: usize,
+    pub max_bytes: usize,
+}
+
+/// Bounds on what an in-memory repository keeps; unset limits don't apply
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryLimits {
+    /// How long an entry is kept after it was last stored
+    pub ttl: Option<Duration>,
+    /// Total size of the entries, beyond which the least recently used are evicted
+    pub max_bytes: Option<usize>,
+}
+
+struct Entry<V> {
+    value: V,
+    size: usize,
+    expires_at: Option<Instant>,
+}
+
+impl<V> Entry<V> {
+    fn is_expired(&self, now: Instant) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Secondary key a value can be looked up by, if it has one
+type KeyFn<V> = fn(&V) -> Option<&str>;
+
+struct Entries<V> {
+    /// Least recently used first
+    entries: LinkedHashMap<Uuid, Entry<V>>,
+    /// IDs by secondary key, when the store has one
+    keys: HashMap<String, Uuid>,
+    key: Option<KeyFn<V>>,
+    bytes: usize,
+    expired: u64,
+    evicted: u64,
+    /// Entries expired or evicted since the store was last unlocked
+    dropped: Vec<Uuid>,
+}
+
+impl<V> Entries<V> {
+    fn insert(&mut self, id: Uuid, entry: Entry<V>) {
+        if let Some(key) = self.key.and_then(|key| key(&entry.value)) {
+            self.keys.insert(key.to_string(), id);
+        }
+        self.bytes += entry.size;
+        self.entries.insert(id, entry);
+    }
+
+    fn remove(&mut self, id: &Uuid) -> Option<Entry<V>> {
+        let entry = self.entries.remove(id)?;
+        self.unindex(id, &entry);
+        Some(entry)
+    }
+
+    /// Evict the least recently used entry
+    fn pop_front(&mut self) -> Option<Entry<V>> {
+        let (id, entry) = self.entries.pop_front()?;
+        self.unindex(&id, &entry);
+        self.dropped.push(id);
+        Some(entry)
+    }
+
+    fn unindex(&mut self, id: &Uuid, entry: &Entry<V>) {
+        self.bytes -= entry.size;
+        if let Some(key) = self.key.and_then(|key| key(&entry.value)) {
+            if self.keys.get(key) == Some(id) {
+                self.keys.remove(key);
+            }
+        }
+    }
+
+    /// Remove the entry if its time ran out, counting it as expired
+    fn remove_if_expired(&mut self, id: &Uuid, now: Instant) -> bool {
+        let expired = self
+            .entries
+            .get(id)
+            .is_some_and(|entry| entry.is_expired(now));
+        if expired {
+            self.remove(id);
+            self.expired += 1;
+            self.dropped.push(*id);
+        }
+        expired
+    }
+}
+
+/// Entries of an in-memory repository with their sizes, kept in order of use so that
+/// the least recently used can be evicted once the memory quota is exceeded. Expired
+/// entries are never returned, and are dropped by [`MemoryStore::sweep`] or when next
+/// looked up.
+pub(super) struct MemoryStore<V> {
+    state: Mutex<Entries<V>>,
+    limits: MemoryLimits,
+    on_evict: Mutex<Option<EvictionCallback>>,
+}
+
+/// Locked entries, reporting those expired or evicted meanwhile once unlocked, so that
+/// the callback is free to use the store
+struct Locked<'a, V> {
+    guard: Option<MutexGuard<'a, Entries<V>>>,
+    on_evict: &'a Mutex<Option<EvictionCallback>>,
+}
+
+impl<V> Deref for Locked<'_, V> {
+    type Target = Entries<V>;
+
+    fn deref(&self) -> &Entries<V> {
+        self.guard
+            .as_ref()
+            .expect("entries are locked until dropped")
+    }
+}
+
+impl<V> DerefMut for Locked<'_, V> {
+    fn deref_mut(&mut self) -> &mut Entries<V> {
+        self.guard
+            .as_mut()
+            .expect("entries are locked until dropped")
+    }
+}
+
+impl<V> Drop for Locked<'_, V> {
+    fn drop(&mut self) {
+        let Some(mut guard) = self.guard.take() else {
+            return;
+        };
+        let dropped = mem::take(&mut guard.dropped);
+        drop(guard);
+        if dropped.is_empty() {
+            return;
+        }
+        let on_evict = self
+            .on_evict
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .clone();
+        if let Some(on_evict) = on_evict {
+            for id in dropped {
+                on_evict(id);
+            }
+        }
+    }
+}
+
+impl<V: Clone> MemoryStore<V> {
+    pub fn new(limits: MemoryLimits) -> Self {
+        Self::build(limits, None)
+    }
+
+    /// Create a store whose values can also be looked up by the key `key` returns, with
+    /// [`MemoryStore::get_by_key`]. A value's key must not change while it is stored.
+    pub fn with_key(limits: MemoryLimits, key: KeyFn<V>) -> Self {
+        Self::build(limits, Some(key))
+    }
+
+    fn build(limits: MemoryLimits, key: Option<KeyFn<V>>) -> Self {
+        Self {
+            state: Mutex::new(Entries {
+                entries: LinkedHashMap::new(),
+                keys: HashMap::new(),
+                key,
+                bytes: 0,
+                expired: 0,
+                evicted: 0,
+                dropped: Vec::new(),
+            }),
+            limits,
+            on_evict: Mutex::new(None),
+        }
+    }
+
+    /// Have `callback` called with the ID of every entry that expires or is evicted,
+    /// after the store is unlocked
+    pub fn on_evict(&self, callback: EvictionCallback) {
+        *self
+            .on_evict
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(callback);
+    }
+
+    /// Store a value taking `size` bytes, making room for it by evicting the least
+    /// recently used entries if needed
+    pub fn insert(&self, id: Uuid, value: V, size: usize) -> Result<(), QuotaExceeded> {
+        if let Some(max_bytes) = self.limits.max_bytes {
+            if size > max_bytes {
+                return Err(QuotaExceeded { size, max_bytes });
+            }
+        }
+
+        let mut state = self.lock();
+        state.remove(&id);
+        if let Some(max_bytes) = self.limits.max_bytes {
+            while state.bytes + size > max_bytes {
+                if state.pop_front().is_none() {
+                    break;
+                }
+                state.evicted += 1;
+            }
+        }
+        let expires_at = self.expiry();
+        state.insert(
+            id,
+            Entry {
+                value,
+                size,
+                expires_at,
+            },
+        );
+        Ok(())
+    }
+
+    /// Value stored under `id`, marking it as recently used
+    pub fn get(&self, id: Uuid) -> Option<V> {
+        let mut state = self.lock();
+        if state.remove_if_expired(&id, Instant::now()) {
+            return None;
+        }
+        state.entries.to_back(&id).map(|entry| entry.value.clone())
+    }
+
+    /// Value stored with the secondary key `key`, marking it as recently used
+    pub fn get_by_key(&self, key: &str) -> Option<V> {
+        let mut state = self.lock();
+        let id = *state.keys.get(key)?;
+        if state.remove_if_expired(&id, Instant::now()) {
+            return None;
+        }
+        state.entries.to_back(&id).map(|entry| entry.value.clone())
+    }
+
+    /// Every unexpired value matching `predicate`, leaving the order of use alone
+    pub fn filter(&self, predicate: impl Fn(&V) -> bool) -> Vec<V> {
+        let state = self.lock();
+        let now = Instant::now();
+        state
+            .entries
+            .values()
+            .filter(|entry| !entry.is_expired(now) && predicate(&entry.value))
+            .map(|entry| entry.value.clone())
+            .collect()
+    }
+
+    pub fn remove(&self, id: Uuid) {
+        self.lock().remove(&id);
+    }
+
+    /// Change the value stored under `id` in place, or remove it when `change` returns
+    /// false. A kept value counts as stored anew, restarting its time to live. Returns
+    /// the changed value, or `None` if there is no such entry.
+    pub fn update(&self, id: Uuid, change: impl FnOnce(&mut V) -> bool) -> Option<V> {
+        let mut state = self.lock();
+        if state.remove_if_expired(&id, Instant::now()) {
+            return None;
+        }
+        let expires_at = self.expiry();
+        let entry = state.entries.to_back(&id)?;
+        let keep = change(&mut entry.value);
+        let value = entry.value.clone();
+        if keep {
+            entry.expires_at = expires_at;
+        } else {
+            state.remove(&id);
+        }
+        Some(value)
+    }
+
+    /// Drop every expired entry, returning how many there were
+    pub fn sweep(&self) -> usize {
+        let mut state = self.lock();
+        let now = Instant::now();
+        let expired: Vec<Uuid> = state
+            .entries
+            .iter()
+            .filter(|(_, entry)| entry.is_expired(now))
+            .map(|(id, _)| *id)
+            .collect();
+        for id in &expired {
+            state.remove(id);
+        }
+        state.expired += expired.len() as u64;
+        state.dropped.extend(&expired);
+        expired.len()
+    }
+
+    pub fn stats(&self) -> StorageStats {
+        let state = self.lock();
+        StorageStats {
+            entries: state.entries.len(),
+            bytes: state.bytes,
+            expired: state.expired,
+            evicted: state.evicted,
+        }
+    }
+
+    fn expiry(&self) -> Option<Instant> {
+        self.limits.ttl.map(|ttl| Instant::now() + ttl)
+    }
+
+    fn lock(&self) -> Locked<'_, V> {
+        Locked {
+            guard: Some(
+                self.state
+                    .lock()
+                    .unwrap_or_else(|poisoned| poisoned.into_inner()),
+            ),
+            on_evict: &self.on_evict,
+        }
+    }
+}
+
+impl<V: Clone + Send + 'static> MemoryStore<V> {
+    /// Sweep the store every `interval` until it is dropped
+    pub fn spawn_sweeper(store: &Arc<Self>, interval: Duration) -> JoinHandle<()> {
+        let store = Arc::downgrade(store);
+        tokio::spawn(async move {
+            let mut ticks = tokio::time::interval(interval);
+            ticks.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticks.tick().await;
+                let Some(store) = store.upgrade() else {
+                    break;
+                };
+                store.sweep();
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn store_evicts_least_recently_used_over_quota_and_expires_entries() {
+        let store = MemoryStore::new(MemoryLimits {
+            ttl: None,
+            max_bytes: Some(10),
+        });
+        let ids: Vec<Uuid> = (0..3).map(|_| Uuid::new_v4()).collect();
+        store.insert(ids[0], "a", 4).unwrap();
+        store.insert(ids[1], "b", 4).unwrap();
+        // Using the first entry leaves the second as the one to evict
+        assert_eq!(store.get(ids[0]), Some("a"));
+        store.insert(ids[2], "c", 4).unwrap();
+        assert_eq!(store.get(ids[1]), None);
+        assert_eq!(store.filter(|_| true).len(), 2);
+        assert!(matches!(
+            store.insert(Uuid::new_v4(), "d", 11),
+            Err(QuotaExceeded {
+                size: 11,
+                max_bytes: 10
+            })
+        ));
+        assert_eq!(
+            store.stats(),
+            StorageStats {
+                entries: 2,
+                bytes: 8,
+                expired: 0,
+                evicted: 1
+            }
+        );
+
+        // Values found by their key stop being found once evicted or replaced
+        let store = MemoryStore::with_key(
+            MemoryLimits {
+                ttl: None,
+                max_bytes: Some(8),
+            },
+            |value: &String| value.get(..1),
+        );
+        store.insert(ids[0], "a1".to_string(), 4).unwrap();
+        store.insert(ids[1], "b1".to_string(), 4).unwrap();
+        assert_eq!(store.get_by_key("a"), Some("a1".to_string()));
+        store.insert(ids[2], "c1".to_string(), 4).unwrap();
+        assert_eq!(store.get_by_key("b"), None);
+        store.insert(ids[2], "d1".to_string(), 4).unwrap();
+        assert_eq!(store.get_by_key("c"), None);
+        assert_eq!(store.get_by_key("d"), Some("d1".to_string()));
+        store.remove(ids[2]);
+        assert_eq!(store.get_by_key("d"), None);
+
+        // A zero time to live expires entries right away, reporting each of them once
+        let store = Arc::new(MemoryStore::new(MemoryLimits {
+            ttl: Some(Duration::ZERO),
+            max_bytes: None,
+        }));
+        let dropped = Arc::new(Mutex::new(Vec::new()));
+        let callback_store = Arc::downgrade(&store);
+        let callback_dropped = Arc::clone(&dropped);
+        store.on_evict(Arc::new(move |id| {
+            // Called once the store is unlocked again
+            let store = callback_store.upgrade().unwrap();
+            assert_eq!(
+                store.stats().entries + callback_dropped.lock().unwrap().len(),
+                1
+            );
+            callback_dropped.lock().unwrap().push(id);
+        }));
+        store.insert(ids[0], "a", 4).unwrap();
+        store.insert(ids[1], "b", 4).unwrap();
+        assert_eq!(store.get(ids[0]), None);
+        assert_eq!(store.filter(|_| true), Vec::<&str>::new());
+        assert_eq!(store.sweep(), 1);
+        assert_eq!(*dropped.lock().unwrap(), vec![ids[0], ids[1]]);
+        assert_eq!(
+            store.stats(),
+            StorageStats {
+                entries: 0,
+                bytes: 0,
+                expired: 2,
+                evicted: 0
+            }
+        );
+    }
+}
@@ -1,5 +1,19 @@
+mod file_store;
+pub mod file_system_ascii_art_repository;
+pub mod file_system_image_repository;
 pub mod in_memory_ascii_art_repository;
 pub mod in_memory_image_repository;
+mod memory_store;
+pub mod sqlite_ascii_art_repository;
+mod sqlite_database;
+pub mod sqlite_image_repository;
 
+pub use file_store::FileStoreError;
+pub use file_system_ascii_art_repository::FileSystemAsciiArtRepository;
+pub use file_system_image_repository::FileSystemImageRepository;
 pub use in_memory_ascii_art_repository::InMemoryAsciiArtRepository;
 pub use in_memory_image_repository::InMemoryImageRepository;
+pub use memory_store::{MemoryLimits, QuotaExceeded};
+pub use sqlite_ascii_art_repository::SqliteAsciiArtRepository;
+pub use sqlite_database::{SqliteDatabase, SqliteRepositoryError};
+pub use sqlite_image_repository::SqliteImageRepository;
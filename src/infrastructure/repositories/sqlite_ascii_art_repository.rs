@@ -0,0 +1,273 @@
+use super::sqlite_database::{parse_uuid, SqliteDatabase, SqliteRepositoryError};
+use crate::domain::{
+    entities::{ascii_art::DetailLevel, AsciiArt},
+    repositories::{AsciiArtQuery, AsciiArtRepository},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, SecondsFormat, Utc};
+use rusqlite::{params, params_from_iter, OptionalExtension, Row};
+use uuid::Uuid;
+
+const SELECT_COLUMNS: &str =
+    "SELECT id, image_id, content, width, height, detail_level, created_at, frames FROM ascii_art";
+
+/// SQLite implementation of AsciiArtRepository
+#[derive(Clone)]
+pub struct SqliteAsciiArtRepository {
+    database: SqliteDatabase,
+}
+
+impl SqliteAsciiArtRepository {
+    /// Create a repository on top of an opened database
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self { database }
+    }
+}
+
+/// Raw column values of an `ascii_art` row
+type AsciiArtRow = (String, String, String, u32, u32, String, String, String);
+
+fn read_row(row: &Row<'_>) -> rusqlite::Result<AsciiArtRow> {
+    Ok((
+        row.get(0)?,
+        row.get(1)?,
+        row.get(2)?,
+        row.get(3)?,
+        row.get(4)?,
+        row.get(5)?,
+        row.get(6)?,
+        row.get(7)?,
+    ))
+}
+
+fn into_ascii_art(row: AsciiArtRow) -> Result<AsciiArt, SqliteRepositoryError> {
+    let (id, image_id, content, width, height, detail_level, created_at, frames) = row;
+    Ok(AsciiArt {
+        id: parse_uuid(&id)?,
+        image_id: parse_uuid(&image_id)?,
+        content,
+        width,
+        height,
+        detail_level: DetailLevel::from_name(&detail_level).ok_or_else(|| {
+            SqliteRepositoryError::InvalidData(format!("invalid detail level '{}'", detail_level))
+        })?,
+        created_at: DateTime::parse_from_rfc3339(&created_at)
+            .map_err(|_| {
+                SqliteRepositoryError::InvalidData(format!("invalid timestamp '{}'", created_at))
+            })?
+            .with_timezone(&Utc),
+        frames: serde_json::from_str(&frames)?,
+    })
+}
+
+/// Timestamps are stored with a fixed width so that text order matches time order
+fn timestamp(instant: DateTime<Utc>) -> String {
+    instant.to_rfc3339_opts(SecondsFormat::Nanos, true)
+}
+
+#[async_trait]
+impl AsciiArtRepository for SqliteAsciiArtRepository {
+    type Error = SqliteRepositoryError;
+
+    async fn save(&self, ascii_art: &AsciiArt) -> Result<(), Self::Error> {
+        let ascii_art = ascii_art.clone();
+        let frames = serde_json::to_string(&ascii_art.frames)?;
+        self.database
+            .call(move |connection| {
+                connection.execute(
+                    "INSERT OR REPLACE INTO ascii_art
+                        (id, image_id, content, width, height, detail_level, created_at, frames)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                    params![
+                        ascii_art.id.to_string(),
+                        ascii_art.image_id.to_string(),
+                        ascii_art.content,
+                        ascii_art.width,
+                        ascii_art.height,
+                        ascii_art.detail_level.to_string(),
+                        timestamp(ascii_art.created_at),
+                        frames,
+                    ],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<AsciiArt>, Self::Error> {
+        self.database
+            .call(move |connection| {
+                connection
+                    .query_row(
+                        &format!("{} WHERE id = ?1", SELECT_COLUMNS),
+                        params![id.to_string()],
+                        read_row,
+                    )
+                    .optional()?
+                    .map(into_ascii_art)
+                    .transpose()
+            })
+            .await
+    }
+
+    async fn find_by_image_id(&self, image_id: Uuid) -> Result<Vec<AsciiArt>, Self::Error> {
+        self.list(&AsciiArtQuery {
+            image_id: Some(image_id),
+            ..AsciiArtQuery::default()
+        })
+        .await
+    }
+
+    async fn list(&self, query: &AsciiArtQuery) -> Result<Vec<AsciiArt>, Self::Error> {
+        // Only add the conditions that are set, so SQLite can use the column indices
+        let mut conditions = Vec::new();
+        let mut values = Vec::new();
+        if let Some(image_id) = query.image_id {
+            conditions.push("image_id = ?");
+            values.push(image_id.to_string());
+        }
+        if let Some(detail_level) = query.detail_level {
+            conditions.push("detail_level = ?");
+            values.push(detail_level.to_string());
+        }
+        if let Some(after) = query.created_after {
+            conditions.push("created_at >= ?");
+            values.push(timestamp(after));
+        }
+        if let Some(before) = query.created_before {
+            conditions.push("created_at < ?");
+            values.push(timestamp(before));
+        }
+
+        let mut sql = SELECT_COLUMNS.to_string();
+        if !conditions.is_empty() {
+            sql.push_str(" WHERE ");
+            sql.push_str(&conditions.join(" AND "));
+        }
+        // Ties are broken by ID, as in the other backends, for stable pages
+        sql.push_str(" ORDER BY created_at DESC, id");
+        if query.limit.is_some() || query.offset > 0 {
+            // A negative limit means no limit in SQLite
+            let limit = query.limit.map_or(-1, |limit| limit as i64);
+            sql.push_str(&format!(" LIMIT {} OFFSET {}", limit, query.offset));
+        }
+
+        self.database
+            .call(move |connection| {
+                let mut statement = connection.prepare(&sql)?;
+                let rows = statement
+                    .query_map(params_from_iter(values), read_row)?
+                    .collect::<Result<Vec<_>, _>>()?;
+                rows.into_iter().map(into_ascii_art).collect()
+            })
+            .await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
+        self.database
+            .call(move |connection| {
+                connection.execute(
+                    "DELETE FROM ascii_art WHERE id = ?1",
+                    params![id.to_string()],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[tokio::test]
+    async fn list_filters_by_image_detail_and_date() {
+        let repository = SqliteAsciiArtRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let (image_a, image_b) = (Uuid::new_v4(), Uuid::new_v4());
+        let now = Utc::now();
+
+        let mut old = AsciiArt::new(image_a, "old".into(), 3, 1, DetailLevel::Low);
+        old.created_at = now - Duration::days(2);
+        let recent = AsciiArt::new(image_a, "recent".into(), 6, 1, DetailLevel::High);
+        let other = AsciiArt::new(image_b, "other".into(), 5, 1, DetailLevel::High);
+        for ascii_art in [&old, &recent, &other] {
+            repository.save(ascii_art).await.unwrap();
+        }
+
+        let contents = |results: Vec<AsciiArt>| -> Vec<String> {
+            results.into_iter().map(|a| a.content).collect()
+        };
+        let by_image = AsciiArtQuery {
+            image_id: Some(image_a),
+            ..Default::default()
+        };
+        assert_eq!(
+            contents(repository.list(&by_image).await.unwrap()),
+            ["recent", "old"]
+        );
+
+        let high_detail = AsciiArtQuery {
+            detail_level: Some(DetailLevel::High),
+            ..by_image.clone()
+        };
+        assert_eq!(
+            contents(repository.list(&high_detail).await.unwrap()),
+            ["recent"]
+        );
+
+        let last_day = AsciiArtQuery {
+            created_after: Some(now - Duration::days(1)),
+            ..Default::default()
+        };
+        assert_eq!(repository.list(&last_day).await.unwrap().len(), 2);
+
+        let second_page = AsciiArtQuery {
+            offset: 1,
+            limit: Some(1),
+            ..Default::default()
+        };
+        assert_eq!(repository.list(&second_page).await.unwrap().len(), 1);
+
+        let found = repository.find_by_id(old.id).await.unwrap().unwrap();
+        assert_eq!(found.created_at, old.created_at);
+    }
+
+    #[tokio::test]
+    async fn backends_agree_on_the_order_of_simultaneous_conversions() {
+        use crate::infrastructure::repositories::{
+            FileSystemAsciiArtRepository, InMemoryAsciiArtRepository,
+        };
+
+        let image_id = Uuid::new_v4();
+        let created_at = Utc::now();
+        let mut conversions: Vec<AsciiArt> = (0..5)
+            .map(|i| {
+                let mut ascii_art =
+                    AsciiArt::new(image_id, format!("art {i}"), 5, 1, DetailLevel::Low);
+                ascii_art.created_at = created_at;
+                ascii_art
+            })
+            .collect();
+
+        let root = std::env::temp_dir().join(format!("ascii-art-order-{}", Uuid::new_v4()));
+        let sqlite = SqliteAsciiArtRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let memory = InMemoryAsciiArtRepository::new();
+        let disk = FileSystemAsciiArtRepository::open(&root).await.unwrap();
+        for ascii_art in &conversions {
+            sqlite.save(ascii_art).await.unwrap();
+            memory.save(ascii_art).await.unwrap();
+            disk.save(ascii_art).await.unwrap();
+        }
+
+        conversions.sort_by_key(|ascii_art| ascii_art.id);
+        let expected: Vec<Uuid> = conversions.iter().map(|a| a.id).collect();
+        let ids = |results: Vec<AsciiArt>| -> Vec<Uuid> { results.iter().map(|a| a.id).collect() };
+        let query = AsciiArtQuery::default();
+        assert_eq!(ids(sqlite.list(&query).await.unwrap()), expected);
+        assert_eq!(ids(memory.list(&query).await.unwrap()), expected);
+        assert_eq!(ids(disk.list(&query).await.unwrap()), expected);
+
+        std::fs::remove_dir_all(root).unwrap();
+    }
+}
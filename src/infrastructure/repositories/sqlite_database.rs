@@ -0,0 +1,124 @@
+use rusqlite::Connection;
+use std::{
+    path::Path,
+    sync::{Arc, Mutex, PoisonError},
+};
+use thiserror::Error;
+
+/// Schema migrations in order of application. The number of applied migrations is kept
+/// in SQLite's `user_version` pragma, so released migrations must never be edited;
+/// append a new one instead.
+const MIGRATIONS: &[&str] = &[
+    "
+    CREATE TABLE images (
+        id TEXT PRIMARY KEY NOT NULL,
+        original_filename TEXT NOT NULL,
+        content_type TEXT NOT NULL,
+        data BLOB NOT NULL,
+        width INTEGER NOT NULL,
+        height INTEGER NOT NULL
+    );
+
+    CREATE TABLE ascii_art (
+        id TEXT PRIMARY KEY NOT NULL,
+        image_id TEXT NOT NULL,
+        content TEXT NOT NULL,
+        width INTEGER NOT NULL,
+        height INTEGER NOT NULL,
+        detail_level TEXT NOT NULL,
+        created_at TEXT NOT NULL,
+        frames TEXT NOT NULL DEFAULT '[]'
+    );
+
+    CREATE INDEX idx_ascii_art_image_id ON ascii_art (image_id);
+    CREATE INDEX idx_ascii_art_created_at ON ascii_art (created_at);
+",
+    "
+    -- Images stored before deduplication are never matched by hash
+    ALTER TABLE images ADD COLUMN content_hash TEXT NOT NULL DEFAULT '';
+    ALTER TABLE images ADD COLUMN ref_count INTEGER NOT NULL DEFAULT 1;
+
+    CREATE INDEX idx_images_content_hash ON images (content_hash);
+",
+    "
+    -- Formats by file extension; images stored before detection have neither
+    ALTER TABLE images ADD COLUMN declared_format TEXT;
+    ALTER TABLE images ADD COLUMN detected_format TEXT;
+",
+];
+
+#[derive(Error, Debug)]
+pub enum SqliteRepositoryError {
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+    #[error("Invalid stored data: {0}")]
+    InvalidData(String),
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+    #[error("Database task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+/// SQLite connection shared by the SQLite repositories
+#[derive(Clone)]
+pub struct SqliteDatabase {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDatabase {
+    /// Open or create the database file at `path`, applying pending migrations
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteRepositoryError> {
+        let connection = Connection::open(path)?;
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        Self::initialize(connection)
+    }
+
+    /// Open a private in-memory database, mostly useful for tests
+    pub fn open_in_memory() -> Result<Self, SqliteRepositoryError> {
+        Self::initialize(Connection::open_in_memory()?)
+    }
+
+    fn initialize(mut connection: Connection) -> Result<Self, SqliteRepositoryError> {
+        let applied: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if applied > MIGRATIONS.len() {
+            return Err(SqliteRepositoryError::InvalidData(format!(
+                "schema version {} is newer than the supported version {}",
+                applied,
+                MIGRATIONS.len()
+            )));
+        }
+
+        for (version, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+            let transaction = connection.transaction()?;
+            transaction.execute_batch(migration)?;
+            transaction.pragma_update(None, "user_version", version + 1)?;
+            transaction.commit()?;
+        }
+
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Run a closure against the connection on the blocking thread pool
+    pub(crate) async fn call<T, F>(&self, f: F) -> Result<T, SqliteRepositoryError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&Connection) -> Result<T, SqliteRepositoryError> + Send + 'static,
+    {
+        let connection = Arc::clone(&self.connection);
+        tokio::task::spawn_blocking(move || {
+            // A panic in another closure cannot leave the connection half-updated, as
+            // every statement is atomic on its own
+            let connection = connection.lock().unwrap_or_else(PoisonError::into_inner);
+            f(&connection)
+        })
+        .await?
+    }
+}
+
+/// Parse an ID stored as text
+pub(crate) fn parse_uuid(value: &str) -> Result<uuid::Uuid, SqliteRepositoryError> {
+    uuid::Uuid::parse_str(value)
+        .map_err(|_| SqliteRepositoryError::InvalidData(format!("invalid ID '{}'", value)))
+}
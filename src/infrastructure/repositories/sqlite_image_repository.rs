@@ -0,0 +1,199 @@
+use super::sqlite_database::{parse_uuid, SqliteDatabase, SqliteRepositoryError};
+use crate::domain::{
+    entities::ImageData, repositories::ImageRepository, value_objects::ImageFormat,
+};
+use async_trait::async_trait;
+use rusqlite::{params, OptionalExtension, Row};
+use uuid::Uuid;
+
+/// Columns read back into an `ImageData`, in the order `image_from_row` expects
+const IMAGE_COLUMNS: &str = "id, original_filename, content_type, data, width, height, \
+     content_hash, ref_count, declared_format, detected_format";
+
+/// SQLite implementation of ImageRepository
+#[derive(Clone)]
+pub struct SqliteImageRepository {
+    database: SqliteDatabase,
+}
+
+impl SqliteImageRepository {
+    /// Create a repository on top of an opened database
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self { database }
+    }
+
+    /// Find the first image matching a `WHERE` clause with a single parameter
+    async fn find_one(
+        &self,
+        condition: &'static str,
+        value: String,
+    ) -> Result<Option<ImageData>, SqliteRepositoryError> {
+        self.database
+            .call(move |connection| {
+                let sql = format!(
+                    "SELECT {} FROM images WHERE {} LIMIT 1",
+                    IMAGE_COLUMNS, condition
+                );
+                let row = connection
+                    .query_row(&sql, params![value], |row| {
+                        Ok((row.get::<_, String>(0)?, image_from_row(row)?))
+                    })
+                    .optional()?;
+
+                row.map(|(id, image)| {
+                    Ok(ImageData {
+                        id: parse_uuid(&id)?,
+                        ..image
+                    })
+                })
+                .transpose()
+            })
+            .await
+    }
+}
+
+/// Read an image from a row of `IMAGE_COLUMNS`; its ID is left nil to be parsed by the
+/// caller, since parse errors aren't rusqlite errors
+fn image_from_row(row: &Row<'_>) -> rusqlite::Result<ImageData> {
+    Ok(ImageData {
+        id: Uuid::nil(),
+        original_filename: row.get(1)?,
+        content_type: row.get(2)?,
+        data: row.get(3)?,
+        width: row.get(4)?,
+        height: row.get(5)?,
+        content_hash: row.get(6)?,
+        ref_count: row.get(7)?,
+        declared_format: format_from_column(row.get(8)?),
+        detected_format: format_from_column(row.get(9)?),
+    })
+}
+
+/// Formats are stored by their file extension
+fn format_from_column(extension: Option<String>) -> Option<ImageFormat> {
+    extension.as_deref().and_then(ImageFormat::from_extension)
+}
+
+#[async_trait]
+impl ImageRepository for SqliteImageRepository {
+    type Error = SqliteRepositoryError;
+
+    async fn save(&self, image: &ImageData) -> Result<(), Self::Error> {
+        let image = image.clone();
+        self.database
+            .call(move |connection| {
+                connection.execute(
+                    "INSERT OR REPLACE INTO images
+                        (id, original_filename, content_type, data, width, height,
+                         content_hash, ref_count, declared_format, detected_format)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    params![
+                        image.id.to_string(),
+                        image.original_filename,
+                        image.content_type,
+                        image.data,
+                        image.width,
+                        image.height,
+                        image.content_hash,
+                        image.ref_count,
+                        image.declared_format.map(|format| format.extension()),
+                        image.detected_format.map(|format| format.extension()),
+                    ],
+                )?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn find_by_id(&self, id: Uuid) -> Result<Option<ImageData>, Self::Error> {
+        self.find_one("id = ?1", id.to_string()).await
+    }
+
+    async fn find_by_content_hash(&self, hash: &str) -> Result<Option<ImageData>, Self::Error> {
+        self.find_one("content_hash = ?1", hash.to_string()).await
+    }
+
+    async fn delete(&self, id: Uuid) -> Result<(), Self::Error> {
+        self.database
+            .call(move |connection| {
+                connection.execute("DELETE FROM images WHERE id = ?1", params![id.to_string()])?;
+                Ok(())
+            })
+            .await
+    }
+
+    async fn retain(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        self.database
+            .call(move |connection| {
+                let count = connection
+                    .query_row(
+                        "UPDATE images SET ref_count = ref_count + 1 WHERE id = ?1
+                         RETURNING ref_count",
+                        params![id.to_string()],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                Ok(count)
+            })
+            .await
+    }
+
+    async fn release(&self, id: Uuid) -> Result<Option<u32>, Self::Error> {
+        self.database
+            .call(move |connection| {
+                // Both statements run under the connection lock, so no other
+                // repository call can slip in between
+                let count: Option<u32> = connection
+                    .query_row(
+                        "UPDATE images SET ref_count = MAX(ref_count - 1, 0) WHERE id = ?1
+                         RETURNING ref_count",
+                        params![id.to_string()],
+                        |row| row.get(0),
+                    )
+                    .optional()?;
+                if count == Some(0) {
+                    connection
+                        .execute("DELETE FROM images WHERE id = ?1", params![id.to_string()])?;
+                }
+                Ok(count)
+            })
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn shared_images_are_deleted_with_their_last_reference() {
+        let repository = SqliteImageRepository::new(SqliteDatabase::open_in_memory().unwrap());
+        let image = ImageData::new("a.jpg".into(), "image/png".into(), vec![1, 2, 3], 1, 1)
+            .with_formats(Some(ImageFormat::Jpeg), ImageFormat::Png);
+        repository.save(&image).await.unwrap();
+
+        let found = repository
+            .find_by_content_hash(&ImageData::hash_content(&[1, 2, 3]))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!((found.id, found.ref_count), (image.id, 1));
+        assert_eq!(
+            (found.declared_format, found.detected_format),
+            (Some(ImageFormat::Jpeg), Some(ImageFormat::Png))
+        );
+        assert!(repository
+            .find_by_content_hash(&ImageData::hash_content(&[1, 2]))
+            .await
+            .unwrap()
+            .is_none());
+
+        assert_eq!(repository.retain(image.id).await.unwrap(), Some(2));
+        assert_eq!(repository.release(image.id).await.unwrap(), Some(1));
+        assert!(repository.find_by_id(image.id).await.unwrap().is_some());
+        assert_eq!(repository.release(image.id).await.unwrap(), Some(0));
+        assert!(repository.find_by_id(image.id).await.unwrap().is_none());
+        assert_eq!(repository.release(image.id).await.unwrap(), None);
+        assert_eq!(repository.retain(image.id).await.unwrap(), None);
+    }
+}
@@ -0,0 +1,166 @@
+use crate::domain::value_objects::ImageFormat;
+use std::io::{Cursor, Read, Write};
+use thiserror::Error;
+use zip::{result::ZipError, write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+#[derive(Error, Debug)]
+pub enum ArchiveError {
+    #[error("Invalid ZIP archive: {0}")]
+    Invalid(#[from] ZipError),
+    #[error("Too many files in the archive (max: {max})")]
+    TooManyEntries { max: usize },
+    #[error("'{name}' is too large (max size: {max_size} bytes)")]
+    EntryTooLarge { name: String, max_size: usize },
+    #[error("Archive contents are too large (max size: {max_size} bytes)")]
+    TooLarge { max_size: usize },
+    #[error("Error reading '{0}' from the archive")]
+    Read(String),
+}
+
+/// File taken out of an uploaded archive
+#[derive(Debug)]
+pub struct ArchiveEntry {
+    pub filename: String,
+    /// Guessed from the extension, since archives don't record it
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+/// Caps on what an uploaded archive may expand to, so that a small upload cannot
+/// unpack into something huge
+#[derive(Debug, Clone, Copy)]
+pub struct ArchiveLimits {
+    pub max_entries: usize,
+    pub max_entry_size: usize,
+    pub max_total_size: usize,
+}
+
+/// Whether an uploaded file is a ZIP archive rather than an image
+pub fn is_zip(filename: &str, content_type: &str) -> bool {
+    matches!(
+        content_type,
+        "application/zip" | "application/x-zip-compressed"
+    ) || filename.to_lowercase().ends_with(".zip")
+}
+
+/// Read the files of a ZIP archive. Directories and hidden files, like the `__MACOSX`
+/// folder, are skipped; anything else is returned for the caller to accept or reject.
+pub fn extract_files(
+    data: &[u8],
+    limits: ArchiveLimits,
+) -> Result<Vec<ArchiveEntry>, ArchiveError> {
+    let mut archive = ZipArchive::new(Cursor::new(data))?;
+    let mut entries = Vec::new();
+    let mut total_size = 0;
+
+    for index in 0..archive.len() {
+        let file = archive.by_index(index)?;
+        let name = file.name().to_string();
+        if file.is_dir()
+            || name
+                .split('/')
+                .any(|part| part.starts_with('.') || part == "__MACOSX")
+        {
+            continue;
+        }
+
+        if entries.len() == limits.max_entries {
+            return Err(ArchiveError::TooManyEntries {
+                max: limits.max_entries,
+            });
+        }
+
+        // The declared size can't be trusted, so stop reading just past the limit
+        let mut data = Vec::new();
+        file.take(limits.max_entry_size as u64 + 1)
+            .read_to_end(&mut data)
+            .map_err(|_| ArchiveError::Read(name.clone()))?;
+        if data.len() > limits.max_entry_size {
+            return Err(ArchiveError::EntryTooLarge {
+                name,
+                max_size: limits.max_entry_size,
+            });
+        }
+        total_size += data.len();
+        if total_size > limits.max_total_size {
+            return Err(ArchiveError::TooLarge {
+                max_size: limits.max_total_size,
+            });
+        }
+
+        let content_type = name
+            .rsplit_once('.')
+            .and_then(|(_, extension)| ImageFormat::from_extension(extension))
+            .map_or("application/octet-stream", |format| format.mime_type());
+        entries.push(ArchiveEntry {
+            filename: name,
+            content_type: content_type.to_string(),
+            data,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Pack named files into a ZIP archive
+pub fn write_archive<'a>(
+    files: impl IntoIterator<Item = (&'a str, &'a [u8])>,
+) -> Result<Vec<u8>, ZipError> {
+    let mut writer = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default();
+    for (name, data) in files {
+        writer.start_file(name, options)?;
+        writer.write_all(data)?;
+    }
+    Ok(writer.finish()?.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracted_archives_skip_hidden_files_and_respect_limits() {
+        let archive = write_archive([
+            ("sprites/a.png", &b"png"[..]),
+            ("__MACOSX/sprites/._a.png", &b"junk"[..]),
+            ("notes.txt", &b"hello"[..]),
+        ])
+        .unwrap();
+
+        let limits = ArchiveLimits {
+            max_entries: 2,
+            max_entry_size: 5,
+            max_total_size: 8,
+        };
+        let entries = extract_files(&archive, limits).unwrap();
+        let names: Vec<_> = entries
+            .iter()
+            .map(|entry| (entry.filename.as_str(), entry.content_type.as_str()))
+            .collect();
+        assert_eq!(
+            names,
+            [
+                ("sprites/a.png", "image/png"),
+                ("notes.txt", "application/octet-stream")
+            ]
+        );
+
+        let small = ArchiveLimits {
+            max_entry_size: 4,
+            ..limits
+        };
+        assert!(matches!(
+            extract_files(&archive, small),
+            Err(ArchiveError::EntryTooLarge { .. })
+        ));
+        let few = ArchiveLimits {
+            max_entries: 1,
+            ..limits
+        };
+        assert!(matches!(
+            extract_files(&archive, few),
+            Err(ArchiveError::TooManyEntries { max: 1 })
+        ));
+    }
+}
@@ -18,6 +18,12 @@ pub enum WebError {
     PayloadTooLarge,
     #[error("Unsupported media type")]
     UnsupportedMediaType,
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
+    #[error("Service unavailable: {0}")]
+    ServiceUnavailable(String),
 }
 
 impl IntoResponse for WebError {
@@ -34,6 +40,9 @@ impl IntoResponse for WebError {
                 StatusCode::UNSUPPORTED_MEDIA_TYPE,
                 "Unsupported media type".to_string(),
             ),
+            WebError::NotAcceptable(msg) => (StatusCode::NOT_ACCEPTABLE, msg.clone()),
+            WebError::UnprocessableEntity(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
+            WebError::ServiceUnavailable(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
         };
 
         let body = Json(json!({
@@ -1,14 +1,31 @@
-use crate::infrastructure::web::error::WebError;
+use crate::{
+    application::use_cases::batch_convert::MAX_BATCH_FILES,
+    infrastructure::web::{
+        archive::{self, ArchiveLimits},
+        error::WebError,
+    },
+};
 use axum::{
     async_trait,
     extract::{FromRequest, Multipart, Request},
 };
 
+/// Largest image accepted in one upload
+const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Largest request body of a batch upload
+pub const MAX_BATCH_BODY_SIZE: usize = 100 * 1024 * 1024;
+
+/// Most that the archives of a batch upload may expand to
+const MAX_BATCH_EXPANDED_SIZE: usize = 256 * 1024 * 1024;
+
 /// Multipart form data for image upload
 pub struct ImageUpload {
     pub filename: String,
     pub content_type: String,
     pub data: Vec<u8>,
+    /// Other text fields of the form, in order
+    pub fields: Vec<(String, String)>,
 }
 
 #[async_trait]
@@ -26,6 +43,7 @@ where
         let mut filename = None;
         let mut content_type = None;
         let mut data = None;
+        let mut fields = Vec::new();
 
         while let Some(field) = multipart
             .next_field()
@@ -42,8 +60,14 @@ where
                         WebError::BadRequest("Error reading image data".to_string())
                     })?);
                 }
+                _ if field.file_name().is_none() => {
+                    let value = field.text().await.map_err(|_| {
+                        WebError::BadRequest(format!("Error reading field '{}'", field_name))
+                    })?;
+                    fields.push((field_name, value));
+                }
                 _ => {
-                    // Skip unknown fields
+                    // Skip other files
                 }
             }
         }
@@ -51,21 +75,14 @@ where
         let filename = filename
             .ok_or_else(|| WebError::BadRequest("Missing filename in image field".to_string()))?;
 
-        let content_type = content_type.ok_or_else(|| {
-            WebError::BadRequest("Missing content type in image field".to_string())
-        })?;
+        // Only recorded as what the client claims; the format is detected from the data
+        let content_type = content_type.unwrap_or_else(|| "application/octet-stream".to_string());
 
         let data = data
             .ok_or_else(|| WebError::BadRequest("Missing image data".to_string()))?
             .to_vec();
 
-        // Validate content type
-        if !content_type.starts_with("image/") {
-            return Err(WebError::UnsupportedMediaType);
-        }
-
-        // Validate file size (10MB limit)
-        const MAX_FILE_SIZE: usize = 10 * 1024 * 1024;
+        // Validate file size
         if data.len() > MAX_FILE_SIZE {
             return Err(WebError::PayloadTooLarge);
         }
@@ -74,6 +91,103 @@ where
             filename,
             content_type,
             data,
+            fields,
         })
     }
 }
+
+/// Multipart form data for a batch of images. Every file field counts, whatever its
+/// name, and ZIP archives are replaced by the files inside them.
+pub struct BatchUpload {
+    pub files: Vec<UploadedFile>,
+    /// Text fields of the form, in order
+    pub fields: Vec<(String, String)>,
+}
+
+/// File of a batch upload, not validated yet
+pub struct UploadedFile {
+    pub filename: String,
+    pub content_type: String,
+    pub data: Vec<u8>,
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for BatchUpload
+where
+    S: Send + Sync,
+{
+    type Rejection = WebError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let mut multipart = Multipart::from_request(req, state)
+            .await
+            .map_err(|_| WebError::BadRequest("Invalid multipart data".to_string()))?;
+
+        let mut files = Vec::new();
+        let mut fields = Vec::new();
+        let mut expanded_size = 0;
+
+        while let Some(field) = multipart
+            .next_field()
+            .await
+            .map_err(|_| WebError::BadRequest("Error reading multipart field".to_string()))?
+        {
+            let field_name = field.name().unwrap_or("").to_string();
+
+            let Some(filename) = field.file_name().map(|s| s.to_string()) else {
+                let value = field.text().await.map_err(|_| {
+                    WebError::BadRequest(format!("Error reading field '{}'", field_name))
+                })?;
+                fields.push((field_name, value));
+                continue;
+            };
+
+            let content_type = field
+                .content_type()
+                .unwrap_or("application/octet-stream")
+                .to_string();
+            let data = field
+                .bytes()
+                .await
+                .map_err(|_| WebError::BadRequest(format!("Error reading '{}'", filename)))?
+                .to_vec();
+
+            if !archive::is_zip(&filename, &content_type) {
+                files.push(UploadedFile {
+                    filename,
+                    content_type,
+                    data,
+                });
+                continue;
+            }
+
+            let limits = ArchiveLimits {
+                max_entries: MAX_BATCH_FILES.saturating_sub(files.len()),
+                max_entry_size: MAX_FILE_SIZE,
+                max_total_size: MAX_BATCH_EXPANDED_SIZE - expanded_size,
+            };
+            // Inflating an archive takes a while, so keep it off the async executor
+            let entries =
+                tokio::task::spawn_blocking(move || archive::extract_files(&data, limits))
+                    .await
+                    .map_err(|e| WebError::InternalServerError(e.to_string()))?
+                    .map_err(|e| {
+                        WebError::BadRequest(format!("Error reading '{}': {}", filename, e))
+                    })?;
+            for entry in entries {
+                expanded_size += entry.data.len();
+                files.push(UploadedFile {
+                    filename: entry.filename,
+                    content_type: entry.content_type,
+                    data: entry.data,
+                });
+            }
+        }
+
+        if files.is_empty() {
+            return Err(WebError::BadRequest("No images in the batch".to_string()));
+        }
+
+        Ok(BatchUpload { files, fields })
+    }
+}
@@ -1,3 +1,4 @@
+pub mod archive;
 pub mod error;
 pub mod extractors;
 pub mod routes;
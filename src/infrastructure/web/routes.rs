@@ -1,11 +1,19 @@
 use crate::{
-    infrastructure::repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
+    domain::repositories::{AsciiArtRepository, ImageRepository},
+    infrastructure::{
+        repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
+        web::extractors::MAX_BATCH_BODY_SIZE,
+    },
     presentation::handlers::{
         ascii_handlers::{AppState, *},
+        batch_handlers::*,
         health_check,
+        job_handlers::*,
+        preview_handlers::*,
     },
 };
 use axum::{
+    extract::DefaultBodyLimit,
     http::StatusCode,
     response::Html,
     routing::{get, post},
@@ -14,14 +22,14 @@ use axum::{
 use tower_http::cors::CorsLayer;
 use tower_http::services::ServeDir;
 
-/// Type alias for concrete application state
+/// Type alias for application state backed by the in-memory repositories
 pub type ConcreteAppState = AppState<InMemoryImageRepository, InMemoryAsciiArtRepository>;
 
 async fn debug_frontend() -> Result<Html<String>, StatusCode> {
     // Check if frontend files exist
     let dist_exists = std::path::Path::new("frontend/dist").exists();
     let index_exists = std::path::Path::new("frontend/dist/index.html").exists();
-    
+
     let debug_info = format!(
         r#"<html><body>
         <h1>Frontend Debug Info</h1>
@@ -51,30 +59,306 @@ async fn debug_frontend() -> Result<Html<String>, StatusCode> {
             "<li>Directory does not exist</li>".to_string()
         }
     );
-    
+
     Ok(Html(debug_info))
 }
 
 /// Create application routes
-pub fn create_routes() -> Router<ConcreteAppState> {
+pub fn create_routes<IR, AR>() -> Router<AppState<IR, AR>>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
     Router::new()
         // Health check
         .route("/health", get(health_check))
         // Debug route
         .route("/debug", get(debug_frontend))
         // API routes
-        .route("/api/upload", post(upload_image))
-        .route("/api/convert/:image_id", post(convert_to_ascii))
+        .route("/api/upload", post(upload_image::<IR, AR>))
+        .route("/api/convert", post(upload_and_convert::<IR, AR>))
+        .route(
+            "/api/batch",
+            post(batch_convert::<IR, AR>).layer(DefaultBodyLimit::max(MAX_BATCH_BODY_SIZE)),
+        )
+        .route("/api/convert/:image_id", post(convert_to_ascii::<IR, AR>))
+        .route("/api/preview/:image_id", get(preview_ascii_art::<IR, AR>))
+        .route("/api/jobs", post(submit_conversion_job::<IR, AR>))
+        .route("/api/jobs/:id", get(get_job::<IR, AR>))
+        .route("/api/jobs/:id/cancel", post(cancel_job::<IR, AR>))
+        .route("/api/storage", get(storage_stats::<IR, AR>))
+        .route("/api/ascii", get(list_ascii_art::<IR, AR>))
+        .route(
+            "/api/ascii/:id",
+            get(get_ascii_art::<IR, AR>).delete(delete_ascii_art::<IR, AR>),
+        )
+        .route(
+            "/api/ascii/:id/render.png",
+            get(render_ascii_art_png::<IR, AR>),
+        )
+        .route(
+            "/api/ascii/:id/render.svg",
+            get(render_ascii_art_svg::<IR, AR>),
+        )
+        .route(
+            "/api/ascii/:id/render.html",
+            get(render_ascii_art_html::<IR, AR>),
+        )
+        .route(
+            "/api/images/:image_id",
+            get(get_image::<IR, AR>).delete(delete_image::<IR, AR>),
+        )
+        .route(
+            "/api/images/:image_id/conversions",
+            get(list_image_conversions::<IR, AR>),
+        )
         // CORS layer for web frontend
         .layer(CorsLayer::permissive())
         // Static frontend (built with Trunk into frontend/dist) - MUST BE LAST
-        .fallback_service(
-            ServeDir::new("frontend/dist")
-                .not_found_service(ServeDir::new("static"))
-        )
+        .fallback_service(ServeDir::new("frontend/dist").not_found_service(ServeDir::new("static")))
 }
 
 /// Create the full application with state
-pub fn create_app(state: ConcreteAppState) -> Router {
+pub fn create_app<IR, AR>(state: AppState<IR, AR>) -> Router
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
     create_routes().with_state(state)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        application::{
+            services::{ArtRenderer, AsciiConversionService, ConversionCache, JobQueue},
+            use_cases::*,
+        },
+        domain::{
+            entities::{ascii_art::DetailLevel, AsciiArt, ImageData},
+            value_objects::ImageLimits,
+        },
+    };
+    use axum::{body::Body, http::Request};
+    use serde_json::Value;
+    use std::sync::Arc;
+    use tower::ServiceExt;
+
+    /// Application state wired as the server does, over empty in-memory repositories
+    fn test_state() -> (
+        ConcreteAppState,
+        Arc<InMemoryImageRepository>,
+        Arc<InMemoryAsciiArtRepository>,
+    ) {
+        let image_repo = Arc::new(InMemoryImageRepository::new());
+        let ascii_art_repo = Arc::new(InMemoryAsciiArtRepository::new());
+        let limits = ImageLimits::default();
+        let conversion_service = Arc::new(AsciiConversionService::with_limits(limits));
+        let job_queue = Arc::new(JobQueue::new(1, JobQueue::DEFAULT_MAX_PENDING));
+        let conversion_cache = Arc::new(ConversionCache::new(ConversionCache::DEFAULT_CAPACITY));
+
+        let upload_use_case = Arc::new(UploadImageUseCase::new(
+            Arc::clone(&image_repo),
+            1024 * 1024,
+            limits,
+        ));
+        let convert_use_case = Arc::new(ConvertImageToAsciiUseCase::new(
+            Arc::clone(&image_repo),
+            Arc::clone(&ascii_art_repo),
+            Arc::clone(&conversion_service),
+            Arc::clone(&conversion_cache),
+        ));
+        let upload_and_convert_use_case = Arc::new(UploadAndConvertUseCase::new(
+            Arc::clone(&upload_use_case),
+            Arc::clone(&convert_use_case),
+            Arc::clone(&image_repo),
+        ));
+
+        let state = AppState {
+            preview_use_case: Arc::new(PreviewConversionUseCase::new(conversion_service)),
+            batch_convert_use_case: Arc::new(BatchConvertUseCase::new(
+                Arc::clone(&upload_and_convert_use_case),
+                1,
+            )),
+            submit_job_use_case: Arc::new(SubmitConversionJobUseCase::new(
+                Arc::clone(&image_repo),
+                Arc::clone(&convert_use_case),
+                Arc::clone(&job_queue),
+            )),
+            get_job_use_case: Arc::new(GetJobUseCase::new(Arc::clone(&job_queue))),
+            cancel_job_use_case: Arc::new(CancelJobUseCase::new(job_queue)),
+            list_use_case: Arc::new(ListAsciiArtUseCase::new(Arc::clone(&ascii_art_repo))),
+            get_ascii_art_use_case: Arc::new(GetAsciiArtUseCase::new(Arc::clone(&ascii_art_repo))),
+            delete_ascii_art_use_case: Arc::new(DeleteAsciiArtUseCase::new(Arc::clone(
+                &ascii_art_repo,
+            ))),
+            get_image_use_case: Arc::new(GetImageUseCase::new(Arc::clone(&image_repo))),
+            delete_image_use_case: Arc::new(DeleteImageUseCase::new(
+                Arc::clone(&image_repo),
+                Arc::clone(&ascii_art_repo),
+                conversion_cache,
+            )),
+            storage_stats_use_case: Arc::new(GetStorageStatsUseCase::new(
+                Arc::clone(&image_repo),
+                Arc::clone(&ascii_art_repo),
+            )),
+            export_use_case: Arc::new(ExportAsciiArtUseCase::new(
+                Arc::clone(&ascii_art_repo),
+                Arc::new(ArtRenderer::new()),
+            )),
+            upload_use_case,
+            convert_use_case,
+            upload_and_convert_use_case,
+        };
+        (state, image_repo, ascii_art_repo)
+    }
+
+    /// Send a bodiless request, returning the status and the JSON body, if any
+    async fn send(app: &Router, method: &str, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+    }
+
+    #[tokio::test]
+    async fn stored_ascii_art_is_listed_fetched_and_deleted() {
+        let (state, image_repo, ascii_art_repo) = test_state();
+        let app = create_app(state);
+
+        let image = ImageData::new("a.png".into(), "image/png".into(), vec![1], 1, 1);
+        image_repo.save(&image).await.unwrap();
+        let start = chrono::Utc::now();
+        let mut ids = Vec::new();
+        for minutes in 0..3 {
+            let mut art =
+                AsciiArt::new(image.id, format!("art {minutes}"), 5, 1, DetailLevel::High);
+            art.created_at = start + chrono::Duration::minutes(minutes);
+            ascii_art_repo.save(&art).await.unwrap();
+            ids.push(art.id.to_string());
+        }
+
+        let (status, page) = send(&app, "GET", "/api/ascii?limit=2&offset=1").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            (page["count"].as_u64(), page["offset"].as_u64()),
+            (Some(2), Some(1))
+        );
+        assert_eq!(page["ascii_art"][0]["id"], ids[1].as_str());
+        assert_eq!(page["ascii_art"][1]["id"], ids[0].as_str());
+
+        let uri = format!("/api/images/{}/conversions?limit=1", image.id);
+        let (status, page) = send(&app, "GET", &uri).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(page["ascii_art"][0]["id"], ids[2].as_str());
+
+        let (status, art) = send(&app, "GET", &format!("/api/ascii/{}", ids[0])).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(art["ascii_art"], "art 0");
+
+        let uri = format!("/api/ascii/{}", ids[0]);
+        assert_eq!(send(&app, "DELETE", &uri).await.0, StatusCode::NO_CONTENT);
+        assert_eq!(send(&app, "GET", &uri).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "DELETE", &uri).await.0, StatusCode::NOT_FOUND);
+        assert_eq!(send(&app, "GET", "/api/ascii").await.1["count"], 2);
+    }
+
+    #[tokio::test]
+    async fn invalid_list_and_lookup_requests_are_rejected() {
+        let (state, ..) = test_state();
+        let app = create_app(state);
+        let unknown = uuid::Uuid::new_v4();
+
+        for uri in [
+            "/api/ascii?limit=0",
+            "/api/ascii?detail=extreme",
+            "/api/ascii?image_id=not-a-uuid",
+            "/api/ascii?created_after=2024-02-01T00:00:00Z&created_before=2024-01-01T00:00:00Z",
+            "/api/ascii/not-a-uuid",
+        ] {
+            assert_eq!(
+                send(&app, "GET", uri).await.0,
+                StatusCode::BAD_REQUEST,
+                "{uri}"
+            );
+        }
+
+        let uri = format!("/api/images/{unknown}/conversions");
+        assert_eq!(send(&app, "GET", &uri).await.0, StatusCode::NOT_FOUND);
+        let uri = format!("/api/ascii/{unknown}");
+        assert_eq!(send(&app, "GET", &uri).await.0, StatusCode::NOT_FOUND);
+    }
+
+    /// Post a multipart form holding a small PNG and the text fields `fields`
+    async fn post_form(app: &Router, uri: &str, fields: &[(&str, &str)]) -> StatusCode {
+        let mut png = Vec::new();
+        image::DynamicImage::ImageLuma8(image::GrayImage::new(8, 8))
+            .write_to(&mut std::io::Cursor::new(&mut png), image::ImageFormat::Png)
+            .unwrap();
+
+        let boundary = "form-boundary";
+        let mut body = Vec::new();
+        for (name, value) in fields {
+            body.extend_from_slice(
+                format!(
+                    "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+                )
+                .as_bytes(),
+            );
+        }
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"a.png\"\r\nContent-Type: image/png\r\n\r\n"
+            )
+            .as_bytes(),
+        );
+        body.extend_from_slice(&png);
+        body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+
+        let request = Request::builder()
+            .method("POST")
+            .uri(uri)
+            .header(
+                "content-type",
+                format!("multipart/form-data; boundary={boundary}"),
+            )
+            .body(Body::from(body))
+            .unwrap();
+        app.clone().oneshot(request).await.unwrap().status()
+    }
+
+    #[tokio::test]
+    async fn upload_and_convert_refuses_bad_form_fields() {
+        let (state, image_repo, ascii_art_repo) = test_state();
+        let app = create_app(state);
+
+        for field in [
+            ("persist", "maybe"),
+            ("width", "wide"),
+            ("width", "0"),
+            ("detail", "extreme"),
+            ("mode", "sketch"),
+            ("ramp", ""),
+        ] {
+            let status = post_form(&app, "/api/convert", &[field]).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{field:?}");
+        }
+        assert_eq!(image_repo.storage_stats().unwrap().entries, 0);
+
+        for format in ["json", "text", "png"] {
+            let fields = [("persist", "false"), ("format", format)];
+            let status = post_form(&app, "/api/convert", &fields).await;
+            assert_eq!(status, StatusCode::OK, "{format}");
+        }
+        assert_eq!(image_repo.storage_stats().unwrap().entries, 0);
+        assert_eq!(ascii_art_repo.storage_stats().unwrap().entries, 0);
+    }
+}
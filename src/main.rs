@@ -1,15 +1,29 @@
 use ascii_converter::{
     application::{
-        services::AsciiConversionService,
-        use_cases::{ConvertImageToAsciiUseCase, UploadImageUseCase},
+        services::{ArtRenderer, AsciiConversionService, ConversionCache, JobQueue},
+        use_cases::{
+            BatchConvertUseCase, CancelJobUseCase, ConvertImageToAsciiUseCase,
+            DeleteAsciiArtUseCase, DeleteImageUseCase, ExportAsciiArtUseCase, GetAsciiArtUseCase,
+            GetImageUseCase, GetJobUseCase, GetStorageStatsUseCase, ListAsciiArtUseCase,
+            PreviewConversionUseCase, SubmitConversionJobUseCase, UploadAndConvertUseCase,
+            UploadImageUseCase,
+        },
+    },
+    domain::{
+        repositories::{AsciiArtRepository, ImageRepository},
+        value_objects::ImageLimits,
     },
     infrastructure::{
-        repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
+        repositories::{
+            FileSystemAsciiArtRepository, FileSystemImageRepository, InMemoryAsciiArtRepository,
+            InMemoryImageRepository, MemoryLimits, SqliteAsciiArtRepository, SqliteDatabase,
+            SqliteImageRepository,
+        },
         web::create_app,
     },
     presentation::handlers::ascii_handlers::AppState,
 };
-use std::sync::Arc;
+use std::{str::FromStr, sync::Arc, time::Duration};
 use tokio::net::TcpListener;
 use tracing::{info, Level};
 
@@ -18,30 +32,179 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Initialize tracing
     tracing_subscriber::fmt().with_max_level(Level::INFO).init();
 
-    // Create repositories
-    let image_repo = Arc::new(InMemoryImageRepository::new());
-    let ascii_art_repo = Arc::new(InMemoryAsciiArtRepository::new());
+    // Select storage backend from environment variables, defaulting to in-memory
+    let storage = std::env::var("STORAGE").unwrap_or_else(|_| "memory".to_string());
+    let dir = std::env::var("STORAGE_DIR").unwrap_or_else(|_| "data".to_string());
+    match storage.as_str() {
+        "memory" => {
+            // Entries are kept until deleted unless a time to live or quota is set; 0 or
+            // unset lifts a limit
+            let ttl_secs: u64 = env_number("MEMORY_TTL_SECS").unwrap_or(0);
+            let ttl = (ttl_secs > 0).then(|| Duration::from_secs(ttl_secs));
+            let quota = |name| {
+                let megabytes: usize = env_number(name).unwrap_or(0);
+                (megabytes > 0).then(|| megabytes * 1024 * 1024)
+            };
+            let image_repo = InMemoryImageRepository::with_limits(MemoryLimits {
+                ttl,
+                max_bytes: quota("MEMORY_IMAGE_QUOTA_MB"),
+            });
+            let ascii_art_repo = InMemoryAsciiArtRepository::with_limits(MemoryLimits {
+                ttl,
+                max_bytes: quota("MEMORY_ASCII_ART_QUOTA_MB"),
+            });
+            let sweep_interval = Duration::from_secs(
+                env_number("MEMORY_SWEEP_INTERVAL_SECS")
+                    .unwrap_or(60)
+                    .max(1),
+            );
+            image_repo.spawn_sweeper(sweep_interval);
+            ascii_art_repo.spawn_sweeper(sweep_interval);
+
+            info!("💾 Using in-memory storage");
+            serve(Arc::new(image_repo), Arc::new(ascii_art_repo)).await
+        }
+        "disk" => {
+            info!("💾 Using disk storage in {}", dir);
+            serve(
+                Arc::new(FileSystemImageRepository::open(&dir).await?),
+                Arc::new(FileSystemAsciiArtRepository::open(&dir).await?),
+            )
+            .await
+        }
+        "sqlite" => {
+            std::fs::create_dir_all(&dir)?;
+            let path = std::path::Path::new(&dir).join("ascii-converter.sqlite3");
+            info!("💾 Using SQLite storage in {}", path.display());
+            let database = SqliteDatabase::open(&path)?;
+            serve(
+                Arc::new(SqliteImageRepository::new(database.clone())),
+                Arc::new(SqliteAsciiArtRepository::new(database)),
+            )
+            .await
+        }
+        other => Err(format!(
+            "Unknown STORAGE backend '{}' (expected memory, disk or sqlite)",
+            other
+        )
+        .into()),
+    }
+}
+
+/// Numeric setting from an environment variable, `None` when unset or invalid
+fn env_number<T: FromStr>(name: &str) -> Option<T> {
+    std::env::var(name)
+        .ok()
+        .and_then(|value| value.parse().ok())
+}
+
+/// Wire up use cases on top of the given repositories and run the server
+async fn serve<IR, AR>(
+    image_repo: Arc<IR>,
+    ascii_art_repo: Arc<AR>,
+) -> Result<(), Box<dyn std::error::Error>>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
+    // Images are refused from their header when they would decode beyond these limits
+    let defaults = ImageLimits::default();
+    let limits = ImageLimits {
+        max_width: env_number("MAX_IMAGE_WIDTH").unwrap_or(defaults.max_width),
+        max_height: env_number("MAX_IMAGE_HEIGHT").unwrap_or(defaults.max_height),
+        max_pixels: env_number("MAX_IMAGE_PIXELS").unwrap_or(defaults.max_pixels),
+    };
 
     // Create services
-    let conversion_service = Arc::new(AsciiConversionService::new());
+    let conversion_service = Arc::new(AsciiConversionService::with_limits(limits));
+    let renderer = Arc::new(ArtRenderer::new());
+
+    // Background and batch conversions each run on at most JOB_WORKERS blocking threads
+    // at a time
+    let workers = env_number("JOB_WORKERS").unwrap_or_else(|| {
+        std::thread::available_parallelism()
+            .map(|n| n.get())
+            .unwrap_or(1)
+    });
+    let job_queue = Arc::new(JobQueue::new(workers, JobQueue::DEFAULT_MAX_PENDING));
+
+    // Repeated conversions of an image reuse the stored result; 0 disables the cache
+    let cache_size =
+        env_number("CONVERSION_CACHE_SIZE").unwrap_or(ConversionCache::DEFAULT_CAPACITY);
+    let conversion_cache = Arc::new(ConversionCache::new(cache_size));
 
     // Create use cases
     const MAX_FILE_SIZE: usize = 10 * 1024 * 1024; // 10MB
     let upload_use_case = Arc::new(UploadImageUseCase::new(
         Arc::clone(&image_repo),
         MAX_FILE_SIZE,
+        limits,
     ));
 
+    let preview_use_case = Arc::new(PreviewConversionUseCase::new(Arc::clone(
+        &conversion_service,
+    )));
     let convert_use_case = Arc::new(ConvertImageToAsciiUseCase::new(
         Arc::clone(&image_repo),
         Arc::clone(&ascii_art_repo),
         conversion_service,
+        Arc::clone(&conversion_cache),
+    ));
+    let upload_and_convert_use_case = Arc::new(UploadAndConvertUseCase::new(
+        Arc::clone(&upload_use_case),
+        Arc::clone(&convert_use_case),
+        Arc::clone(&image_repo),
+    ));
+    let batch_convert_use_case = Arc::new(BatchConvertUseCase::new(
+        Arc::clone(&upload_and_convert_use_case),
+        workers,
+    ));
+    let submit_job_use_case = Arc::new(SubmitConversionJobUseCase::new(
+        Arc::clone(&image_repo),
+        Arc::clone(&convert_use_case),
+        Arc::clone(&job_queue),
+    ));
+    let get_job_use_case = Arc::new(GetJobUseCase::new(Arc::clone(&job_queue)));
+    let cancel_job_use_case = Arc::new(CancelJobUseCase::new(job_queue));
+
+    let list_use_case = Arc::new(ListAsciiArtUseCase::new(Arc::clone(&ascii_art_repo)));
+    let get_ascii_art_use_case = Arc::new(GetAsciiArtUseCase::new(Arc::clone(&ascii_art_repo)));
+    let delete_ascii_art_use_case =
+        Arc::new(DeleteAsciiArtUseCase::new(Arc::clone(&ascii_art_repo)));
+    let get_image_use_case = Arc::new(GetImageUseCase::new(Arc::clone(&image_repo)));
+    let delete_image_use_case = Arc::new(DeleteImageUseCase::new(
+        Arc::clone(&image_repo),
+        Arc::clone(&ascii_art_repo),
+        conversion_cache,
+    ));
+    // Images the memory backend expires or evicts take their conversions along
+    delete_image_use_case.cascade_evictions();
+    let storage_stats_use_case = Arc::new(GetStorageStatsUseCase::new(
+        Arc::clone(&image_repo),
+        Arc::clone(&ascii_art_repo),
+    ));
+    let export_use_case = Arc::new(ExportAsciiArtUseCase::new(
+        Arc::clone(&ascii_art_repo),
+        renderer,
     ));
 
     // Create application state
     let state = AppState {
         upload_use_case,
         convert_use_case,
+        list_use_case,
+        get_ascii_art_use_case,
+        delete_ascii_art_use_case,
+        get_image_use_case,
+        delete_image_use_case,
+        export_use_case,
+        upload_and_convert_use_case,
+        batch_convert_use_case,
+        submit_job_use_case,
+        get_job_use_case,
+        cancel_job_use_case,
+        preview_use_case,
+        storage_stats_use_case,
     };
 
     // Create application
@@ -66,6 +229,46 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     info!("      ?detail=high|low            - Detail level (default: high)");
     info!("      ?contrast=1.2               - Contrast factor (default: 1.2)");
     info!("      ?blur=0.5                   - Blur sigma (default: 0.5)");
+    info!("      ?color=none|256|truecolor   - ANSI color mode (default: none)");
+    info!("      ?mode=ascii|braille|halfblock|quadrant|structural|edges - Render mode (default: ascii)");
+    info!("      ?threshold=0-255            - Braille/block threshold (default: automatic)");
+    info!("      ?dither=none|floyd-steinberg|atkinson|jarvis|sierra|bayer2|bayer4|bayer8");
+    info!("                                  - Dithering (default: none)");
+    info!("      ?serpentine=true            - Serpentine error diffusion (default: false)");
+    info!("      ?invert=true                - Swap light and dark (default: false)");
+    info!("      ?ramp=%20.oO@               - Custom glyph ramp, light to dense (URL-encoded)");
+    info!("      ?sort_ramp=true             - Sort custom ramp by ink density (default: false)");
+    info!("      ?edge_high=0.3&edge_low=0.15 - Edge hysteresis thresholds (edges mode)");
+    info!("      ?edge_thin=true             - Non-maximum suppression (edges mode)");
+    info!("      ?edge_fill=ramp|blank       - Fill for non-edge cells (edges mode)");
+    info!("  POST /api/convert               - Upload and convert in one request (multipart form)");
+    info!("      persist=false               - Discard the uploaded image after converting");
+    info!("  POST /api/batch                 - Convert several images or ZIP archives at once");
+    info!("      format=json|zip             - Manifest of results, or ZIP of .txt files");
+    info!(
+        "  POST /api/jobs?image_id=uuid    - Convert in the background (convert parameters apply)"
+    );
+    info!("  GET  /api/jobs/:id              - Poll job status and progress");
+    info!("  POST /api/jobs/:id/cancel       - Cancel a queued or running job");
+    info!("  GET  /api/storage               - Storage usage and eviction counters");
+    info!("  GET  /api/ascii                 - List converted ASCII art, newest first");
+    info!("    Query parameters:");
+    info!("      ?image_id=uuid              - Only conversions of this image");
+    info!("      ?detail=high|low            - Only this detail level");
+    info!("      ?created_after=2024-01-01T00:00:00Z&created_before=... - Date range (RFC 3339)");
+    info!("      ?limit=50&offset=0          - Page (default: 50, max: 200)");
+    info!("  GET  /api/ascii/:id             - Get stored ASCII art");
+    info!("  DELETE /api/ascii/:id           - Delete stored ASCII art");
+    info!("  GET  /api/ascii/:id/render.png  - Render stored ASCII art as PNG (or render.svg, render.html)");
+    info!("    Query parameters:");
+    info!("      ?fg=ffffff&bg=000000        - Text and background colors");
+    info!("      ?font_size=13&padding=16    - Row height and margin in pixels");
+    info!("      ?colors=true                - Keep per-cell ANSI colors (default: true)");
+    info!("  GET  /api/images/:image_id      - Get uploaded image metadata");
+    info!("  DELETE /api/images/:image_id    - Delete image and all of its conversions");
+    info!(
+        "  GET  /api/images/:image_id/conversions - List conversions of an image (?limit&offset)"
+    );
 
     axum::serve(listener, app).await?;
 
@@ -1,31 +1,86 @@
 use crate::{
-    application::use_cases::{
-        convert_image_to_ascii::ConvertImageRequest, upload_image::UploadImageRequest,
-        ConvertImageToAsciiUseCase, UploadImageUseCase,
+    application::{
+        services::RenderError,
+        use_cases::{
+            convert_image_to_ascii::{
+                ConvertImageError, ConvertImageRequest, ConvertImageResponse,
+            },
+            delete_ascii_art::DeleteAsciiArtError,
+            delete_image::DeleteImageError,
+            export_ascii_art::{
+                ExportAsciiArtError, ExportAsciiArtRequest, ExportAsciiArtResponse,
+            },
+            get_ascii_art::GetAsciiArtError,
+            get_image::GetImageError,
+            list_ascii_art::{ListAsciiArtError, DEFAULT_PAGE_SIZE},
+            upload_and_convert::{UploadAndConvertError, UploadAndConvertRequest},
+            upload_image::{UploadImageError, UploadImageRequest},
+            BatchConvertUseCase, CancelJobUseCase, ConvertImageToAsciiUseCase,
+            DeleteAsciiArtUseCase, DeleteImageUseCase, ExportAsciiArtUseCase, GetAsciiArtUseCase,
+            GetImageUseCase, GetJobUseCase, GetStorageStatsUseCase, ListAsciiArtUseCase,
+            PreviewConversionUseCase, SubmitConversionJobUseCase, UploadAndConvertUseCase,
+            UploadImageUseCase,
+        },
     },
     domain::{
-        entities::ascii_art::DetailLevel,
-        repositories::{AsciiArtRepository, ImageRepository},
-        value_objects::ConversionConfig,
-    },
-    infrastructure::{
-        repositories::{InMemoryAsciiArtRepository, InMemoryImageRepository},
-        web::{error::WebError, extractors::ImageUpload},
+        entities::{ascii_art::DetailLevel, AsciiArt, AsciiFrame, ImageData},
+        repositories::{AsciiArtQuery, AsciiArtRepository, ImageRepository, StorageStats},
+        value_objects::{
+            parse_hex_color, ColorMode, ConversionConfig, ConversionParams, Dithering, EdgeFill,
+            ExportFormat, ExportOptions, RenderMode,
+        },
     },
+    infrastructure::web::{error::WebError, extractors::ImageUpload},
 };
 use axum::{
     extract::{Path, Query, State},
-    response::Json,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::sync::Arc;
 use uuid::Uuid;
 
 /// Application state containing use cases
-#[derive(Clone)]
 pub struct AppState<IR: ImageRepository, AR: AsciiArtRepository> {
     pub upload_use_case: Arc<UploadImageUseCase<IR>>,
     pub convert_use_case: Arc<ConvertImageToAsciiUseCase<IR, AR>>,
+    pub list_use_case: Arc<ListAsciiArtUseCase<AR>>,
+    pub get_ascii_art_use_case: Arc<GetAsciiArtUseCase<AR>>,
+    pub delete_ascii_art_use_case: Arc<DeleteAsciiArtUseCase<AR>>,
+    pub get_image_use_case: Arc<GetImageUseCase<IR>>,
+    pub delete_image_use_case: Arc<DeleteImageUseCase<IR, AR>>,
+    pub export_use_case: Arc<ExportAsciiArtUseCase<AR>>,
+    pub upload_and_convert_use_case: Arc<UploadAndConvertUseCase<IR, AR>>,
+    pub batch_convert_use_case: Arc<BatchConvertUseCase<IR, AR>>,
+    pub submit_job_use_case: Arc<SubmitConversionJobUseCase<IR, AR>>,
+    pub get_job_use_case: Arc<GetJobUseCase>,
+    pub cancel_job_use_case: Arc<CancelJobUseCase>,
+    pub preview_use_case: Arc<PreviewConversionUseCase>,
+    pub storage_stats_use_case: Arc<GetStorageStatsUseCase<IR, AR>>,
+}
+
+// Implemented by hand since the repositories themselves need not be Clone
+impl<IR: ImageRepository, AR: AsciiArtRepository> Clone for AppState<IR, AR> {
+    fn clone(&self) -> Self {
+        Self {
+            upload_use_case: Arc::clone(&self.upload_use_case),
+            convert_use_case: Arc::clone(&self.convert_use_case),
+            list_use_case: Arc::clone(&self.list_use_case),
+            get_ascii_art_use_case: Arc::clone(&self.get_ascii_art_use_case),
+            delete_ascii_art_use_case: Arc::clone(&self.delete_ascii_art_use_case),
+            get_image_use_case: Arc::clone(&self.get_image_use_case),
+            delete_image_use_case: Arc::clone(&self.delete_image_use_case),
+            export_use_case: Arc::clone(&self.export_use_case),
+            upload_and_convert_use_case: Arc::clone(&self.upload_and_convert_use_case),
+            batch_convert_use_case: Arc::clone(&self.batch_convert_use_case),
+            submit_job_use_case: Arc::clone(&self.submit_job_use_case),
+            get_job_use_case: Arc::clone(&self.get_job_use_case),
+            cancel_job_use_case: Arc::clone(&self.cancel_job_use_case),
+            preview_use_case: Arc::clone(&self.preview_use_case),
+            storage_stats_use_case: Arc::clone(&self.storage_stats_use_case),
+        }
+    }
 }
 
 /// Request for converting image to ASCII
@@ -35,30 +90,238 @@ pub struct ConvertToAsciiRequest {
     pub detail: Option<String>,
     pub contrast: Option<f32>,
     pub blur: Option<f32>,
+    pub color: Option<String>,
+    pub mode: Option<String>,
+    pub threshold: Option<u8>,
+    pub dither: Option<String>,
+    pub serpentine: Option<bool>,
+    pub invert: Option<bool>,
+    pub ramp: Option<String>,
+    pub sort_ramp: Option<bool>,
+    pub edge_high: Option<f32>,
+    pub edge_low: Option<f32>,
+    pub edge_thin: Option<bool>,
+    pub edge_fill: Option<String>,
+    /// Response format, overriding the `Accept` header: json, text, ansi, html,
+    /// html-snippet, png or svg
+    pub format: Option<String>,
+    /// Send `Content-Disposition: attachment` so that the response downloads as a file
+    pub download: Option<bool>,
+}
+
+/// Fields of the single-shot upload and convert form besides the conversion parameters
+#[derive(Debug, Deserialize)]
+pub struct UploadAndConvertForm {
+    /// Keep the uploaded image for later conversions (default: true)
+    pub persist: Option<bool>,
+}
+
+/// Filters for listing converted ASCII art
+#[derive(Debug, Deserialize)]
+pub struct ListAsciiArtRequest {
+    pub image_id: Option<String>,
+    pub detail: Option<String>,
+    /// RFC 3339 timestamp, inclusive
+    pub created_after: Option<String>,
+    /// RFC 3339 timestamp, exclusive
+    pub created_before: Option<String>,
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// Page of a list endpoint
+#[derive(Debug, Deserialize)]
+pub struct PaginationRequest {
+    pub limit: Option<usize>,
+    pub offset: Option<usize>,
+}
+
+/// Appearance options for exporting ASCII art as an image or HTML
+#[derive(Debug, Default, Deserialize)]
+pub struct ExportRequest {
+    /// Text color as hex, e.g. `ffffff`
+    pub fg: Option<String>,
+    /// Background color as hex, e.g. `000000`
+    pub bg: Option<String>,
+    pub padding: Option<u32>,
+    pub font_size: Option<u32>,
+    /// Use the ANSI colors of colored art per cell
+    pub colors: Option<bool>,
+    /// HTML only: return an embeddable `<pre>` element instead of a full document
+    pub embed: Option<bool>,
+    /// Send `Content-Disposition: attachment` so that the response downloads as a file
+    pub download: Option<bool>,
 }
 
 /// Response for image upload
 #[derive(Debug, Serialize)]
 pub struct UploadResponse {
     pub image_id: String,
+    /// Format detected from the file's contents
     pub format: String,
+    /// Format named by the content type or file extension, when it names one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_format: Option<String>,
     pub width: u32,
     pub height: u32,
+    /// The same file was uploaded before, and `image_id` is that upload's image
+    pub deduplicated: bool,
     pub message: String,
 }
 
 /// Response for ASCII conversion
 #[derive(Debug, Serialize)]
 pub struct ConvertResponse {
-    pub ascii_art_id: String,
+    /// `null` when the conversion was not stored
+    pub ascii_art_id: Option<String>,
+    pub ascii_art: String,
+    pub width: u32,
+    pub height: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationResponse>,
+    /// An identical earlier conversion was returned instead of converting again
+    pub cached: bool,
+}
+
+/// Response for storage usage; a repository is `null` when its backend keeps
+/// everything until deleted
+#[derive(Debug, Serialize)]
+pub struct StorageResponse {
+    pub images: Option<StorageStats>,
+    pub ascii_art: Option<StorageStats>,
+}
+
+/// Summary of a stored conversion, without its content
+#[derive(Debug, Serialize)]
+pub struct AsciiArtSummary {
+    pub id: String,
+    pub image_id: String,
+    pub width: u32,
+    pub height: u32,
+    pub detail: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub animated: bool,
+}
+
+impl From<AsciiArt> for AsciiArtSummary {
+    fn from(ascii_art: AsciiArt) -> Self {
+        Self {
+            id: ascii_art.id.to_string(),
+            image_id: ascii_art.image_id.to_string(),
+            width: ascii_art.width,
+            height: ascii_art.height,
+            detail: ascii_art.detail_level.to_string(),
+            created_at: ascii_art.created_at,
+            animated: ascii_art.is_animated(),
+        }
+    }
+}
+
+/// Response for listing ASCII art
+#[derive(Debug, Serialize)]
+pub struct ListAsciiArtResponse {
+    pub ascii_art: Vec<AsciiArtSummary>,
+    pub count: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+/// Response for fetching a stored conversion
+#[derive(Debug, Serialize)]
+pub struct AsciiArtResponse {
+    pub id: String,
+    pub image_id: String,
     pub ascii_art: String,
     pub width: u32,
     pub height: u32,
+    pub detail: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationResponse>,
+}
+
+impl From<AsciiArt> for AsciiArtResponse {
+    fn from(ascii_art: AsciiArt) -> Self {
+        Self {
+            id: ascii_art.id.to_string(),
+            image_id: ascii_art.image_id.to_string(),
+            ascii_art: ascii_art.content,
+            width: ascii_art.width,
+            height: ascii_art.height,
+            detail: ascii_art.detail_level.to_string(),
+            created_at: ascii_art.created_at,
+            animation: AnimationResponse::from_frames(ascii_art.frames),
+        }
+    }
+}
+
+/// Response for fetching an uploaded image's metadata
+#[derive(Debug, Serialize)]
+pub struct ImageResponse {
+    pub image_id: String,
+    pub filename: String,
+    pub content_type: String,
+    pub width: u32,
+    pub height: u32,
+    pub size_bytes: usize,
+    /// Hex SHA-256 of the image bytes
+    pub content_hash: String,
+    /// Number of uploads sharing this image
+    pub references: u32,
+    /// Format detected from the file's contents, unknown for images stored before
+    /// detection
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+    /// Format the upload claimed to be
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub declared_format: Option<String>,
+}
+
+impl From<ImageData> for ImageResponse {
+    fn from(image: ImageData) -> Self {
+        Self {
+            image_id: image.id.to_string(),
+            filename: image.original_filename,
+            content_type: image.content_type,
+            width: image.width,
+            height: image.height,
+            size_bytes: image.data.len(),
+            content_hash: image.content_hash,
+            references: image.ref_count,
+            format: image.detected_format.map(|format| format.to_string()),
+            declared_format: image.declared_format.map(|format| format.to_string()),
+        }
+    }
+}
+
+/// Frame sequence of an animated conversion, laid out for playback:
+/// show `frames[i]` for `delays_ms[i]` milliseconds, then loop
+#[derive(Debug, Serialize)]
+pub struct AnimationResponse {
+    pub frames: Vec<String>,
+    pub delays_ms: Vec<u32>,
+    pub duration_ms: u32,
+}
+
+impl AnimationResponse {
+    /// Build the playback layout from frames, if there is more than one
+    pub fn from_frames(frames: Vec<AsciiFrame>) -> Option<Self> {
+        if frames.len() < 2 {
+            return None;
+        }
+
+        let delays_ms: Vec<u32> = frames.iter().map(|frame| frame.delay_ms).collect();
+        Some(Self {
+            duration_ms: delays_ms.iter().sum(),
+            frames: frames.into_iter().map(|frame| frame.content).collect(),
+            delays_ms,
+        })
+    }
 }
 
 /// Upload image endpoint
-pub async fn upload_image(
-    State(state): State<AppState<InMemoryImageRepository, InMemoryAsciiArtRepository>>,
+pub async fn upload_image<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
     upload: ImageUpload,
 ) -> Result<Json<UploadResponse>, WebError> {
     let request = UploadImageRequest {
@@ -71,26 +334,121 @@ pub async fn upload_image(
         .upload_use_case
         .execute(request)
         .await
-        .map_err(|e| WebError::BadRequest(e.to_string()))?;
+        .map_err(upload_error)?;
+
+    let mut message = if response.deduplicated {
+        "Image already uploaded; reusing the stored copy".to_string()
+    } else {
+        "Image uploaded successfully".to_string()
+    };
+    if let Some(declared) = response.declared_format.filter(|&f| f != response.format) {
+        message.push_str(&format!(
+            " (declared as {} but detected as {})",
+            declared, response.format
+        ));
+    }
 
     Ok(Json(UploadResponse {
         image_id: response.image_id.to_string(),
         format: response.format.to_string(),
+        declared_format: response.declared_format.map(|format| format.to_string()),
         width: response.width,
         height: response.height,
-        message: "Image uploaded successfully".to_string(),
+        deduplicated: response.deduplicated,
+        message,
     }))
 }
 
 /// Convert image to ASCII endpoint
-pub async fn convert_to_ascii(
-    State(state): State<AppState<InMemoryImageRepository, InMemoryAsciiArtRepository>>,
+pub async fn convert_to_ascii<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
     Path(image_id): Path<String>,
     Query(params): Query<ConvertToAsciiRequest>,
-) -> Result<Json<ConvertResponse>, WebError> {
+    headers: HeaderMap,
+) -> Result<Response, WebError> {
     let image_id = Uuid::parse_str(&image_id)
         .map_err(|_| WebError::BadRequest("Invalid image ID format".to_string()))?;
 
+    let config = conversion_config(&params)?;
+    let export_format = response_format(params.format.as_deref(), &headers)?;
+
+    let request = ConvertImageRequest { image_id, config };
+
+    let response = state
+        .convert_use_case
+        .execute(request)
+        .await
+        .map_err(convert_error)?;
+
+    conversion_response(
+        &state,
+        response,
+        export_format,
+        params.download.unwrap_or(false),
+    )
+    .await
+}
+
+/// Upload an image and convert it in one request. The multipart form carries the image
+/// in its `image` field and the conversion parameters as further fields.
+pub async fn upload_and_convert<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    headers: HeaderMap,
+    upload: ImageUpload,
+) -> Result<Response, WebError> {
+    let params: ConvertToAsciiRequest = parse_form(&upload.fields)?;
+    let options: UploadAndConvertForm = parse_form(&upload.fields)?;
+
+    let config = conversion_config(&params)?;
+    let export_format = response_format(params.format.as_deref(), &headers)?;
+
+    let response = state
+        .upload_and_convert_use_case
+        .execute(UploadAndConvertRequest {
+            upload: UploadImageRequest {
+                filename: upload.filename,
+                content_type: upload.content_type,
+                data: upload.data,
+            },
+            config,
+            persist_image: options.persist.unwrap_or(true),
+        })
+        .await
+        .map_err(|e| match e {
+            UploadAndConvertError::Upload(e) => upload_error(e),
+            UploadAndConvertError::Convert(e) => convert_error(e),
+        })?;
+
+    let mut http_response = conversion_response(
+        &state,
+        response.conversion,
+        export_format,
+        params.download.unwrap_or(false),
+    )
+    .await?;
+    if let Some(image_id) = response.image_id {
+        if let Ok(value) = HeaderValue::from_str(&image_id.to_string()) {
+            http_response.headers_mut().insert(IMAGE_ID_HEADER, value);
+        }
+    }
+    Ok(http_response)
+}
+
+/// Header naming the stored original of a single-shot conversion
+const IMAGE_ID_HEADER: &str = "x-image-id";
+
+/// Read the text fields of a multipart form into a parameter struct
+pub(crate) fn parse_form<T: DeserializeOwned>(fields: &[(String, String)]) -> Result<T, WebError> {
+    let form =
+        serde_urlencoded::to_string(fields).map_err(|e| WebError::BadRequest(e.to_string()))?;
+    serde_urlencoded::from_str(&form)
+        .map_err(|e| WebError::BadRequest(format!("Invalid form field: {}", e)))
+}
+
+/// Assemble the conversion configuration from the request parameters
+pub(crate) fn conversion_config(
+    params: &ConvertToAsciiRequest,
+) -> Result<ConversionConfig, WebError> {
     let detail_level = match params.detail.as_deref() {
         Some("low") => DetailLevel::Low,
         Some("high") | None => DetailLevel::High,
@@ -101,39 +459,493 @@ pub async fn convert_to_ascii(
         }
     };
 
-    let width = params.width.unwrap_or(100);
-    let contrast = params.contrast.unwrap_or(1.2);
-    let blur = params.blur.unwrap_or(0.5);
+    let color_mode = match params.color.as_deref() {
+        Some(name) => ColorMode::from_name(name).ok_or_else(|| {
+            WebError::BadRequest("Invalid color mode. Use 'none', '256' or 'truecolor'".to_string())
+        })?,
+        None => ColorMode::None,
+    };
 
-    let config = ConversionConfig::with_params(width, detail_level, contrast, blur);
+    let render_mode = match params.mode.as_deref() {
+        Some(name) => RenderMode::from_name(name).ok_or_else(|| {
+            WebError::BadRequest(
+                "Invalid render mode. Use 'ascii', 'braille', 'halfblock', 'quadrant', 'structural' or 'edges'"
+                    .to_string(),
+            )
+        })?,
+        None => RenderMode::Ascii,
+    };
 
-    if !config.is_valid() {
-        return Err(WebError::BadRequest(
-            "Invalid conversion parameters".to_string(),
-        ));
+    let dithering = match params.dither.as_deref() {
+        Some(name) => Dithering::from_name(name).ok_or_else(|| {
+            WebError::BadRequest(
+                "Invalid dithering. Use 'none', 'floyd-steinberg', 'atkinson', 'jarvis', 'sierra', \
+                 'bayer2', 'bayer4' or 'bayer8'"
+                    .to_string(),
+            )
+        })?,
+        None => Dithering::None,
+    };
+
+    let edge_fill = match params.edge_fill.as_deref() {
+        Some(name) => Some(EdgeFill::from_name(name).ok_or_else(|| {
+            WebError::BadRequest("Invalid edge fill. Use 'ramp' or 'blank'".to_string())
+        })?),
+        None => None,
+    };
+
+    ConversionParams {
+        width: params.width,
+        detail_level: Some(detail_level),
+        contrast_factor: params.contrast,
+        blur_sigma: params.blur,
+        color_mode: Some(color_mode),
+        render_mode: Some(render_mode),
+        threshold: params.threshold,
+        dithering: Some(dithering),
+        serpentine: params.serpentine,
+        invert: params.invert,
+        ramp: params.ramp.clone(),
+        sort_ramp: params.sort_ramp,
+        edge_high: params.edge_high,
+        edge_low: params.edge_low,
+        edge_thin: params.edge_thin,
+        edge_fill,
     }
+    .into_config()
+    .map_err(|e| WebError::BadRequest(e.to_string()))
+}
 
-    let request = ConvertImageRequest { image_id, config };
+/// Map a failed upload to its HTTP error
+fn upload_error(e: UploadImageError) -> WebError {
+    match e {
+        UploadImageError::InvalidImageData => WebError::BadRequest(e.to_string()),
+        UploadImageError::ImageTooLarge { .. } => WebError::PayloadTooLarge,
+        UploadImageError::DimensionsTooLarge { .. } => WebError::UnprocessableEntity(e.to_string()),
+        UploadImageError::UnsupportedFormat => WebError::UnsupportedMediaType,
+        UploadImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+    }
+}
 
-    let response = state
-        .convert_use_case
-        .execute(request)
+/// Map a failed conversion to its HTTP error
+fn convert_error(e: ConvertImageError) -> WebError {
+    match e {
+        ConvertImageError::ImageNotFound => WebError::NotFound("Image not found".to_string()),
+        ConvertImageError::InvalidConfig => WebError::BadRequest(e.to_string()),
+        ConvertImageError::LimitsExceeded(_) => WebError::UnprocessableEntity(e.to_string()),
+        ConvertImageError::ConversionFailed(_)
+        | ConvertImageError::Cancelled
+        | ConvertImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+    }
+}
+
+/// Respond with a conversion as JSON or, when another format was negotiated, as an export
+async fn conversion_response<IR: ImageRepository, AR: AsciiArtRepository>(
+    state: &AppState<IR, AR>,
+    response: ConvertImageResponse,
+    export_format: Option<ExportFormat>,
+    download: bool,
+) -> Result<Response, WebError> {
+    let cache_status = if response.cached { "hit" } else { "miss" };
+    let mut http_response = match export_format {
+        Some(format) => {
+            let export = state
+                .export_use_case
+                .export_content(response.content, format, ExportOptions::default())
+                .await
+                .map_err(export_error)?;
+            export_response(export, response.ascii_art_id, format, download)
+        }
+        None => {
+            let mut json = Json(ConvertResponse {
+                ascii_art_id: response.ascii_art_id.map(|id| id.to_string()),
+                ascii_art: response.content,
+                width: response.width,
+                height: response.height,
+                animation: AnimationResponse::from_frames(response.frames),
+                cached: response.cached,
+            })
+            .into_response();
+            if download {
+                attach(&mut json, response.ascii_art_id, "json");
+            }
+            json
+        }
+    };
+    http_response
+        .headers_mut()
+        .insert(header::VARY, HeaderValue::from_static("accept"));
+    http_response.headers_mut().insert(
+        CONVERSION_CACHE_HEADER,
+        HeaderValue::from_static(cache_status),
+    );
+    Ok(http_response)
+}
+
+/// Header telling whether a conversion came from the cache: `hit` or `miss`
+const CONVERSION_CACHE_HEADER: &str = "x-conversion-cache";
+
+/// Pick the response format from the `format` parameter or, failing that, the `Accept`
+/// header; `None` stands for the JSON response
+fn response_format(
+    format: Option<&str>,
+    headers: &HeaderMap,
+) -> Result<Option<ExportFormat>, WebError> {
+    if let Some(name) = format {
+        if name.eq_ignore_ascii_case("json") {
+            return Ok(None);
+        }
+        return ExportFormat::from_name(name).map(Some).ok_or_else(|| {
+            WebError::BadRequest(
+                "Invalid format. Use 'json', 'text', 'ansi', 'html', 'html-snippet', 'png' or 'svg'"
+                    .to_string(),
+            )
+        });
+    }
+
+    let Some(accept) = headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .filter(|value| !value.trim().is_empty())
+    else {
+        return Ok(None);
+    };
+
+    let mut ranges: Vec<(&str, f32)> = accept
+        .split(',')
+        .map(|range| {
+            let mut parts = range.split(';');
+            let media_type = parts.next().unwrap_or("").trim();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse().ok())
+                .unwrap_or(1.0);
+            (media_type, quality)
+        })
+        .filter(|(_, quality)| *quality > 0.0)
+        .collect();
+    // Stable, so equally preferred types keep the client's order
+    ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    for (media_type, _) in ranges {
+        match media_type.to_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => return Ok(None),
+            other => {
+                if let Some(format) = ExportFormat::from_media_type(other) {
+                    return Ok(Some(format));
+                }
+            }
+        }
+    }
+    Err(WebError::NotAcceptable(
+        "Supported types are application/json, text/plain, text/x-ansi, text/html, \
+         image/png and image/svg+xml"
+            .to_string(),
+    ))
+}
+
+/// Mark a response as a file download named after the ASCII art, or generically when it
+/// isn't stored
+fn attach(response: &mut Response, ascii_art_id: Option<Uuid>, extension: &str) {
+    let name = ascii_art_id.map_or_else(|| "ascii-art".to_string(), |id| id.to_string());
+    let disposition = format!("attachment; filename=\"{}.{}\"", name, extension);
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_DISPOSITION, value);
+    }
+}
+
+/// List converted ASCII art endpoint, newest first
+pub async fn list_ascii_art<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Query(params): Query<ListAsciiArtRequest>,
+) -> Result<Json<ListAsciiArtResponse>, WebError> {
+    let parse_timestamp = |value: &str| {
+        chrono::DateTime::parse_from_rfc3339(value)
+            .map(|t| t.with_timezone(&chrono::Utc))
+            .map_err(|_| {
+                WebError::BadRequest(format!("Invalid timestamp '{}'. Use RFC 3339", value))
+            })
+    };
+
+    let query = AsciiArtQuery {
+        image_id: params
+            .image_id
+            .as_deref()
+            .map(|id| parse_id(id, "image"))
+            .transpose()?,
+        detail_level: match params.detail.as_deref() {
+            Some(name) => Some(DetailLevel::from_name(name).ok_or_else(|| {
+                WebError::BadRequest("Invalid detail level. Use 'low' or 'high'".to_string())
+            })?),
+            None => None,
+        },
+        created_after: params
+            .created_after
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+        created_before: params
+            .created_before
+            .as_deref()
+            .map(parse_timestamp)
+            .transpose()?,
+        offset: params.offset.unwrap_or(0),
+        limit: Some(params.limit.unwrap_or(DEFAULT_PAGE_SIZE)),
+    };
+
+    list_page(&state, query).await
+}
+
+/// List the conversions of an image endpoint, newest first
+pub async fn list_image_conversions<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(image_id): Path<String>,
+    Query(params): Query<PaginationRequest>,
+) -> Result<Json<ListAsciiArtResponse>, WebError> {
+    let image_id = parse_id(&image_id, "image")?;
+
+    // Distinguish an unknown image from one without conversions
+    state
+        .get_image_use_case
+        .execute(image_id)
         .await
         .map_err(|e| match e {
-            crate::application::use_cases::convert_image_to_ascii::ConvertImageError::ImageNotFound => {
-                WebError::NotFound("Image not found".to_string())
+            GetImageError::NotFound => WebError::NotFound(e.to_string()),
+            GetImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    let query = AsciiArtQuery {
+        image_id: Some(image_id),
+        offset: params.offset.unwrap_or(0),
+        limit: Some(params.limit.unwrap_or(DEFAULT_PAGE_SIZE)),
+        ..AsciiArtQuery::default()
+    };
+
+    list_page(&state, query).await
+}
+
+/// Run a paginated ASCII art query
+async fn list_page<IR: ImageRepository, AR: AsciiArtRepository>(
+    state: &AppState<IR, AR>,
+    query: AsciiArtQuery,
+) -> Result<Json<ListAsciiArtResponse>, WebError> {
+    let (offset, limit) = (query.offset, query.limit.unwrap_or(DEFAULT_PAGE_SIZE));
+
+    let ascii_art = state
+        .list_use_case
+        .execute(query)
+        .await
+        .map_err(|e| match e {
+            ListAsciiArtError::InvalidDateRange | ListAsciiArtError::InvalidLimit { .. } => {
+                WebError::BadRequest(e.to_string())
             }
-            _ => WebError::InternalServerError(e.to_string()),
+            ListAsciiArtError::Repository(_) => WebError::InternalServerError(e.to_string()),
         })?;
 
-    Ok(Json(ConvertResponse {
-        ascii_art_id: response.ascii_art_id.to_string(),
-        ascii_art: response.content,
-        width: response.width,
-        height: response.height,
+    Ok(Json(ListAsciiArtResponse {
+        count: ascii_art.len(),
+        ascii_art: ascii_art.into_iter().map(AsciiArtSummary::from).collect(),
+        offset,
+        limit,
     }))
 }
 
+/// Get stored ASCII art endpoint
+pub async fn get_ascii_art<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+) -> Result<Json<AsciiArtResponse>, WebError> {
+    let id = parse_id(&id, "ASCII art")?;
+
+    let ascii_art = state
+        .get_ascii_art_use_case
+        .execute(id)
+        .await
+        .map_err(|e| match e {
+            GetAsciiArtError::NotFound => WebError::NotFound(e.to_string()),
+            GetAsciiArtError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    Ok(Json(AsciiArtResponse::from(ascii_art)))
+}
+
+/// Delete stored ASCII art endpoint
+pub async fn delete_ascii_art<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, WebError> {
+    let id = parse_id(&id, "ASCII art")?;
+
+    state
+        .delete_ascii_art_use_case
+        .execute(id)
+        .await
+        .map_err(|e| match e {
+            DeleteAsciiArtError::NotFound => WebError::NotFound(e.to_string()),
+            DeleteAsciiArtError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Get uploaded image metadata endpoint
+pub async fn get_image<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(image_id): Path<String>,
+) -> Result<Json<ImageResponse>, WebError> {
+    let image_id = parse_id(&image_id, "image")?;
+
+    let image = state
+        .get_image_use_case
+        .execute(image_id)
+        .await
+        .map_err(|e| match e {
+            GetImageError::NotFound => WebError::NotFound(e.to_string()),
+            GetImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    Ok(Json(ImageResponse::from(image)))
+}
+
+/// Delete uploaded image endpoint, along with all of its conversions
+pub async fn delete_image<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(image_id): Path<String>,
+) -> Result<StatusCode, WebError> {
+    let image_id = parse_id(&image_id, "image")?;
+
+    state
+        .delete_image_use_case
+        .execute(image_id)
+        .await
+        .map_err(|e| match e {
+            DeleteImageError::NotFound => WebError::NotFound(e.to_string()),
+            DeleteImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Render stored ASCII art as PNG endpoint
+pub async fn render_ascii_art_png<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+    Query(params): Query<ExportRequest>,
+) -> Result<Response, WebError> {
+    let ascii_art_id = parse_id(&id, "ASCII art")?;
+    export_ascii_art(&state, ascii_art_id, &params, ExportFormat::Png).await
+}
+
+/// Render stored ASCII art as SVG endpoint
+pub async fn render_ascii_art_svg<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+    Query(params): Query<ExportRequest>,
+) -> Result<Response, WebError> {
+    let ascii_art_id = parse_id(&id, "ASCII art")?;
+    export_ascii_art(&state, ascii_art_id, &params, ExportFormat::Svg).await
+}
+
+/// Render stored ASCII art as HTML endpoint; `embed=true` returns only the `<pre>` snippet
+pub async fn render_ascii_art_html<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+    Query(params): Query<ExportRequest>,
+) -> Result<Response, WebError> {
+    let ascii_art_id = parse_id(&id, "ASCII art")?;
+    let format = if params.embed.unwrap_or(false) {
+        ExportFormat::HtmlSnippet
+    } else {
+        ExportFormat::Html
+    };
+    export_ascii_art(&state, ascii_art_id, &params, format).await
+}
+
+/// Export stored ASCII art in the given format
+async fn export_ascii_art<IR: ImageRepository, AR: AsciiArtRepository>(
+    state: &AppState<IR, AR>,
+    ascii_art_id: Uuid,
+    params: &ExportRequest,
+    format: ExportFormat,
+) -> Result<Response, WebError> {
+    let parse_color = |value: Option<&str>, default: [u8; 3]| match value {
+        Some(value) => parse_hex_color(value).ok_or_else(|| {
+            WebError::BadRequest(format!("Invalid color '{}'. Use hex, e.g. ff8800", value))
+        }),
+        None => Ok(default),
+    };
+
+    let defaults = ExportOptions::default();
+    let options = ExportOptions {
+        foreground: parse_color(params.fg.as_deref(), defaults.foreground)?,
+        background: parse_color(params.bg.as_deref(), defaults.background)?,
+        cell_colors: params.colors.unwrap_or(defaults.cell_colors),
+        padding: params.padding.unwrap_or(defaults.padding),
+        font_size: params.font_size.unwrap_or(defaults.font_size),
+    };
+
+    let response = state
+        .export_use_case
+        .execute(ExportAsciiArtRequest {
+            ascii_art_id,
+            format,
+            options,
+        })
+        .await
+        .map_err(export_error)?;
+
+    Ok(export_response(
+        response,
+        Some(ascii_art_id),
+        format,
+        params.download.unwrap_or(false),
+    ))
+}
+
+/// Map export failures to their status
+fn export_error(e: ExportAsciiArtError) -> WebError {
+    match e {
+        ExportAsciiArtError::NotFound => WebError::NotFound(e.to_string()),
+        ExportAsciiArtError::InvalidOptions => WebError::BadRequest(format!(
+            "Invalid export options. Font size must be {}-{} and padding at most {}",
+            ExportOptions::FONT_SIZE_RANGE.start(),
+            ExportOptions::FONT_SIZE_RANGE.end(),
+            ExportOptions::MAX_PADDING
+        )),
+        ExportAsciiArtError::RenderFailed(RenderError::TooLarge { .. }) => {
+            WebError::UnprocessableEntity(format!("{}. Use a smaller font size or padding", e))
+        }
+        ExportAsciiArtError::RenderFailed(RenderError::Encode(_))
+        | ExportAsciiArtError::TaskFailed(_)
+        | ExportAsciiArtError::Repository(_) => WebError::InternalServerError(e.to_string()),
+    }
+}
+
+/// Respond with exported art, as a download when asked
+fn export_response(
+    response: ExportAsciiArtResponse,
+    ascii_art_id: Option<Uuid>,
+    format: ExportFormat,
+    download: bool,
+) -> Response {
+    let mut http_response = (
+        [(header::CONTENT_TYPE, response.content_type)],
+        response.data,
+    )
+        .into_response();
+    if download {
+        attach(&mut http_response, ascii_art_id, format.extension());
+    }
+    http_response
+}
+
+/// Parse an ID path or query parameter
+pub(crate) fn parse_id(id: &str, what: &str) -> Result<Uuid, WebError> {
+    Uuid::parse_str(id).map_err(|_| WebError::BadRequest(format!("Invalid {} ID format", what)))
+}
+
 /// Health check endpoint
 pub async fn health_check() -> Result<Json<serde_json::Value>, WebError> {
     Ok(Json(serde_json::json!({
@@ -142,3 +954,116 @@ pub async fn health_check() -> Result<Json<serde_json::Value>, WebError> {
         "version": env!("CARGO_PKG_VERSION")
     })))
 }
+
+/// Storage usage and eviction counters endpoint
+pub async fn storage_stats<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+) -> Json<StorageResponse> {
+    let stats = state.storage_stats_use_case.execute();
+    Json(StorageResponse {
+        images: stats.images,
+        ascii_art: stats.ascii_art,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::value_objects::ImageLimits;
+
+    #[test]
+    fn upload_errors_map_to_their_status() {
+        let status = |e| upload_error(e).into_response().status();
+        assert_eq!(
+            status(UploadImageError::InvalidImageData),
+            StatusCode::BAD_REQUEST
+        );
+        assert_eq!(
+            status(UploadImageError::ImageTooLarge { max_size: 1 }),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            status(UploadImageError::DimensionsTooLarge {
+                width: 2,
+                height: 2,
+                limits: ImageLimits::default(),
+            }),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(UploadImageError::UnsupportedFormat),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE
+        );
+        assert_eq!(
+            status(UploadImageError::Repository("disk full".into())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    #[test]
+    fn conversion_limits_are_client_errors() {
+        let status = |e| convert_error(e).into_response().status();
+        assert_eq!(
+            status(ConvertImageError::LimitsExceeded("too tall".to_string())),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(
+            status(ConvertImageError::ConversionFailed("broken".to_string())),
+            StatusCode::INTERNAL_SERVER_ERROR
+        );
+    }
+
+    /// Response format negotiated for an `Accept` header, or the status refusing it
+    fn negotiate(accept: &str) -> Result<Option<ExportFormat>, StatusCode> {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_str(accept).unwrap());
+        response_format(None, &headers).map_err(|e| e.into_response().status())
+    }
+
+    #[test]
+    fn accept_header_picks_the_most_preferred_offered_type() {
+        assert_eq!(negotiate(""), Ok(None));
+        assert_eq!(negotiate("*/*"), Ok(None));
+        assert_eq!(negotiate("image/svg+xml"), Ok(Some(ExportFormat::Svg)));
+        assert_eq!(negotiate("IMAGE/PNG"), Ok(Some(ExportFormat::Png)));
+        assert_eq!(negotiate("image/*"), Ok(Some(ExportFormat::Png)));
+        assert_eq!(
+            negotiate("text/*;q=0.5, image/*"),
+            Ok(Some(ExportFormat::Png))
+        );
+
+        // Higher quality wins, equal quality keeps the client's order
+        assert_eq!(
+            negotiate("text/html;q=0.4, text/x-ansi; q=0.9, application/json;q=0.8"),
+            Ok(Some(ExportFormat::Ansi))
+        );
+        assert_eq!(
+            negotiate("image/png, text/plain"),
+            Ok(Some(ExportFormat::Png))
+        );
+
+        // Refused and unknown types are skipped
+        assert_eq!(negotiate("image/png;q=0, */*;q=0.1"), Ok(None));
+        assert_eq!(
+            negotiate("video/mp4, text/plain;q=0.2"),
+            Ok(Some(ExportFormat::Text))
+        );
+        assert_eq!(negotiate("video/mp4"), Err(StatusCode::NOT_ACCEPTABLE));
+        assert_eq!(negotiate("image/png;q=0"), Err(StatusCode::NOT_ACCEPTABLE));
+    }
+
+    #[test]
+    fn format_parameter_overrides_the_accept_header() {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static("image/png"));
+        assert!(matches!(response_format(Some("JSON"), &headers), Ok(None)));
+        assert!(matches!(
+            response_format(Some("svg"), &headers),
+            Ok(Some(ExportFormat::Svg))
+        ));
+        assert!(matches!(
+            response_format(Some("gif"), &headers),
+            Err(WebError::BadRequest(_))
+        ));
+    }
+}
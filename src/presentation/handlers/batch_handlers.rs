@@ -0,0 +1,301 @@
+use crate::{
+    application::{
+        services::ansi::strip_ansi,
+        use_cases::{
+            batch_convert::{BatchConvertError, BatchConvertRequest, BatchItemResult},
+            upload_image::UploadImageRequest,
+        },
+    },
+    domain::repositories::{AsciiArtRepository, ImageRepository},
+    infrastructure::web::{archive, error::WebError, extractors::BatchUpload},
+    presentation::handlers::ascii_handlers::{
+        conversion_config, parse_form, AnimationResponse, AppState, ConvertToAsciiRequest,
+        UploadAndConvertForm,
+    },
+};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap},
+    response::{IntoResponse, Json, Response},
+};
+use serde::Serialize;
+use std::collections::HashSet;
+
+/// Name of the manifest inside a ZIP response
+const MANIFEST_NAME: &str = "manifest.json";
+
+/// Outcome of a batch conversion
+#[derive(Debug, Serialize)]
+pub struct BatchResponse {
+    pub total: usize,
+    pub succeeded: usize,
+    pub failed: usize,
+    /// One entry per file, in upload order
+    pub results: Vec<BatchItemResponse>,
+}
+
+/// Result for one file of a batch: the conversion or the reason it failed
+#[derive(Debug, Serialize)]
+pub struct BatchItemResponse {
+    pub filename: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ascii_art_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub height: Option<u32>,
+    /// JSON responses only
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ascii_art: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub animation: Option<AnimationResponse>,
+    /// ZIP responses only: the files holding this conversion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outputs: Option<Vec<String>>,
+    /// An identical earlier conversion was reused
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cached: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Upload and convert a batch of images with the same parameters. The multipart form
+/// carries any number of image files and ZIP archives of images, plus the convert
+/// endpoint's parameters as fields. Responds with a JSON manifest, or with a ZIP of
+/// `.txt` files and the manifest for `format=zip` or `Accept: application/zip`.
+pub async fn batch_convert<IR, AR>(
+    State(state): State<AppState<IR, AR>>,
+    headers: HeaderMap,
+    upload: BatchUpload,
+) -> Result<Response, WebError>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
+    let params: ConvertToAsciiRequest = parse_form(&upload.fields)?;
+    let options: UploadAndConvertForm = parse_form(&upload.fields)?;
+    let config = conversion_config(&params)?;
+    let zip = wants_zip(params.format.as_deref(), &headers)?;
+
+    let files = upload
+        .files
+        .into_iter()
+        .map(|file| UploadImageRequest {
+            filename: file.filename,
+            content_type: file.content_type,
+            data: file.data,
+        })
+        .collect();
+
+    let response = state
+        .batch_convert_use_case
+        .execute(BatchConvertRequest {
+            files,
+            config,
+            persist_images: options.persist.unwrap_or(true),
+        })
+        .await
+        .map_err(|e| match e {
+            BatchConvertError::Empty
+            | BatchConvertError::TooManyFiles { .. }
+            | BatchConvertError::InvalidConfig => WebError::BadRequest(e.to_string()),
+        })?;
+
+    if !zip {
+        let results = response.items.into_iter().map(item_response).collect();
+        return Ok(Json(batch_response(results)).into_response());
+    }
+
+    let mut used_names = HashSet::from([MANIFEST_NAME.to_string()]);
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut results = Vec::new();
+    for item in response.items {
+        let mut result = item_response(item);
+        if let Some(content) = result.ascii_art.take() {
+            let stem = output_stem(&result.filename);
+            let named: Vec<(String, String)> = match result.animation.take() {
+                None => vec![(unique_name(&mut used_names, &stem, ""), content)],
+                Some(animation) => animation
+                    .frames
+                    .into_iter()
+                    .enumerate()
+                    .map(|(index, frame)| {
+                        let suffix = format!(".{:04}", index + 1);
+                        (unique_name(&mut used_names, &stem, &suffix), frame)
+                    })
+                    .collect(),
+            };
+            let mut outputs = Vec::with_capacity(named.len());
+            for (name, content) in named {
+                outputs.push(name.clone());
+                // Plain text, like the text export
+                files.push((name, strip_ansi(&content)));
+            }
+            result.outputs = Some(outputs);
+        }
+        results.push(result);
+    }
+
+    let manifest = serde_json::to_string_pretty(&batch_response(results))
+        .map_err(|e| WebError::InternalServerError(e.to_string()))?;
+    let archive = archive::write_archive(
+        files
+            .iter()
+            .map(|(name, content)| (name.as_str(), content.as_bytes()))
+            .chain([(MANIFEST_NAME, manifest.as_bytes())]),
+    )
+    .map_err(|e| WebError::InternalServerError(e.to_string()))?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            (
+                header::CONTENT_DISPOSITION,
+                "attachment; filename=\"ascii-art.zip\"",
+            ),
+            (header::VARY, "accept"),
+        ],
+        archive,
+    )
+        .into_response())
+}
+
+/// Whether to respond with a ZIP, from the `format` parameter or the `Accept` header
+fn wants_zip(format: Option<&str>, headers: &HeaderMap) -> Result<bool, WebError> {
+    match format.map(str::to_lowercase).as_deref() {
+        Some("zip") => Ok(true),
+        Some("json") => Ok(false),
+        Some(_) => Err(WebError::BadRequest(
+            "Invalid format. Use 'json' or 'zip'".to_string(),
+        )),
+        None => Ok(headers
+            .get(header::ACCEPT)
+            .and_then(|value| value.to_str().ok())
+            .is_some_and(|accept| {
+                accept.split(',').any(|range| {
+                    let mut parts = range.split(';');
+                    parts.next().unwrap_or("").trim() == "application/zip"
+                        && !parts.any(|param| {
+                            param
+                                .trim()
+                                .strip_prefix("q=")
+                                .and_then(|q| q.trim().parse::<f32>().ok())
+                                .is_some_and(|q| q <= 0.0)
+                        })
+                })
+            })),
+    }
+}
+
+fn item_response(item: BatchItemResult) -> BatchItemResponse {
+    let mut response = BatchItemResponse {
+        filename: item.filename,
+        image_id: None,
+        ascii_art_id: None,
+        width: None,
+        height: None,
+        ascii_art: None,
+        animation: None,
+        outputs: None,
+        cached: None,
+        error: None,
+    };
+    match item.result {
+        Ok(converted) => {
+            let conversion = converted.conversion;
+            response.image_id = converted.image_id.map(|id| id.to_string());
+            response.ascii_art_id = conversion.ascii_art_id.map(|id| id.to_string());
+            response.width = Some(conversion.width);
+            response.height = Some(conversion.height);
+            response.ascii_art = Some(conversion.content);
+            response.animation = AnimationResponse::from_frames(conversion.frames);
+            response.cached = Some(conversion.cached);
+        }
+        Err(e) => response.error = Some(e.to_string()),
+    }
+    response
+}
+
+fn batch_response(results: Vec<BatchItemResponse>) -> BatchResponse {
+    let failed = results.iter().filter(|item| item.error.is_some()).count();
+    BatchResponse {
+        total: results.len(),
+        succeeded: results.len() - failed,
+        failed,
+        results,
+    }
+}
+
+/// Path of an upload without its extension, keeping only the safe parts of any folders:
+/// no roots, drive prefixes or parent references
+fn output_stem(filename: &str) -> String {
+    let path: Vec<&str> = filename
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != "." && *part != ".." && !part.contains(':'))
+        .collect();
+    let path = path.join("/");
+    let stem = match path.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() && !stem.ends_with('/') => stem,
+        _ => path.as_str(),
+    };
+    if stem.is_empty() {
+        "image".to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// `<stem><suffix>.txt`, numbered when another file of the batch already took it
+fn unique_name(used_names: &mut HashSet<String>, stem: &str, suffix: &str) -> String {
+    let mut name = format!("{}{}.txt", stem, suffix);
+    let mut copy = 1;
+    while !used_names.insert(name.clone()) {
+        copy += 1;
+        name = format!("{}-{}{}.txt", stem, copy, suffix);
+    }
+    name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::HeaderValue;
+
+    #[test]
+    fn output_names_keep_safe_folders_and_never_repeat() {
+        assert_eq!(output_stem("cats/tom.png"), "cats/tom");
+        assert_eq!(output_stem("../../etc/passwd"), "etc/passwd");
+        assert_eq!(output_stem("C:\\pics\\.\\a.b.jpg"), "pics/a.b");
+        assert_eq!(output_stem("C:a.png"), "image");
+        assert_eq!(output_stem(".hidden"), ".hidden");
+        assert_eq!(output_stem("dir/.png"), "dir/.png");
+        assert_eq!(output_stem("/.."), "image");
+
+        let mut used_names = HashSet::new();
+        let names: Vec<String> = [("a", ""), ("a", ""), ("a", ".0001"), ("a", "")]
+            .iter()
+            .map(|(stem, suffix)| unique_name(&mut used_names, stem, suffix))
+            .collect();
+        assert_eq!(names, ["a.txt", "a-2.txt", "a.0001.txt", "a-3.txt"]);
+    }
+
+    #[test]
+    fn zip_is_chosen_by_format_or_an_acceptable_media_range() {
+        let accept = |value: &'static str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+            headers
+        };
+
+        assert!(wants_zip(Some("ZIP"), &accept("application/json")).unwrap());
+        assert!(!wants_zip(Some("json"), &accept("application/zip")).unwrap());
+        assert!(wants_zip(Some("tar"), &HeaderMap::new()).is_err());
+
+        assert!(!wants_zip(None, &HeaderMap::new()).unwrap());
+        assert!(wants_zip(None, &accept("text/html, application/zip;q=0.9")).unwrap());
+        assert!(!wants_zip(None, &accept("application/zip;q=0, */*")).unwrap());
+        assert!(!wants_zip(None, &accept("application/zipper")).unwrap());
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{
+    application::use_cases::{
+        cancel_job::CancelJobError, convert_image_to_ascii::ConvertImageRequest,
+        get_job::GetJobError, submit_conversion_job::SubmitConversionJobError,
+    },
+    domain::{
+        entities::ConversionJob,
+        repositories::{AsciiArtRepository, ImageRepository},
+    },
+    infrastructure::web::error::WebError,
+    presentation::handlers::ascii_handlers::{
+        conversion_config, parse_id, AppState, ConvertToAsciiRequest,
+    },
+};
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use serde::{Deserialize, Serialize};
+
+/// Image to convert in the background; conversion parameters are those of the convert
+/// endpoint
+#[derive(Debug, Deserialize)]
+pub struct SubmitJobRequest {
+    pub image_id: String,
+}
+
+/// Response describing a background conversion job
+#[derive(Debug, Serialize)]
+pub struct JobResponse {
+    pub job_id: String,
+    pub image_id: String,
+    /// queued, running, done, failed or cancelled
+    pub status: String,
+    /// Fraction of the work done, from 0.0 to 1.0
+    pub progress: f32,
+    /// Stored result, once done
+    pub ascii_art_id: Option<String>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<ConversionJob> for JobResponse {
+    fn from(job: ConversionJob) -> Self {
+        Self {
+            job_id: job.id.to_string(),
+            image_id: job.image_id.to_string(),
+            status: job.status.to_string(),
+            progress: job.progress,
+            ascii_art_id: job.ascii_art_id.map(|id| id.to_string()),
+            error: job.error,
+            created_at: job.created_at,
+            updated_at: job.updated_at,
+        }
+    }
+}
+
+/// Submit a background conversion endpoint; responds with `202 Accepted` and the job
+pub async fn submit_conversion_job<IR, AR>(
+    State(state): State<AppState<IR, AR>>,
+    Query(job): Query<SubmitJobRequest>,
+    Query(params): Query<ConvertToAsciiRequest>,
+) -> Result<Response, WebError>
+where
+    IR: ImageRepository + 'static,
+    AR: AsciiArtRepository + 'static,
+{
+    let image_id = parse_id(&job.image_id, "image")?;
+    let config = conversion_config(&params)?;
+
+    let job = state
+        .submit_job_use_case
+        .execute(ConvertImageRequest { image_id, config })
+        .await
+        .map_err(|e| match e {
+            SubmitConversionJobError::ImageNotFound => WebError::NotFound(e.to_string()),
+            SubmitConversionJobError::InvalidConfig => WebError::BadRequest(e.to_string()),
+            SubmitConversionJobError::QueueFull(_) => WebError::ServiceUnavailable(e.to_string()),
+            SubmitConversionJobError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    let location = format!("/api/jobs/{}", job.id);
+    Ok((
+        StatusCode::ACCEPTED,
+        [(header::LOCATION, location)],
+        Json(JobResponse::from(job)),
+    )
+        .into_response())
+}
+
+/// Poll a background conversion job endpoint
+pub async fn get_job<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, WebError> {
+    let id = parse_id(&id, "job")?;
+
+    let job = state.get_job_use_case.execute(id).map_err(|e| match e {
+        GetJobError::NotFound => WebError::NotFound(e.to_string()),
+    })?;
+
+    Ok(Json(JobResponse::from(job)))
+}
+
+/// Cancel a background conversion job endpoint
+pub async fn cancel_job<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(id): Path<String>,
+) -> Result<Json<JobResponse>, WebError> {
+    let id = parse_id(&id, "job")?;
+
+    let job = state.cancel_job_use_case.execute(id).map_err(|e| match e {
+        CancelJobError::NotFound => WebError::NotFound(e.to_string()),
+    })?;
+
+    Ok(Json(JobResponse::from(job)))
+}
@@ -1,3 +1,9 @@
 pub mod ascii_handlers;
+pub mod batch_handlers;
+pub mod job_handlers;
+pub mod preview_handlers;
 
 pub use ascii_handlers::*;
+pub use batch_handlers::*;
+pub use job_handlers::*;
+pub use preview_handlers::*;
@@ -0,0 +1,337 @@
+use crate::{
+    application::{
+        services::AsciiRendering,
+        use_cases::{
+            get_image::GetImageError,
+            preview_conversion::{PreviewConversionError, PreviewConversionUseCase},
+        },
+    },
+    domain::{
+        entities::ImageData,
+        repositories::{AsciiArtRepository, ImageRepository},
+    },
+    infrastructure::web::error::WebError,
+    presentation::handlers::ascii_handlers::{
+        conversion_config, parse_id, AnimationResponse, AppState, ConvertToAsciiRequest,
+    },
+};
+use async_trait::async_trait;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Path, State,
+    },
+    response::Response,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    task::{JoinError, JoinHandle},
+    time::{sleep_until, Instant},
+};
+
+/// Quiet period after the last parameter change before a preview is rendered
+const PREVIEW_DEBOUNCE: Duration = Duration::from_millis(150);
+
+/// Message sent to live preview clients. `revision` counts the parameter messages the
+/// server had received when it started the render, so clients can tell stale previews.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum PreviewMessage {
+    Preview {
+        revision: u64,
+        ascii_art: String,
+        width: u32,
+        height: u32,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        animation: Option<AnimationResponse>,
+    },
+    Error {
+        revision: u64,
+        error: String,
+    },
+}
+
+/// Two-way text channel to a live preview client
+#[async_trait]
+trait PreviewChannel: Send {
+    /// Next text message, or `None` once the client has gone away
+    async fn recv(&mut self) -> Option<String>;
+
+    async fn send(&mut self, message: &PreviewMessage) -> Result<(), axum::Error>;
+}
+
+#[async_trait]
+impl PreviewChannel for WebSocket {
+    async fn recv(&mut self) -> Option<String> {
+        loop {
+            match WebSocket::recv(self).await {
+                Some(Ok(Message::Text(text))) => return Some(text),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return None,
+                Some(Ok(_)) => continue,
+            }
+        }
+    }
+
+    async fn send(&mut self, message: &PreviewMessage) -> Result<(), axum::Error> {
+        let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+        WebSocket::send(self, Message::Text(text)).await
+    }
+}
+
+/// Render in progress for a live preview connection
+struct PreviewRender {
+    revision: u64,
+    cancelled: Arc<AtomicBool>,
+    task: JoinHandle<Result<AsciiRendering, PreviewConversionError>>,
+}
+
+/// Live preview endpoint. Clients send JSON objects with any of the convert endpoint's
+/// parameters, which are merged into the current ones (`null` restores a default), and
+/// receive a fresh preview once the changes settle.
+pub async fn preview_ascii_art<IR: ImageRepository, AR: AsciiArtRepository>(
+    State(state): State<AppState<IR, AR>>,
+    Path(image_id): Path<String>,
+    ws: WebSocketUpgrade,
+) -> Result<Response, WebError> {
+    let image_id = parse_id(&image_id, "image")?;
+
+    // Load the image once for the whole session
+    let image = state
+        .get_image_use_case
+        .execute(image_id)
+        .await
+        .map_err(|e| match e {
+            GetImageError::NotFound => WebError::NotFound(e.to_string()),
+            GetImageError::Repository(_) => WebError::InternalServerError(e.to_string()),
+        })?;
+
+    let preview_use_case = Arc::clone(&state.preview_use_case);
+    Ok(ws.on_upgrade(move |socket| run_preview(socket, preview_use_case, Arc::new(image))))
+}
+
+/// Serve a live preview connection. At most one render runs at a time: a parameter
+/// change cancels the render in progress, and the next one only starts once it has
+/// stopped and the changes have settled.
+async fn run_preview(
+    mut channel: impl PreviewChannel,
+    preview_use_case: Arc<PreviewConversionUseCase>,
+    image: Arc<ImageData>,
+) {
+    let mut params = Map::new();
+    let mut revision = 0;
+    // Render the defaults right away
+    let mut deadline = Some(Instant::now());
+    let mut in_flight: Option<PreviewRender> = None;
+
+    loop {
+        let reply = tokio::select! {
+            text = channel.recv() => {
+                let Some(text) = text else {
+                    break;
+                };
+
+                revision += 1;
+                match merge_params(&mut params, &text) {
+                    Ok(()) => {
+                        // The render in progress is stale now
+                        if let Some(render) = &in_flight {
+                            render.cancelled.store(true, Ordering::Relaxed);
+                        }
+                        deadline = Some(Instant::now() + PREVIEW_DEBOUNCE);
+                        None
+                    }
+                    Err(error) => Some(PreviewMessage::Error { revision, error }),
+                }
+            }
+            _ = sleep_until(deadline.unwrap_or_else(Instant::now)),
+                if deadline.is_some() && in_flight.is_none() =>
+            {
+                deadline = None;
+                match start_render(&preview_use_case, &image, &params, revision) {
+                    Ok(render) => {
+                        in_flight = Some(render);
+                        None
+                    }
+                    Err(error) => Some(PreviewMessage::Error { revision, error }),
+                }
+            }
+            result = finished(&mut in_flight) => {
+                let render = in_flight.take();
+                let revision = render.as_ref().map_or(revision, |render| render.revision);
+                let stale = render.is_some_and(|render| render.cancelled.load(Ordering::Relaxed));
+                match result {
+                    _ if stale => None,
+                    Ok(Ok(rendering)) => Some(PreviewMessage::Preview {
+                        revision,
+                        ascii_art: rendering.content,
+                        width: rendering.columns,
+                        height: rendering.rows,
+                        animation: AnimationResponse::from_frames(rendering.frames),
+                    }),
+                    Ok(Err(PreviewConversionError::Cancelled)) => None,
+                    Ok(Err(e)) => Some(PreviewMessage::Error { revision, error: e.to_string() }),
+                    Err(e) => Some(PreviewMessage::Error { revision, error: e.to_string() }),
+                }
+            }
+        };
+
+        if let Some(reply) = reply {
+            if channel.send(&reply).await.is_err() {
+                break;
+            }
+        }
+    }
+
+    if let Some(render) = in_flight {
+        render.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Apply a client's parameter changes
+fn merge_params(params: &mut Map<String, Value>, text: &str) -> Result<(), String> {
+    let changes: Map<String, Value> =
+        serde_json::from_str(text).map_err(|e| format!("Invalid parameters: {}", e))?;
+    for (name, value) in changes {
+        if value.is_null() {
+            params.remove(&name);
+        } else {
+            params.insert(name, value);
+        }
+    }
+    Ok(())
+}
+
+/// Start rendering a preview with the current parameters
+fn start_render(
+    preview_use_case: &Arc<PreviewConversionUseCase>,
+    image: &Arc<ImageData>,
+    params: &Map<String, Value>,
+    revision: u64,
+) -> Result<PreviewRender, String> {
+    let request: ConvertToAsciiRequest = serde_json::from_value(Value::Object(params.clone()))
+        .map_err(|e| format!("Invalid parameters: {}", e))?;
+    let config = conversion_config(&request).map_err(|e| match e {
+        WebError::BadRequest(message) => message,
+        e => e.to_string(),
+    })?;
+
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let task = tokio::spawn({
+        let preview_use_case = Arc::clone(preview_use_case);
+        let image = Arc::clone(image);
+        let cancelled = Arc::clone(&cancelled);
+        async move { preview_use_case.execute(image, config, cancelled).await }
+    });
+
+    Ok(PreviewRender {
+        revision,
+        cancelled,
+        task,
+    })
+}
+
+/// Wait for the render in progress, or forever if there is none
+async fn finished(
+    in_flight: &mut Option<PreviewRender>,
+) -> Result<Result<AsciiRendering, PreviewConversionError>, JoinError> {
+    match in_flight {
+        Some(render) => (&mut render.task).await,
+        None => std::future::pending().await,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::application::services::AsciiConversionService;
+    use image::{codecs::png::PngEncoder, ExtendedColorType, ImageEncoder};
+    use serde_json::json;
+    use tokio::sync::mpsc;
+
+    /// Client feeding parameter messages and collecting the replies as JSON
+    struct FakeClient {
+        incoming: mpsc::UnboundedReceiver<String>,
+        outgoing: mpsc::UnboundedSender<Value>,
+    }
+
+    #[async_trait]
+    impl PreviewChannel for FakeClient {
+        async fn recv(&mut self) -> Option<String> {
+            self.incoming.recv().await
+        }
+
+        async fn send(&mut self, message: &PreviewMessage) -> Result<(), axum::Error> {
+            let message = serde_json::to_value(message).map_err(axum::Error::new)?;
+            self.outgoing.send(message).map_err(axum::Error::new)
+        }
+    }
+
+    #[test]
+    fn params_merge_and_null_restores_a_default() {
+        let mut params = Map::new();
+        merge_params(&mut params, r#"{"width": 40, "mode": "braille"}"#).unwrap();
+        merge_params(&mut params, r#"{"width": null, "invert": true}"#).unwrap();
+        assert_eq!(
+            Value::Object(params.clone()),
+            json!({"mode": "braille", "invert": true})
+        );
+
+        assert!(merge_params(&mut params, "[1, 2]").is_err());
+        assert_eq!(params.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn changes_are_debounced_into_one_preview_of_the_latest_revision() {
+        let mut png = Vec::new();
+        PngEncoder::new(&mut png)
+            .write_image(&[128; 3 * 32 * 32], 32, 32, ExtendedColorType::Rgb8)
+            .unwrap();
+        let image = ImageData::new("a.png".into(), "image/png".into(), png, 32, 32);
+        let preview_use_case = Arc::new(PreviewConversionUseCase::new(Arc::new(
+            AsciiConversionService::new(),
+        )));
+
+        let (client, incoming) = mpsc::unbounded_channel();
+        let (outgoing, mut replies) = mpsc::unbounded_channel();
+        for message in [r#"{"width": 8}"#, "not json", r#"{"width": 12}"#] {
+            client.send(message.to_string()).unwrap();
+        }
+        let session = tokio::spawn(run_preview(
+            FakeClient { incoming, outgoing },
+            preview_use_case,
+            Arc::new(image),
+        ));
+
+        let mut received = Vec::new();
+        let latest = loop {
+            let reply = tokio::time::timeout(Duration::from_secs(10), replies.recv())
+                .await
+                .unwrap()
+                .unwrap();
+            if reply["type"] == "preview" && reply["revision"] == 3 {
+                break reply;
+            }
+            received.push(reply);
+        };
+        assert_eq!(latest["width"], 12);
+        let (errors, previews): (Vec<Value>, Vec<Value>) = received
+            .into_iter()
+            .partition(|reply| reply["type"] == "error");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0]["revision"], 2);
+        // Only the render of the defaults may have finished before the changes arrived
+        assert!(previews.iter().all(|reply| reply["revision"] == 0));
+
+        drop(client);
+        session.await.unwrap();
+        assert!(replies.recv().await.is_none());
+    }
+}